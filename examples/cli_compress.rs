@@ -3,7 +3,7 @@
 
 #[tokio::main]
 async fn main() {
-    #[cfg(features = "deflate")]
+    #[cfg(feature = "deflate")]
     if let Err(err) = inner::run().await {
         eprintln!("Error: {}", err);
         eprintln!("Usage: cli_compress <input file or directory> <output ZIP file name>");
@@ -11,7 +11,7 @@ async fn main() {
     }
 }
 
-#[cfg(features = "deflate")]
+#[cfg(feature = "deflate")]
 mod inner {
 
     use async_zip::base::write::ZipFileWriter;
@@ -20,10 +20,11 @@ mod inner {
     use std::path::{Path, PathBuf};
 
     use anyhow::{anyhow, bail, Result};
-    use futures_util::io::AsyncReadExt;
     use tokio::fs::File;
+    use tokio::io::AsyncReadExt;
+    use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
 
-    async fn run() -> Result<()> {
+    pub(super) async fn run() -> Result<()> {
         let mut args = std::env::args().skip(1);
 
         let input_str = args.next().ok_or(anyhow!("No input file or directory specified."))?;
@@ -42,7 +43,7 @@ mod inner {
             bail!("The input file or directory specified doesn't exist.");
         }
 
-        let mut output_writer = ZipFileWriter::new(File::create(output_path).await?);
+        let mut output_writer = ZipFileWriter::new(File::create(output_path).await?.compat_write());
 
         if input_path.is_dir() {
             handle_directory(input_path, &mut output_writer).await?;
@@ -56,14 +57,14 @@ mod inner {
         Ok(())
     }
 
-    async fn handle_singular(input_path: &Path, writer: &mut ZipFileWriter<File>) -> Result<()> {
+    async fn handle_singular(input_path: &Path, writer: &mut ZipFileWriter<Compat<File>>) -> Result<()> {
         let filename = input_path.file_name().ok_or(anyhow!("Input path terminates in '...'."))?;
         let filename = filename.to_str().ok_or(anyhow!("Input path not valid UTF-8."))?;
 
         write_entry(filename, input_path, writer).await
     }
 
-    async fn handle_directory(input_path: &Path, writer: &mut ZipFileWriter<File>) -> Result<()> {
+    async fn handle_directory(input_path: &Path, writer: &mut ZipFileWriter<Compat<File>>) -> Result<()> {
         let entries = walk_dir(input_path.into()).await?;
         let input_dir_str = input_path.as_os_str().to_str().ok_or(anyhow!("Input path not valid UTF-8."))?;
 
@@ -82,7 +83,7 @@ mod inner {
         Ok(())
     }
 
-    async fn write_entry(filename: &str, input_path: &Path, writer: &mut ZipFileWriter<File>) -> Result<()> {
+    async fn write_entry(filename: &str, input_path: &Path, writer: &mut ZipFileWriter<Compat<File>>) -> Result<()> {
         let mut input_file = File::open(input_path).await?;
         let input_file_size = input_file.metadata().await?.len() as usize;
 
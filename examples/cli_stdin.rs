@@ -0,0 +1,46 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! Demonstrates how to compress stdin into a single-entry ZIP file written to stdout.
+//!
+//! As stdout isn't seekable and the size of stdin isn't known up front, the entry is written using a data descriptor.
+//!
+//! ```sh
+//! cat foo.txt | cargo run --example cli_stdin --features deflate -- foo.txt > foo.zip
+//! ```
+
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "deflate")]
+    if let Err(err) = inner::run().await {
+        eprintln!("Error: {}", err);
+        eprintln!("Usage: cli_stdin <entry name> < <input> > <output ZIP file>");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(feature = "deflate")]
+mod inner {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    use anyhow::{anyhow, Result};
+    use futures_util::io::AsyncWriteExt;
+    use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+    pub(super) async fn run() -> Result<()> {
+        let name = std::env::args().nth(1).ok_or(anyhow!("No entry name specified."))?;
+
+        let mut writer = ZipFileWriter::new(tokio::io::stdout().compat_write());
+        let builder = ZipEntryBuilder::new(name.into(), Compression::Deflate);
+
+        let mut entry_writer = writer.write_entry_stream(builder).await?;
+        futures_util::io::copy(&mut tokio::io::stdin().compat(), &mut entry_writer).await?;
+        entry_writer.close().await?;
+
+        let mut stdout = writer.close().await?;
+        stdout.flush().await?;
+
+        Ok(())
+    }
+}
@@ -1,37 +1,42 @@
 // Copyright (c) 2022 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
+use crate::error::{Result, ZipError};
 use crate::spec::Compression;
 
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-#[cfg(any(
-    feature = "deflate",
-    feature = "bzip2",
-    feature = "zstd",
-    feature = "lzma",
-    feature = "xz",
-    feature = "deflate64"
-))]
+#[cfg(any(feature = "bzip2", feature = "zstd", feature = "lzma", feature = "xz", feature = "deflate64"))]
 use async_compression::futures::bufread;
 use futures_util::io::{AsyncBufRead, AsyncRead};
 use pin_project::pin_project;
 
+#[cfg(feature = "deflate")]
+use crate::base::read::io::deflate::DeflateDecoder;
+#[cfg(feature = "deflate-dictionary")]
+use crate::base::read::io::deflate::DictionaryDeflateDecoder;
+#[cfg(feature = "lzma")]
+use crate::base::read::io::lzma::LzmaHeaderReader;
+#[cfg(feature = "zstd")]
+use crate::base::read::io::zstd::ZstdWindowReader;
+
 /// A wrapping reader which holds concrete types for all respective compression method readers.
 #[pin_project(project = CompressedReaderProj)]
 pub(crate) enum CompressedReader<R> {
     Stored(#[pin] R),
     #[cfg(feature = "deflate")]
-    Deflate(#[pin] bufread::DeflateDecoder<R>),
+    Deflate(#[pin] DeflateDecoder<R>),
+    #[cfg(feature = "deflate-dictionary")]
+    DeflateDictionary(#[pin] DictionaryDeflateDecoder<R>),
     #[cfg(feature = "deflate64")]
     Deflate64(#[pin] bufread::Deflate64Decoder<R>),
     #[cfg(feature = "bzip2")]
     Bz(#[pin] bufread::BzDecoder<R>),
     #[cfg(feature = "lzma")]
-    Lzma(#[pin] bufread::LzmaDecoder<R>),
+    Lzma(#[pin] Box<bufread::LzmaDecoder<LzmaHeaderReader<R>>>),
     #[cfg(feature = "zstd")]
-    Zstd(#[pin] bufread::ZstdDecoder<R>),
+    Zstd(#[pin] bufread::ZstdDecoder<ZstdWindowReader<R>>),
     #[cfg(feature = "xz")]
     Xz(#[pin] bufread::XzDecoder<R>),
 }
@@ -41,21 +46,100 @@ where
     R: AsyncBufRead + Unpin,
 {
     /// Constructs a new wrapping reader from a generic [`AsyncBufRead`] implementer.
-    pub(crate) fn new(reader: R, compression: Compression) -> Self {
-        match compression {
+    ///
+    /// The uncompressed size should be provided where it's known and the data isn't terminated by an end-of-stream
+    /// marker, as LZMA relies upon it to determine where its data ends.
+    #[cfg_attr(not(feature = "lzma"), allow(unused_variables))]
+    pub(crate) fn new(reader: R, compression: Compression, uncompressed_size: Option<u64>) -> Result<Self> {
+        Ok(match compression {
             Compression::Stored => CompressedReader::Stored(reader),
             #[cfg(feature = "deflate")]
-            Compression::Deflate => CompressedReader::Deflate(bufread::DeflateDecoder::new(reader)),
+            Compression::Deflate => CompressedReader::Deflate(DeflateDecoder::new(reader)),
             #[cfg(feature = "deflate64")]
             Compression::Deflate64 => CompressedReader::Deflate64(bufread::Deflate64Decoder::new(reader)),
             #[cfg(feature = "bzip2")]
             Compression::Bz => CompressedReader::Bz(bufread::BzDecoder::new(reader)),
             #[cfg(feature = "lzma")]
-            Compression::Lzma => CompressedReader::Lzma(bufread::LzmaDecoder::new(reader)),
+            Compression::Lzma => CompressedReader::Lzma(Box::new(bufread::LzmaDecoder::new(LzmaHeaderReader::new(
+                reader,
+                uncompressed_size,
+            )))),
             #[cfg(feature = "zstd")]
-            Compression::Zstd => CompressedReader::Zstd(bufread::ZstdDecoder::new(reader)),
+            Compression::Zstd => CompressedReader::Zstd(bufread::ZstdDecoder::new(ZstdWindowReader::new(reader, None))),
             #[cfg(feature = "xz")]
             Compression::Xz => CompressedReader::Xz(bufread::XzDecoder::new(reader)),
+            Compression::Unsupported(value) => return Err(ZipError::CompressionNotSupported(value)),
+        })
+    }
+
+    /// Replaces a Deflate decoder with one which uses the provided preset dictionary.
+    ///
+    /// This must be called before any data has been read.
+    #[cfg(feature = "deflate-dictionary")]
+    pub(crate) fn with_deflate_dictionary(self, dictionary: &[u8]) -> Self {
+        match self {
+            CompressedReader::Deflate(inner) => {
+                CompressedReader::DeflateDictionary(DictionaryDeflateDecoder::new(inner.into_inner(), dictionary))
+            }
+            other => other,
+        }
+    }
+
+    /// Returns a reference to the inner value.
+    pub(crate) fn get_ref(&self) -> &R {
+        match self {
+            CompressedReader::Stored(inner) => inner,
+            #[cfg(feature = "deflate")]
+            CompressedReader::Deflate(inner) => inner.get_ref(),
+            #[cfg(feature = "deflate-dictionary")]
+            CompressedReader::DeflateDictionary(inner) => inner.get_ref(),
+            #[cfg(feature = "deflate64")]
+            CompressedReader::Deflate64(inner) => inner.get_ref(),
+            #[cfg(feature = "bzip2")]
+            CompressedReader::Bz(inner) => inner.get_ref(),
+            #[cfg(feature = "lzma")]
+            CompressedReader::Lzma(inner) => inner.get_ref().get_ref(),
+            #[cfg(feature = "zstd")]
+            CompressedReader::Zstd(inner) => inner.get_ref().get_ref(),
+            #[cfg(feature = "xz")]
+            CompressedReader::Xz(inner) => inner.get_ref(),
+        }
+    }
+
+    /// Returns a mutable reference to the inner value.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        match self {
+            CompressedReader::Stored(inner) => inner,
+            #[cfg(feature = "deflate")]
+            CompressedReader::Deflate(inner) => inner.get_mut(),
+            #[cfg(feature = "deflate-dictionary")]
+            CompressedReader::DeflateDictionary(inner) => inner.get_mut(),
+            #[cfg(feature = "deflate64")]
+            CompressedReader::Deflate64(inner) => inner.get_mut(),
+            #[cfg(feature = "bzip2")]
+            CompressedReader::Bz(inner) => inner.get_mut(),
+            #[cfg(feature = "lzma")]
+            CompressedReader::Lzma(inner) => inner.get_mut().get_mut(),
+            #[cfg(feature = "zstd")]
+            CompressedReader::Zstd(inner) => inner.get_mut().get_mut(),
+            #[cfg(feature = "xz")]
+            CompressedReader::Xz(inner) => inner.get_mut(),
+        }
+    }
+
+    /// Replaces a Zstd decoder with one which rejects data requiring a window larger than the provided size (as a power
+    /// of two). This must be called before any data is read.
+    #[cfg(feature = "zstd")]
+    pub(crate) fn with_zstd_max_window_log(self, max_window_log: u32) -> Self {
+        match self {
+            CompressedReader::Zstd(inner) => {
+                // The first frame's header is checked up front so that its window size is reported distinctly, whereas
+                // the decoder's own limit still applies to any later frames.
+                let reader = ZstdWindowReader::new(inner.into_inner().into_inner(), Some(max_window_log));
+                let params = [async_compression::zstd::DParameter::window_log_max(max_window_log)];
+                CompressedReader::Zstd(bufread::ZstdDecoder::with_params(reader, &params))
+            }
+            other => other,
         }
     }
 
@@ -65,14 +149,16 @@ where
             CompressedReader::Stored(inner) => inner,
             #[cfg(feature = "deflate")]
             CompressedReader::Deflate(inner) => inner.into_inner(),
+            #[cfg(feature = "deflate-dictionary")]
+            CompressedReader::DeflateDictionary(inner) => inner.into_inner(),
             #[cfg(feature = "deflate64")]
             CompressedReader::Deflate64(inner) => inner.into_inner(),
             #[cfg(feature = "bzip2")]
             CompressedReader::Bz(inner) => inner.into_inner(),
             #[cfg(feature = "lzma")]
-            CompressedReader::Lzma(inner) => inner.into_inner(),
+            CompressedReader::Lzma(inner) => (*inner).into_inner().into_inner(),
             #[cfg(feature = "zstd")]
-            CompressedReader::Zstd(inner) => inner.into_inner(),
+            CompressedReader::Zstd(inner) => inner.into_inner().into_inner(),
             #[cfg(feature = "xz")]
             CompressedReader::Xz(inner) => inner.into_inner(),
         }
@@ -88,6 +174,8 @@ where
             CompressedReaderProj::Stored(inner) => inner.poll_read(c, b),
             #[cfg(feature = "deflate")]
             CompressedReaderProj::Deflate(inner) => inner.poll_read(c, b),
+            #[cfg(feature = "deflate-dictionary")]
+            CompressedReaderProj::DeflateDictionary(inner) => inner.poll_read(c, b),
             #[cfg(feature = "deflate64")]
             CompressedReaderProj::Deflate64(inner) => inner.poll_read(c, b),
             #[cfg(feature = "bzip2")]
@@ -0,0 +1,296 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::io::{AsyncBufRead, AsyncRead};
+use pin_project::pin_project;
+
+#[cfg(feature = "aes")]
+use crate::encryption::aes::{AesCipher, AesExtraField, AUTHENTICATION_CODE_LENGTH, PASSWORD_VERIFIER_LENGTH};
+use crate::encryption::zip_crypto::{ZipCryptoKeys, HEADER_LENGTH};
+use crate::error::{Result, ZipError};
+
+use futures_util::io::AsyncReadExt;
+use std::task::ready;
+
+/// A wrapping reader which decrypts an entry's data before it's decompressed.
+#[pin_project(project = DecryptingReaderProj)]
+pub(crate) enum DecryptingReader<R> {
+    Plain(#[pin] R),
+    ZipCrypto(#[pin] ZipCryptoReader<R>),
+    #[cfg(feature = "aes")]
+    Aes(#[pin] Box<AesReader<R>>),
+}
+
+impl<R> DecryptingReader<R> {
+    /// Returns a reference to the inner value.
+    pub(crate) fn get_ref(&self) -> &R {
+        match self {
+            DecryptingReader::Plain(inner) => inner,
+            DecryptingReader::ZipCrypto(inner) => &inner.reader,
+            #[cfg(feature = "aes")]
+            DecryptingReader::Aes(inner) => &inner.reader,
+        }
+    }
+
+    /// Returns a mutable reference to the inner value.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        match self {
+            DecryptingReader::Plain(inner) => inner,
+            DecryptingReader::ZipCrypto(inner) => &mut inner.reader,
+            #[cfg(feature = "aes")]
+            DecryptingReader::Aes(inner) => &mut inner.reader,
+        }
+    }
+
+    /// Consumes this reader and returns the inner value.
+    pub(crate) fn into_inner(self) -> R {
+        match self {
+            DecryptingReader::Plain(inner) => inner,
+            DecryptingReader::ZipCrypto(inner) => inner.reader,
+            #[cfg(feature = "aes")]
+            DecryptingReader::Aes(inner) => inner.reader,
+        }
+    }
+}
+
+impl<R> AsyncBufRead for DecryptingReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        match self.project() {
+            DecryptingReaderProj::Plain(inner) => inner.poll_fill_buf(cx),
+            DecryptingReaderProj::ZipCrypto(inner) => inner.poll_fill_buf(cx),
+            #[cfg(feature = "aes")]
+            DecryptingReaderProj::Aes(inner) => inner.poll_fill_buf(cx),
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        match self.project() {
+            DecryptingReaderProj::Plain(inner) => inner.consume(amt),
+            DecryptingReaderProj::ZipCrypto(inner) => inner.consume(amt),
+            #[cfg(feature = "aes")]
+            DecryptingReaderProj::Aes(inner) => inner.consume(amt),
+        }
+    }
+}
+
+impl<R> AsyncRead for DecryptingReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        match self.project() {
+            DecryptingReaderProj::Plain(inner) => inner.poll_read(c, b),
+            DecryptingReaderProj::ZipCrypto(inner) => inner.poll_read(c, b),
+            #[cfg(feature = "aes")]
+            DecryptingReaderProj::Aes(inner) => inner.poll_read(c, b),
+        }
+    }
+}
+
+/// A reader which decrypts data encrypted with traditional PKWARE encryption (ZipCrypto).
+pub(crate) struct ZipCryptoReader<R> {
+    reader: R,
+    keys: ZipCryptoKeys,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R> ZipCryptoReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Reads and decrypts the encryption header which prefixes the encrypted data, returning
+    /// [`ZipError::WrongPassword`] if its last byte doesn't match the expected check byte.
+    pub(crate) async fn new(mut reader: R, password: &[u8], check_byte: u8) -> Result<Self> {
+        let mut keys = ZipCryptoKeys::new(password);
+        let mut header = [0; HEADER_LENGTH];
+
+        reader.read_exact(&mut header).await?;
+        keys.decrypt(&mut header);
+
+        if header[HEADER_LENGTH - 1] != check_byte {
+            return Err(ZipError::WrongPassword);
+        }
+
+        Ok(Self { reader, keys, buffer: Vec::new(), position: 0 })
+    }
+}
+
+impl<R> AsyncBufRead for ZipCryptoReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        if this.position == this.buffer.len() {
+            let available = ready!(Pin::new(&mut this.reader).poll_fill_buf(cx))?;
+            let length = available.len();
+
+            this.buffer.clear();
+            this.buffer.extend_from_slice(available);
+            this.position = 0;
+
+            Pin::new(&mut this.reader).consume(length);
+            this.keys.decrypt(&mut this.buffer);
+        }
+
+        Poll::Ready(Ok(&this.buffer[this.position..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.position = std::cmp::min(this.position + amt, this.buffer.len());
+    }
+}
+
+impl<R> AsyncRead for ZipCryptoReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let length = std::cmp::min(available.len(), buf.len());
+
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+
+        Poll::Ready(Ok(length))
+    }
+}
+
+/// A reader which decrypts data encrypted with WinZip's AES scheme, verifying its authentication code once all of the
+/// ciphertext has been decrypted.
+#[cfg(feature = "aes")]
+pub(crate) struct AesReader<R> {
+    reader: R,
+    cipher: Option<AesCipher>,
+    remaining: u64,
+    buffer: Vec<u8>,
+    position: usize,
+    code: Vec<u8>,
+}
+
+#[cfg(feature = "aes")]
+impl<R> AesReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Reads the salt and password verifier which prefix the encrypted data, returning [`ZipError::WrongPassword`] if
+    /// the verifier doesn't match the one derived from the provided password.
+    pub(crate) async fn new(
+        mut reader: R,
+        field: &AesExtraField,
+        password: &[u8],
+        compressed_size: u64,
+    ) -> Result<Self> {
+        let remaining = compressed_size
+            .checked_sub(field.strength.overhead())
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+
+        let mut header = vec![0; field.strength.salt_length() + PASSWORD_VERIFIER_LENGTH];
+        reader.read_exact(&mut header).await?;
+
+        let (salt, expected_verifier) = header.split_at(field.strength.salt_length());
+        let (cipher, verifier) = AesCipher::new(password, salt, field.strength);
+
+        if verifier != expected_verifier {
+            return Err(ZipError::WrongPassword);
+        }
+
+        Ok(Self { reader, cipher: Some(cipher), remaining, buffer: Vec::new(), position: 0, code: Vec::new() })
+    }
+
+    /// Decrypts the next chunk of ciphertext into the buffer.
+    fn poll_decrypt_chunk(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.buffer.clear();
+        self.position = 0;
+
+        let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+        if available.is_empty() {
+            return Poll::Ready(Err(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+
+        let length = std::cmp::min(available.len() as u64, self.remaining) as usize;
+        self.buffer.extend_from_slice(&available[..length]);
+        Pin::new(&mut self.reader).consume(length);
+        self.remaining -= length as u64;
+
+        if let Some(cipher) = self.cipher.as_mut() {
+            cipher.decrypt(&mut self.buffer);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    /// Reads and verifies the authentication code which follows the ciphertext.
+    fn poll_verify(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while self.code.len() < AUTHENTICATION_CODE_LENGTH {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                return Poll::Ready(Err(std::io::ErrorKind::UnexpectedEof.into()));
+            }
+
+            let length = std::cmp::min(available.len(), AUTHENTICATION_CODE_LENGTH - self.code.len());
+            self.code.extend_from_slice(&available[..length]);
+            Pin::new(&mut self.reader).consume(length);
+        }
+
+        if let Some(cipher) = self.cipher.take() {
+            if let Err(err) = cipher.verify(&self.code) {
+                return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err)));
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "aes")]
+impl<R> AsyncBufRead for AesReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        if this.position == this.buffer.len() && this.remaining > 0 {
+            ready!(this.poll_decrypt_chunk(cx))?;
+        }
+
+        // The code is verified before the final chunk is returned, as a decompressor may stop reading once it reaches
+        // the end of its stream, and would otherwise never observe a mismatch.
+        if this.remaining == 0 && this.cipher.is_some() {
+            ready!(this.poll_verify(cx))?;
+        }
+
+        Poll::Ready(Ok(&this.buffer[this.position..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.position = std::cmp::min(this.position + amt, this.buffer.len());
+    }
+}
+
+#[cfg(feature = "aes")]
+impl<R> AsyncRead for AesReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let length = std::cmp::min(available.len(), buf.len());
+
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+
+        Poll::Ready(Ok(length))
+    }
+}
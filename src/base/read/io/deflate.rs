@@ -0,0 +1,207 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+#[cfg(feature = "deflate-dictionary")]
+use crate::base::read::io::poll_result_ok;
+
+use std::io::{Error, ErrorKind};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use async_compression::futures::bufread;
+#[cfg(feature = "deflate-dictionary")]
+use flate2::{Decompress, FlushDecompress, Status};
+use futures_util::io::{AsyncBufRead, AsyncRead};
+use pin_project::pin_project;
+
+/// The error returned when a Deflate stream's input ends before a block marked as final has been seen.
+#[derive(Debug, thiserror::Error)]
+#[error("deflate stream ended without a final block")]
+struct MissingFinalBlock;
+
+/// Returns whether or not the provided error was returned by a Deflate decoder whose input ended before a block marked
+/// as final had been seen.
+///
+/// Errors from the inner reader are never matched, so a caller can decide whether the input was legitimately exhausted.
+pub(crate) fn is_missing_final_block(err: &Error) -> bool {
+    err.get_ref().is_some_and(|inner| inner.is::<MissingFinalBlock>())
+}
+
+/// A raw Deflate decoder which reports input ending before a final block as [`MissingFinalBlock`].
+///
+/// `async-compression` doesn't distinguish a stream which ends without a final block from one which is corrupt, so the
+/// inner reader is wrapped to record whether it has reached EOF. Corrupt data is rejected whilst it's being decoded,
+/// so any error raised once the input has been exhausted can only be due to the stream ending early.
+#[pin_project]
+pub(crate) struct DeflateDecoder<R> {
+    #[pin]
+    decoder: bufread::DeflateDecoder<EofReader<R>>,
+}
+
+impl<R> DeflateDecoder<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Constructs a new decoder from a generic [`AsyncBufRead`] implementer.
+    pub(crate) fn new(reader: R) -> Self {
+        Self { decoder: bufread::DeflateDecoder::new(EofReader { reader, eof: false, failed: false }) }
+    }
+
+    /// Returns a reference to the inner value.
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.decoder.get_ref().reader
+    }
+
+    /// Returns a mutable reference to the inner value.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.decoder.get_mut().reader
+    }
+
+    /// Consumes this decoder and returns the inner value.
+    pub(crate) fn into_inner(self) -> R {
+        self.decoder.into_inner().reader
+    }
+}
+
+impl<R> AsyncRead for DeflateDecoder<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let mut project = self.project();
+
+        match ready!(project.decoder.as_mut().poll_read(c, b)) {
+            Err(_) if project.decoder.get_ref().eof && !project.decoder.get_ref().failed => {
+                Poll::Ready(Err(Error::new(ErrorKind::UnexpectedEof, MissingFinalBlock)))
+            }
+            result => Poll::Ready(result),
+        }
+    }
+}
+
+/// A wrapping reader which records whether its inner reader has reached EOF, or has itself failed.
+struct EofReader<R> {
+    reader: R,
+    eof: bool,
+    failed: bool,
+}
+
+impl<R> AsyncBufRead for EofReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        match ready!(Pin::new(&mut this.reader).poll_fill_buf(cx)) {
+            Ok(buffer) => {
+                this.eof = buffer.is_empty();
+                Poll::Ready(Ok(buffer))
+            }
+            Err(err) => {
+                this.failed = true;
+                Poll::Ready(Err(err))
+            }
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut self.get_mut().reader).consume(amt)
+    }
+}
+
+impl<R> AsyncRead for EofReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let length = std::cmp::min(available.len(), buf.len());
+
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+
+        Poll::Ready(Ok(length))
+    }
+}
+
+/// A raw Deflate decoder primed with a preset dictionary.
+///
+/// `async-compression` doesn't support preset dictionaries, so this drives `flate2`'s decompressor directly. Preset
+/// dictionaries are only supported by `flate2`'s zlib backends, hence the `deflate-dictionary` feature enabling one.
+#[cfg(feature = "deflate-dictionary")]
+#[pin_project]
+pub(crate) struct DictionaryDeflateDecoder<R> {
+    #[pin]
+    reader: R,
+    decompress: Decompress,
+    finished: bool,
+}
+
+#[cfg(feature = "deflate-dictionary")]
+impl<R> DictionaryDeflateDecoder<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Constructs a new decoder from a generic [`AsyncBufRead`] implementer and a preset dictionary.
+    pub(crate) fn new(reader: R, dictionary: &[u8]) -> Self {
+        let mut decompress = Decompress::new(false);
+        // Setting a dictionary on a raw inflate stream can't fail.
+        decompress.set_dictionary(dictionary).expect("unable to set deflate dictionary");
+
+        Self { reader, decompress, finished: false }
+    }
+
+    /// Returns a reference to the inner value.
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the inner value.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes this decoder and returns the inner value.
+    pub(crate) fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(feature = "deflate-dictionary")]
+impl<R> AsyncRead for DictionaryDeflateDecoder<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let mut project = self.project();
+
+        loop {
+            if *project.finished || b.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+
+            let input = poll_result_ok!(ready!(project.reader.as_mut().poll_fill_buf(c)));
+            let flush = if input.is_empty() { FlushDecompress::Finish } else { FlushDecompress::None };
+
+            let (in_before, out_before) = (project.decompress.total_in(), project.decompress.total_out());
+            let status =
+                project.decompress.decompress(input, b, flush).map_err(|err| Error::new(ErrorKind::InvalidData, err));
+            let status = poll_result_ok!(status);
+            let consumed = (project.decompress.total_in() - in_before) as usize;
+            let produced = (project.decompress.total_out() - out_before) as usize;
+            let input_empty = input.is_empty();
+            project.reader.as_mut().consume(consumed);
+
+            if status == Status::StreamEnd {
+                *project.finished = true;
+            }
+            if produced > 0 || *project.finished {
+                return Poll::Ready(Ok(produced));
+            }
+            if input_empty {
+                return Poll::Ready(Err(Error::new(ErrorKind::UnexpectedEof, MissingFinalBlock)));
+            }
+        }
+    }
+}
@@ -1,29 +1,103 @@
 // Copyright (c) 2022 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
-use crate::base::read::io::{compressed::CompressedReader, hashed::HashedReader, owned::OwnedReader};
+#[cfg(feature = "aes")]
+use crate::base::read::io::decrypt::AesReader;
+#[cfg(feature = "deflate")]
+use crate::base::read::io::deflate::is_missing_final_block;
+use crate::base::read::io::{
+    compressed::CompressedReader,
+    decrypt::{DecryptingReader, ZipCryptoReader},
+    hashed::HashedReader,
+    owned::OwnedReader,
+    poll_result_ok,
+};
+use crate::base::read::ReadOptions;
+#[cfg(feature = "aes")]
+use crate::encryption::aes::{AesExtraField, AesVendorVersion};
+use crate::encryption::zip_crypto;
 use crate::entry::ZipEntry;
 use crate::error::{Result, ZipError};
+use crate::spec::consts::AES_COMPRESSION_METHOD;
 use crate::spec::Compression;
 
+use std::io::SeekFrom;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{ready, Context, Poll};
 
-use futures_util::io::{AsyncRead, AsyncReadExt, BufReader, Take};
+use bytes::Bytes;
+use futures_util::io::{AsyncRead, AsyncReadExt, AsyncSeek, BufReader, Take};
+use futures_util::Stream;
 use pin_project::pin_project;
 
+type ProgressHook = Box<dyn FnMut(u64) + Send>;
+
 /// A type which encodes that [`ZipEntryReader`] has associated entry data.
 pub struct WithEntry<'a>(OwnedEntry<'a>);
 
 /// A type which encodes that [`ZipEntryReader`] has no associated entry data.
 pub struct WithoutEntry;
 
+pub(crate) use sealed::EntryState;
+
+mod sealed {
+    use crate::entry::ZipEntry;
+
+    /// Provides the entry data associated with a [`ZipEntryReader`](super::ZipEntryReader), if any.
+    pub trait EntryState {
+        fn associated_entry(&self) -> Option<&ZipEntry>;
+    }
+
+    impl EntryState for super::WithEntry<'_> {
+        fn associated_entry(&self) -> Option<&ZipEntry> {
+            Some(self.0.entry())
+        }
+    }
+
+    impl EntryState for super::WithoutEntry {
+        fn associated_entry(&self) -> Option<&ZipEntry> {
+            None
+        }
+    }
+}
+
 /// A ZIP entry reader which may implement decompression.
+///
+/// # Memory usage
+/// Entry data is decompressed incrementally as it's read, so the memory used by a reader is bounded by its internal
+/// buffer and the working state of the decompressor (eg. Deflate's 32KiB window), regardless of the entry's size. As
+/// such, entries larger than the available memory can be extracted by copying this reader into a file (or any other
+/// [`AsyncWrite`](futures_util::io::AsyncWrite) implementer) without any intermediate spilling to disk. Only the
+/// helpers which explicitly collect data (eg. [`ZipEntryReader::read_to_end_checked()`]) hold the whole entry in memory.
+/// Where the decompressor's state shouldn't be held for the lifetime of the reader, large entries can instead be
+/// decompressed into a temporary file upfront via [`ReadOptions::spill_threshold()`].
+///
+/// # CRC32 verification
+/// Readers returned by a ZIP reader compute the CRC32 hash of the data as it's read, and verify it against the entry's
+/// stored value once EOF is reached. A mismatch is returned from that final read as an [`std::io::Error`] wrapping
+/// [`ZipError::CRC32CheckError`], which converts back into the latter via `?`. Readers with associated entry data
+/// attribute the mismatch (and any error from their checked helpers) to the entry via [`ZipError::EntryError`], which
+/// [`ZipError::without_entry()`] unwraps. As the value is taken from the central directory where available, this also
+/// covers entries whose CRC32 value was written within a data descriptor. The check can be skipped via
+/// [`ZipEntryReader::unchecked()`].
+///
+/// # Seeking
+/// Where the underlying source is seekable, readers over unencrypted stored entries implement
+/// [`AsyncSeek`](futures_util::io::AsyncSeek), so that ranges of their data can be read without reading any of the data
+/// before them (eg. via [`seek::ZipFileReader::stored_reader()`](crate::base::read::seek::ZipFileReader::stored_reader),
+/// which checks for such an entry upfront). Seeking within any other entry returns an error.
 #[pin_project]
 pub struct ZipEntryReader<'a, R, E> {
     #[pin]
-    reader: HashedReader<CompressedReader<Take<OwnedReader<'a, R>>>>,
+    reader: HashedReader<CompressedReader<DecryptingReader<Take<OwnedReader<'a, R>>>>>,
     entry: E,
+    compressed_size: u64,
+    unknown_size: bool,
+    trust_zero_crc: bool,
+    expected_crc: Option<u32>,
+    crc_verified: bool,
+    decompressed_read: u64,
+    on_progress: Option<ProgressHook>,
 }
 
 impl<'a, R> ZipEntryReader<'a, R, WithoutEntry>
@@ -31,32 +105,198 @@ where
     R: AsyncRead + Unpin,
 {
     /// Constructs a new entry reader from its required parameters (incl. an owned R).
-    pub(crate) fn new_with_owned(reader: BufReader<R>, compression: Compression, size: u64) -> Self {
-        let reader = HashedReader::new(CompressedReader::new(OwnedReader::Owned(reader).take(size), compression));
-        Self { reader, entry: WithoutEntry }
+    pub(crate) fn new_with_owned(reader: BufReader<R>, entry: &ZipEntry) -> Result<Self> {
+        let reader = OwnedReader::Owned(reader).take(entry.compressed_size());
+        Self::new(DecryptingReader::Plain(reader), entry.compression(), entry)
+    }
+
+    /// Constructs a new entry reader over an entry's already decompressed data of the provided size (eg. once spilled
+    /// to a temporary file).
+    #[cfg(feature = "tokio-fs")]
+    pub(crate) fn new_decompressed(reader: BufReader<R>, size: u64) -> Self {
+        let reader = DecryptingReader::Plain(OwnedReader::Owned(reader).take(size));
+        Self {
+            reader: HashedReader::new(CompressedReader::Stored(reader)),
+            entry: WithoutEntry,
+            compressed_size: size,
+            unknown_size: false,
+            trust_zero_crc: false,
+            expected_crc: None,
+            crc_verified: false,
+            decompressed_read: 0,
+            on_progress: None,
+        }
     }
 
     /// Constructs a new entry reader from its required parameters (incl. a mutable borrow of an R).
-    pub(crate) fn new_with_borrow(reader: BufReader<&'a mut R>, compression: Compression, size: u64) -> Self {
-        let reader = HashedReader::new(CompressedReader::new(OwnedReader::Borrow(reader).take(size), compression));
-        Self { reader, entry: WithoutEntry }
+    pub(crate) fn new_with_borrow(reader: BufReader<&'a mut R>, entry: &ZipEntry) -> Result<Self> {
+        let reader = OwnedReader::Borrow(reader).take(entry.compressed_size());
+        Self::new(DecryptingReader::Plain(reader), entry.compression(), entry)
+    }
+
+    /// Constructs a new entry reader which decrypts the entry's data with the provided password.
+    ///
+    /// The password is verified before the reader is returned, erroring with [`ZipError::WrongPassword`] if it's
+    /// incorrect. Entries which aren't encrypted are read as normal.
+    pub(crate) async fn new_with_password(
+        reader: OwnedReader<'a, R>,
+        entry: &ZipEntry,
+        password: &[u8],
+    ) -> Result<Self> {
+        Self::decrypting(reader, entry, password).await.map_err(|err| err.in_entry(entry))
+    }
+
+    async fn decrypting(reader: OwnedReader<'a, R>, entry: &ZipEntry, password: &[u8]) -> Result<Self> {
+        let reader = reader.take(entry.compressed_size());
+        let aes = entry.compression() == Compression::Unsupported(AES_COMPRESSION_METHOD);
+
+        #[cfg(feature = "aes")]
+        if let Some(field) = AesExtraField::from_extra_fields(entry.extra_fields()).filter(|_| aes) {
+            let reader = AesReader::new(reader, &field, password, entry.compressed_size()).await?;
+            let mut reader = Self::new(DecryptingReader::Aes(Box::new(reader)), field.compression, entry)?;
+
+            // AE-2 entries have no CRC32 value, so their authentication code (verified at EOF) is relied upon instead.
+            reader.crc_verified = field.version == AesVendorVersion::Ae2;
+            return Ok(reader);
+        }
+
+        // AES entries which can't be decrypted by this build are left to fail on their compression method.
+        if !entry.general_purpose_flag.encrypted || aes {
+            return Self::new(DecryptingReader::Plain(reader), entry.compression(), entry);
+        }
+
+        let reader = ZipCryptoReader::new(reader, password, zip_crypto::check_byte(entry)).await?;
+        Self::new(DecryptingReader::ZipCrypto(reader), entry.compression(), entry)
+    }
+
+    fn new(
+        reader: DecryptingReader<Take<OwnedReader<'a, R>>>,
+        compression: Compression,
+        entry: &ZipEntry,
+    ) -> Result<Self> {
+        let reader = CompressedReader::new(reader, compression, entry.lzma_uncompressed_size())
+            .map_err(|err| err.in_entry(entry))?;
+        let reader = HashedReader::new(reader);
+        Ok(Self {
+            reader,
+            entry: WithoutEntry,
+            compressed_size: entry.compressed_size(),
+            unknown_size: entry.has_unknown_size(),
+            trust_zero_crc: false,
+            expected_crc: None,
+            crc_verified: false,
+            decompressed_read: 0,
+            on_progress: None,
+        })
     }
 
     pub(crate) fn into_with_entry(self, entry: &'a ZipEntry) -> ZipEntryReader<'a, R, WithEntry<'a>> {
-        ZipEntryReader { reader: self.reader, entry: WithEntry(OwnedEntry::Borrow(entry)) }
+        ZipEntryReader {
+            reader: self.reader,
+            compressed_size: self.compressed_size,
+            unknown_size: self.unknown_size,
+            trust_zero_crc: self.trust_zero_crc,
+            expected_crc: Some(entry.crc32()),
+            crc_verified: self.crc_verified,
+            decompressed_read: self.decompressed_read,
+            on_progress: self.on_progress,
+            entry: WithEntry(OwnedEntry::Borrow(entry)),
+        }
     }
 
     pub(crate) fn into_with_entry_owned(self, entry: ZipEntry) -> ZipEntryReader<'a, R, WithEntry<'a>> {
-        ZipEntryReader { reader: self.reader, entry: WithEntry(OwnedEntry::Owned(entry)) }
+        ZipEntryReader {
+            reader: self.reader,
+            compressed_size: self.compressed_size,
+            unknown_size: self.unknown_size,
+            trust_zero_crc: self.trust_zero_crc,
+            expected_crc: Some(entry.crc32()),
+            crc_verified: self.crc_verified,
+            decompressed_read: self.decompressed_read,
+            on_progress: self.on_progress,
+            entry: WithEntry(OwnedEntry::Owned(Box::new(entry))),
+        }
     }
 }
 
 impl<'a, R, E> AsyncRead for ZipEntryReader<'a, R, E>
 where
     R: AsyncRead + Unpin,
+    E: EntryState,
 {
     fn poll_read(self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut [u8]) -> Poll<std::io::Result<usize>> {
-        self.project().reader.poll_read(c, b)
+        let mut project = self.project();
+        let read = match ready!(project.reader.as_mut().poll_read(c, b)) {
+            Ok(read) => read,
+            // Some producers (eg. 7-Zip) may omit Deflate's final block, which is only tolerated once all of the entry's
+            // compressed data has been consumed. Any data which was truncated is still caught by the CRC32 check.
+            #[cfg(feature = "deflate")]
+            Err(err) if is_missing_final_block(&err) && project.reader.reader.get_ref().get_ref().limit() == 0 => 0,
+            Err(err) => return Poll::Ready(Err(err)),
+        };
+
+        if read == 0 && !b.is_empty() && !*project.crc_verified {
+            if let Some(expected) = project.expected_crc.take() {
+                let actual = project.reader.peek_hash();
+                if let Err(mut err) = verify_crc(expected, actual, *project.trust_zero_crc) {
+                    if let Some(entry) = project.entry.associated_entry() {
+                        err = err.in_entry(entry);
+                    }
+                    return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err)));
+                }
+                *project.crc_verified = true;
+            }
+        }
+
+        // Reads into an empty buffer aren't reported, and the hook is dropped once it's been called at EOF.
+        if let Some(hook) = project.on_progress.as_mut().filter(|_| !b.is_empty()) {
+            *project.decompressed_read += read as u64;
+            hook(*project.decompressed_read);
+
+            if read == 0 {
+                *project.on_progress = None;
+            }
+        }
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl<'a, R, E> AsyncSeek for ZipEntryReader<'a, R, E>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+    E: Unpin,
+{
+    /// Seeks to a position within the entry's data, which is only supported for unencrypted stored entries.
+    ///
+    /// Positions are relative to the start of the entry's data, and are clamped to its range. As the data may then be
+    /// read out of order, the CRC32 value is no longer verified once a seek has been performed.
+    fn poll_seek(self: Pin<&mut Self>, c: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+
+        let CompressedReader::Stored(DecryptingReader::Plain(reader)) = &mut this.reader.reader else {
+            let err = ZipError::FeatureNotSupported("seeking within compressed or encrypted entries");
+            return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::Unsupported, err)));
+        };
+
+        let length = this.compressed_size;
+        let position = length - reader.limit();
+        let target = match pos {
+            SeekFrom::Start(offset) => offset.min(length),
+            SeekFrom::End(offset) => clamped_offset(length, offset, length),
+            SeekFrom::Current(offset) => clamped_offset(position, offset, length),
+        };
+
+        // Seeking relative to the current position accounts for any data held within the inner buffer.
+        let delta = target as i64 - position as i64;
+        poll_result_ok!(ready!(Pin::new(reader.get_mut()).poll_seek(c, SeekFrom::Current(delta))));
+        reader.set_limit(length - target);
+
+        this.expected_crc = None;
+        this.crc_verified = true;
+        this.decompressed_read = target;
+
+        Poll::Ready(Ok(target))
     }
 }
 
@@ -71,9 +311,91 @@ where
         self.reader.swap_and_compute_hash()
     }
 
+    /// Returns the number of bytes of the entry's compressed data consumed from the underlying reader so far.
+    ///
+    /// This includes any encryption header, and never exceeds the entry's compressed size. Bytes are counted as the
+    /// decompressor consumes them, so the count may run ahead of the decompressed data returned so far, but excludes any
+    /// data which has only been read ahead into the reader's internal buffer.
+    pub fn compressed_consumed(&self) -> u64 {
+        self.compressed_size - self.reader.reader.get_ref().get_ref().limit()
+    }
+
+    /// Sets a hook which is called with the cumulative number of decompressed bytes read so far.
+    ///
+    /// The hook is called after each read which returns data, and a final time once EOF is reached (at which point the
+    /// count equals the entry's uncompressed size for a well-formed entry), but never for reads into an empty buffer.
+    /// Only bytes read after the hook is set are counted.
+    pub fn with_progress(mut self, on_progress: impl FnMut(u64) + Send + 'static) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    /// Consumes this reader and returns a stream of the entry's decompressed data, split into chunks of the provided
+    /// size.
+    ///
+    /// Data is buffered until a full chunk is available, so every chunk but the last holds exactly `chunk_size` bytes
+    /// regardless of how the decompressor's output is sized. The last chunk holds whatever remains and is never empty.
+    /// A `chunk_size` of zero is treated as one. As with the reader itself, a CRC32 mismatch is returned in place of
+    /// the stream's final item. Chunks are yielded as [`Bytes`] so they can be handed to HTTP bodies and other
+    /// consumers without copying.
+    pub fn into_chunks(self, chunk_size: usize) -> impl Stream<Item = Result<Bytes>> + 'a
+    where
+        R: 'a,
+        E: EntryState + 'a,
+    {
+        let chunk_size = std::cmp::max(chunk_size, 1);
+
+        futures_util::stream::try_unfold(self, move |mut reader| async move {
+            let mut chunk = Vec::with_capacity(chunk_size);
+            (&mut reader).take(chunk_size as u64).read_to_end(&mut chunk).await?;
+
+            Ok((!chunk.is_empty()).then(|| (Bytes::from(chunk), reader)))
+        })
+    }
+
+    /// Disables the verification of the entry's CRC32 value once EOF is reached.
+    ///
+    /// This is useful when deliberately streaming partial or known-corrupt data. The checked helpers (eg.
+    /// [`ZipEntryReader::read_to_end_checked()`]) still verify the value.
+    pub fn unchecked(mut self) -> Self {
+        self.expected_crc = None;
+        self
+    }
+
+    /// Sets the CRC32 value which the data read is verified against once EOF is reached.
+    pub(crate) fn expect_crc(mut self, crc: u32) -> Self {
+        self.expected_crc = Some(crc);
+        self
+    }
+
+    /// Applies any options which affect how entry data is read or verified.
+    pub(crate) fn with_options(mut self, options: &ReadOptions) -> Self {
+        self.trust_zero_crc = options.trust_zero_crc;
+
+        // The compressed size is raised alongside the limit so that the number of bytes consumed is still accurate.
+        if self.unknown_size && options.read_unknown_sizes_to_end {
+            self.compressed_size = u64::MAX;
+            self.reader.reader.get_mut().get_mut().set_limit(self.compressed_size);
+        }
+
+        #[cfg(feature = "deflate-dictionary")]
+        if let Some(dictionary) = options.deflate_dictionary.as_deref() {
+            let reader = self.reader.into_inner().with_deflate_dictionary(dictionary);
+            self.reader = HashedReader::new(reader);
+        }
+
+        #[cfg(feature = "zstd")]
+        if let Some(max_window_log) = options.zstd_max_window_log {
+            let reader = self.reader.into_inner().with_zstd_max_window_log(max_window_log);
+            self.reader = HashedReader::new(reader);
+        }
+
+        self
+    }
+
     /// Consumes this reader and returns the inner value.
     pub(crate) fn into_inner(self) -> R {
-        self.reader.into_inner().into_inner().into_inner().owned_into_inner()
+        self.reader.into_inner().into_inner().into_inner().into_inner().owned_into_inner()
     }
 }
 
@@ -90,31 +412,146 @@ where
     ///
     /// This is a helper function synonymous to [`AsyncReadExt::read_to_end()`].
     pub async fn read_to_end_checked(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
-        let read = self.read_to_end(buf).await?;
+        let result = async {
+            let read = self.read_to_end(buf).await?;
+            self.check_crc()?;
 
-        if self.compute_hash() == self.entry.0.entry().crc32() {
             Ok(read)
-        } else {
-            Err(ZipError::CRC32CheckError)
-        }
+        };
+
+        result.await.map_err(|err: ZipError| err.in_entry(self.entry()))
     }
 
     /// Reads all bytes until EOF has been reached, placing them into buf, and verifies the CRC32 values.
     ///
     /// This is a helper function synonymous to [`AsyncReadExt::read_to_string()`].
     pub async fn read_to_string_checked(&mut self, buf: &mut String) -> Result<usize> {
-        let read = self.read_to_string(buf).await?;
+        let result = async {
+            let read = self.read_to_string(buf).await?;
+            self.check_crc()?;
 
-        if self.compute_hash() == self.entry.0.entry().crc32() {
             Ok(read)
-        } else {
-            Err(ZipError::CRC32CheckError)
+        };
+
+        result.await.map_err(|err: ZipError| err.in_entry(self.entry()))
+    }
+
+    /// Reads all bytes until EOF has been reached, verifies the CRC32 values, and returns them as a string.
+    ///
+    /// At most `max_bytes` bytes are decompressed, with [`ZipError::EntryTooLarge`] being returned if the entry holds
+    /// any more (or its recorded uncompressed size says as much, in which case nothing is decompressed). Data which
+    /// isn't valid UTF-8 is rejected with [`ZipError::InvalidUtf8`].
+    pub(crate) async fn read_to_string_capped(&mut self, max_bytes: u64) -> Result<String> {
+        let result = async {
+            if self.entry().uncompressed_size() > max_bytes {
+                return Err(ZipError::EntryTooLarge(max_bytes));
+            }
+
+            let mut buffer = Vec::new();
+            if self.take(max_bytes.saturating_add(1)).read_to_end(&mut buffer).await? as u64 > max_bytes {
+                return Err(ZipError::EntryTooLarge(max_bytes));
+            }
+            self.check_crc()?;
+
+            String::from_utf8(buffer).map_err(|_| ZipError::InvalidUtf8)
+        };
+
+        result.await.map_err(|err: ZipError| err.in_entry(self.entry()))
+    }
+
+    /// Reads all bytes until EOF has been reached, writing them synchronously to a [`std::io::Write`] implementer,
+    /// and verifies the CRC32 values.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn copy_to_sync_checked<W: std::io::Write>(&mut self, writer: &mut W) -> Result<u64> {
+        let result = async {
+            let mut buffer = vec![0; 8192];
+            let mut copied = 0;
+
+            loop {
+                let read = self.read(&mut buffer).await?;
+                if read == 0 {
+                    break;
+                }
+
+                writer.write_all(&buffer[..read])?;
+                copied += read as u64;
+            }
+
+            self.check_crc()?;
+
+            Ok(copied)
+        };
+
+        result.await.map_err(|err: ZipError| err.in_entry(self.entry()))
+    }
+
+    /// Reads and discards all bytes until EOF has been reached, verifying the CRC32 value and the uncompressed size.
+    ///
+    /// Returns the number of bytes read.
+    pub(crate) async fn verify_checked(&mut self) -> Result<u64> {
+        // The CRC32 value is checked below instead, so that a mismatch isn't wrapped within an I/O error.
+        self.expected_crc = None;
+
+        let result = async {
+            let mut buffer = vec![0; 8192];
+            let mut read = 0;
+
+            loop {
+                match self.read(&mut buffer).await? {
+                    0 => break,
+                    n => read += n as u64,
+                }
+            }
+
+            self.check_crc()?;
+
+            let expected = self.entry().uncompressed_size();
+            if read != expected && !self.unknown_size {
+                return Err(ZipError::UncompressedSizeMismatch { expected, actual: read });
+            }
+
+            Ok(read)
+        };
+
+        result.await.map_err(|err: ZipError| err.in_entry(self.entry()))
+    }
+
+    /// Computes the CRC32 hash of bytes read so far and verifies it against the entry's stored value.
+    ///
+    /// If the reader was configured to trust zero CRC32 values, a stored value of zero is accepted with a warning.
+    pub(crate) fn check_crc(&mut self) -> Result<()> {
+        if self.crc_verified {
+            return Ok(());
         }
+
+        let actual = self.compute_hash();
+        verify_crc(self.entry.0.entry().crc32(), actual, self.trust_zero_crc)
     }
 }
 
+/// Applies a signed offset to a base position, clamping the result to `0..=length`.
+fn clamped_offset(base: u64, offset: i64, length: u64) -> u64 {
+    base.saturating_add_signed(offset).min(length)
+}
+
+/// Verifies a computed CRC32 value against an entry's stored value.
+///
+/// If zero CRC32 values are trusted, a stored value of zero is accepted with a warning.
+fn verify_crc(expected: u32, actual: u32, trust_zero_crc: bool) -> Result<()> {
+    if actual == expected {
+        return Ok(());
+    }
+
+    if expected == 0 && trust_zero_crc {
+        log::warn!("entry has a zero CRC32 value, trusting the computed value of {actual:#010x}");
+        return Ok(());
+    }
+
+    Err(ZipError::CRC32CheckError { expected, actual })
+}
+
 enum OwnedEntry<'a> {
-    Owned(ZipEntry),
+    Owned(Box<ZipEntry>),
     Borrow(&'a ZipEntry),
 }
 
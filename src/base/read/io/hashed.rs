@@ -36,6 +36,11 @@ where
         std::mem::take(&mut self.hasher).finalize()
     }
 
+    /// Returns the CRC32 hash of the data read so far without resetting the internal hasher.
+    pub(crate) fn peek_hash(&self) -> u32 {
+        self.hasher.clone().finalize()
+    }
+
     /// Consumes this reader and returns the inner value.
     pub(crate) fn into_inner(self) -> R {
         self.reader
@@ -16,6 +16,10 @@
 //!
 //! The below method is one that compromises on these two contention points. Please submit an issue or PR if you know
 //! of a better algorithm for this (and have tested/verified its performance).
+//!
+//! As the EOCDR is located by its signature alone, its comment length isn't required to match the number of bytes
+//! which follow it. Archives which under-report their comment length (leaving extra trailing bytes) are therefore
+//! still readable, with only the reported number of bytes being treated as the comment.
 
 #[cfg(doc)]
 use futures_util::io::BufReader;
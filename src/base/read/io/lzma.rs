@@ -0,0 +1,123 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::spec::consts::{LZMA_HEADER_LENGTH, LZMA_PROPERTIES_LENGTH};
+
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::io::{AsyncBufRead, AsyncRead};
+
+/// The length of the header which prefixes a bare `.lzma` stream, holding the properties and the uncompressed size.
+const ALONE_HEADER_LENGTH: usize = LZMA_PROPERTIES_LENGTH + 8;
+
+/// A wrapping reader which converts an entry's LZMA data into a bare `.lzma` stream.
+///
+/// Within a ZIP file, LZMA data is prefixed by the LZMA SDK version and the length of the properties which follow,
+/// whereas the decoder expects the properties to be followed by the uncompressed size. Where that size is unknown (ie.
+/// the data is terminated by an end-of-stream marker), it's recorded as such, otherwise the decoder stops once that many
+/// bytes have been produced rather than waiting for a marker which may never come.
+pub(crate) struct LzmaHeaderReader<R> {
+    reader: R,
+    uncompressed_size: Option<u64>,
+    header: Vec<u8>,
+    position: usize,
+}
+
+impl<R> LzmaHeaderReader<R> {
+    /// Constructs a new wrapping reader, provided the entry's uncompressed size if no end-of-stream marker is used.
+    pub(crate) fn new(reader: R, uncompressed_size: Option<u64>) -> Self {
+        Self { reader, uncompressed_size, header: Vec::with_capacity(ALONE_HEADER_LENGTH), position: 0 }
+    }
+
+    /// Returns a reference to the inner value.
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the inner value.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes this reader and returns the inner value.
+    pub(crate) fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> LzmaHeaderReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Reads the ZIP-specific header and properties, and builds the bare header from them.
+    fn poll_header(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while self.header.len() < LZMA_HEADER_LENGTH + LZMA_PROPERTIES_LENGTH {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                return Poll::Ready(Err(std::io::ErrorKind::UnexpectedEof.into()));
+            }
+
+            let length =
+                std::cmp::min(available.len(), LZMA_HEADER_LENGTH + LZMA_PROPERTIES_LENGTH - self.header.len());
+            self.header.extend_from_slice(&available[..length]);
+            Pin::new(&mut self.reader).consume(length);
+        }
+
+        let properties_length = u16::from_le_bytes([self.header[2], self.header[3]]);
+        if properties_length as usize != LZMA_PROPERTIES_LENGTH {
+            return Poll::Ready(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unexpected LZMA properties length of {properties_length}"),
+            )));
+        }
+
+        self.header.drain(..LZMA_HEADER_LENGTH);
+        self.header.extend_from_slice(&self.uncompressed_size.unwrap_or(u64::MAX).to_le_bytes());
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R> AsyncBufRead for LzmaHeaderReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        if this.header.len() != ALONE_HEADER_LENGTH {
+            ready!(this.poll_header(cx))?;
+        }
+
+        if this.position < ALONE_HEADER_LENGTH {
+            return Poll::Ready(Ok(&this.header[this.position..]));
+        }
+
+        Pin::new(&mut this.reader).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+
+        if this.position < ALONE_HEADER_LENGTH {
+            this.position = std::cmp::min(this.position + amt, ALONE_HEADER_LENGTH);
+        } else {
+            Pin::new(&mut this.reader).consume(amt);
+        }
+    }
+}
+
+impl<R> AsyncRead for LzmaHeaderReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let length = std::cmp::min(available.len(), buf.len());
+
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+
+        Poll::Ready(Ok(length))
+    }
+}
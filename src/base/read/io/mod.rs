@@ -3,10 +3,17 @@
 
 pub(crate) mod combined_record;
 pub(crate) mod compressed;
+pub(crate) mod decrypt;
+#[cfg(feature = "deflate")]
+pub(crate) mod deflate;
 pub(crate) mod entry;
 pub(crate) mod hashed;
 pub(crate) mod locator;
+#[cfg(feature = "lzma")]
+pub(crate) mod lzma;
 pub(crate) mod owned;
+#[cfg(feature = "zstd")]
+pub(crate) mod zstd;
 
 pub use combined_record::CombinedCentralDirectoryRecord;
 
@@ -1,10 +1,11 @@
 // Copyright (c) 2022 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
+use std::io::SeekFrom;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use futures_util::io::{AsyncBufRead, AsyncRead, BufReader};
+use futures_util::io::{AsyncBufRead, AsyncRead, AsyncSeek, BufReader};
 use pin_project::pin_project;
 
 /// A wrapping reader which holds an owned R or a mutable borrow to R.
@@ -60,3 +61,15 @@ where
         }
     }
 }
+
+impl<'a, R> AsyncSeek for OwnedReader<'a, R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    fn poll_seek(self: Pin<&mut Self>, c: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+        match self.project() {
+            OwnedReaderProj::Owned(inner) => inner.poll_seek(c, pos),
+            OwnedReaderProj::Borrow(inner) => inner.poll_seek(c, pos),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::error::ZipError;
+
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::io::{AsyncBufRead, AsyncRead};
+
+/// The magic number which begins a Zstandard frame.
+const ZSTD_FRAME_MAGIC: u32 = 0xFD2FB528;
+
+/// The length of the magic number and frame header descriptor, from which the remaining header length is derived.
+const ZSTD_FRAME_DESCRIPTOR_END: usize = 5;
+
+/// A wrapping reader which rejects Zstd data whose first frame requires a window larger than a maximum size.
+///
+/// The window size is parsed from the frame header (either its window descriptor or, for single-segment frames, its
+/// content size) before any data reaches the decoder, rather than relying on the decoder's own error which can't be
+/// distinguished from other failures. The header's bytes are then passed through to the decoder unchanged. Data which
+/// doesn't begin with a Zstd frame is also passed through, leaving the decoder to reject it.
+pub(crate) struct ZstdWindowReader<R> {
+    reader: R,
+    max_window_log: Option<u32>,
+    header: Vec<u8>,
+    position: usize,
+    checked: bool,
+}
+
+impl<R> ZstdWindowReader<R> {
+    /// Constructs a new wrapping reader, provided the maximum window size (as a power of two) if one should be enforced.
+    pub(crate) fn new(reader: R, max_window_log: Option<u32>) -> Self {
+        Self { reader, max_window_log, header: Vec::new(), position: 0, checked: max_window_log.is_none() }
+    }
+
+    /// Returns a reference to the inner value.
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the inner value.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes this reader and returns the inner value.
+    pub(crate) fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> ZstdWindowReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Reads from the inner reader until the header holds the provided number of bytes, or the inner reader ends.
+    fn poll_fill_header(&mut self, cx: &mut Context<'_>, length: usize) -> Poll<std::io::Result<bool>> {
+        while self.header.len() < length {
+            let available = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if available.is_empty() {
+                return Poll::Ready(Ok(false));
+            }
+
+            let consumed = std::cmp::min(available.len(), length - self.header.len());
+            self.header.extend_from_slice(&available[..consumed]);
+            Pin::new(&mut self.reader).consume(consumed);
+        }
+
+        Poll::Ready(Ok(true))
+    }
+
+    /// Reads the first frame's header and checks its window size against the maximum.
+    fn poll_header(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if !ready!(self.poll_fill_header(cx, ZSTD_FRAME_DESCRIPTOR_END))? {
+            return Poll::Ready(Ok(()));
+        }
+        if u32::from_le_bytes([self.header[0], self.header[1], self.header[2], self.header[3]]) != ZSTD_FRAME_MAGIC {
+            return Poll::Ready(Ok(()));
+        }
+
+        let descriptor = self.header[4];
+        let single_segment = descriptor & 0x20 != 0;
+        let dictionary_id_length = [0, 1, 2, 4][usize::from(descriptor & 0x03)];
+        let content_size_length = match descriptor >> 6 {
+            0 => usize::from(single_segment),
+            flag => 1 << flag,
+        };
+        let window_descriptor_length = usize::from(!single_segment);
+
+        let header_length =
+            ZSTD_FRAME_DESCRIPTOR_END + window_descriptor_length + dictionary_id_length + content_size_length;
+        if !ready!(self.poll_fill_header(cx, header_length))? {
+            return Poll::Ready(Ok(()));
+        }
+
+        let window_size = if single_segment {
+            // A single-segment frame's window spans its whole content.
+            let content_size = &self.header[header_length - content_size_length..header_length];
+            let mut bytes = [0; 8];
+            bytes[..content_size.len()].copy_from_slice(content_size);
+            u64::from_le_bytes(bytes) + if content_size_length == 2 { 256 } else { 0 }
+        } else {
+            let window_descriptor = self.header[ZSTD_FRAME_DESCRIPTOR_END];
+            let window_base = 1u64 << (10 + (window_descriptor >> 3));
+            window_base + (window_base / 8) * u64::from(window_descriptor & 0x07)
+        };
+
+        let max_window_size = self.max_window_log.and_then(|log| 1u64.checked_shl(log)).unwrap_or(u64::MAX);
+        if window_size > max_window_size {
+            return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, ZipError::WindowTooLarge)));
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R> AsyncBufRead for ZstdWindowReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        if !this.checked {
+            ready!(this.poll_header(cx))?;
+            this.checked = true;
+        }
+
+        if this.position < this.header.len() {
+            return Poll::Ready(Ok(&this.header[this.position..]));
+        }
+
+        Pin::new(&mut this.reader).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+
+        if this.position < this.header.len() {
+            this.position = std::cmp::min(this.position + amt, this.header.len());
+        } else {
+            Pin::new(&mut this.reader).consume(amt);
+        }
+    }
+}
+
+impl<R> AsyncRead for ZstdWindowReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let length = std::cmp::min(available.len(), buf.len());
+
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+
+        Poll::Ready(Ok(length))
+    }
+}
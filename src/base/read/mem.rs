@@ -71,18 +71,27 @@
 use crate::base::read::seek;
 
 use crate::base::read::io::entry::ZipEntryReader;
+use crate::base::read::io::owned::OwnedReader;
+use crate::base::read::{ReadOptions, VerifyReport};
 use crate::error::{Result, ZipError};
 use crate::file::ZipFile;
 
+use crate::entry::ZipEntry;
+use crate::spec::consts::{EOCDR_SIGNATURE, LFH_SIGNATURE, SIGNATURE_LENGTH};
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::ops::Range;
 use std::sync::Arc;
 
-use futures_util::io::{BufReader, Cursor};
+use futures_util::io::{AsyncReadExt, BufReader, Cursor, Take};
 
 use super::io::entry::{WithEntry, WithoutEntry};
 
 struct Inner {
     data: Vec<u8>,
     file: ZipFile,
+    options: ReadOptions,
 }
 
 // A concurrent ZIP reader which acts over an owned vector of bytes.
@@ -94,7 +103,23 @@ pub struct ZipFileReader {
 impl ZipFileReader {
     /// Constructs a new ZIP reader from an owned vector of bytes.
     pub async fn new(data: Vec<u8>) -> Result<ZipFileReader> {
-        let file = crate::base::read::file(Cursor::new(&data)).await?;
+        Self::with_options(data, ReadOptions::default()).await
+    }
+
+    /// Constructs a new ZIP reader from an owned vector of bytes with the provided options.
+    pub async fn with_options(data: Vec<u8>, options: ReadOptions) -> Result<ZipFileReader> {
+        let file = crate::base::read::file(Cursor::new(&data), &options).await?;
+        Ok(ZipFileReader { inner: Arc::new(Inner { data, file, options }) })
+    }
+
+    /// Constructs a new ZIP reader from an owned vector of bytes whose central directory is missing or corrupt.
+    ///
+    /// Rather than parsing the central directory, the entries are reconstructed by walking the local file headers from
+    /// the start of the data, stopping at the first record which isn't one. As such, any information only held within
+    /// the central directory (eg. entry comments and file attributes) is unavailable, and the ZIP file's comment is
+    /// empty. The sizes and CRC32 value of an entry written with a data descriptor are taken from that descriptor.
+    pub async fn repair(data: Vec<u8>) -> Result<ZipFileReader> {
+        let file = crate::base::read::repair::repair(Cursor::new(&data)).await?;
         Ok(ZipFileReader::from_raw_parts(data, file))
     }
 
@@ -102,7 +127,13 @@ impl ZipFileReader {
     ///
     /// Providing a [`ZipFile`] that wasn't derived from those bytes may lead to inaccurate parsing.
     pub fn from_raw_parts(data: Vec<u8>, file: ZipFile) -> ZipFileReader {
-        ZipFileReader { inner: Arc::new(Inner { data, file }) }
+        Self::from_raw_parts_with_options(data, file, ReadOptions::default())
+    }
+
+    /// Constructs a ZIP reader from an owned vector of bytes, ZIP file information derived from those bytes, and the
+    /// options with which that information was derived.
+    pub(crate) fn from_raw_parts_with_options(data: Vec<u8>, file: ZipFile, options: ReadOptions) -> ZipFileReader {
+        ZipFileReader { inner: Arc::new(Inner { data, file, options }) }
     }
 
     /// Returns this ZIP file's information.
@@ -110,38 +141,260 @@ impl ZipFileReader {
         &self.inner.file
     }
 
+    /// Returns an iterator over the decoded names of this ZIP file's entries, as per [`ZipFile::entry_names()`].
+    pub fn entry_names(&self) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+        self.file().entry_names()
+    }
+
+    /// Returns whether or not any bytes follow the end of this ZIP file's comment, as per
+    /// [`ZipFile::has_trailing_data()`].
+    pub fn has_trailing_data(&self) -> bool {
+        self.file().has_trailing_data()
+    }
+
     /// Returns the raw bytes provided to the reader during construction.
     pub fn data(&self) -> &[u8] {
         &self.inner.data
     }
 
+    /// Returns the raw bytes of this ZIP file's central directory.
+    pub async fn central_directory_bytes(&self) -> Result<Vec<u8>> {
+        crate::base::read::central_directory_bytes(Cursor::new(&self.inner.data), &self.inner.file).await
+    }
+
+    /// Returns each entry alongside the absolute range of its compressed data within the stored ZIP file data.
+    ///
+    /// Every local file header is read to account for any differences in length from the central directory.
+    pub async fn entries_with_ranges(&self) -> Result<Vec<(ZipEntry, Range<u64>)>> {
+        crate::base::read::entries_with_ranges(Cursor::new(&self.inner.data), &self.inner.file).await
+    }
+
     /// Returns a new entry reader if the provided index is valid.
-    pub async fn reader_without_entry(&self, index: usize) -> Result<ZipEntryReader<Cursor<&[u8]>, WithoutEntry>> {
+    pub async fn reader_without_entry(&self, index: usize) -> Result<ZipEntryReader<'_, Cursor<&[u8]>, WithoutEntry>> {
         let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
         let mut cursor = BufReader::new(Cursor::new(&self.inner.data[..]));
 
         stored_entry.seek_to_data_offset(&mut cursor).await?;
 
-        Ok(ZipEntryReader::new_with_owned(
-            cursor,
-            stored_entry.entry.compression(),
-            stored_entry.entry.compressed_size(),
-        ))
+        let reader = ZipEntryReader::new_with_owned(cursor, stored_entry)?;
+
+        Ok(reader.with_options(&self.inner.options).expect_crc(stored_entry.entry.crc32()))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, without verifying the entry's CRC32 value at EOF.
+    ///
+    /// See [`ZipEntryReader::unchecked()`].
+    pub async fn reader_without_entry_unchecked(
+        &self,
+        index: usize,
+    ) -> Result<ZipEntryReader<'_, Cursor<&[u8]>, WithoutEntry>> {
+        Ok(self.reader_without_entry(index).await?.unchecked())
     }
 
     /// Returns a new entry reader if the provided index is valid.
-    pub async fn reader_with_entry(&self, index: usize) -> Result<ZipEntryReader<Cursor<&[u8]>, WithEntry<'_>>> {
+    pub async fn reader_with_entry(&self, index: usize) -> Result<ZipEntryReader<'_, Cursor<&[u8]>, WithEntry<'_>>> {
+        if let Some(password) = self.inner.options.password.as_deref() {
+            return self.reader_with_entry_and_password(index, password).await;
+        }
+
         let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
         let mut cursor = BufReader::new(Cursor::new(&self.inner.data[..]));
 
         stored_entry.seek_to_data_offset(&mut cursor).await?;
 
-        let reader = ZipEntryReader::new_with_owned(
-            cursor,
-            stored_entry.entry.compression(),
-            stored_entry.entry.compressed_size(),
-        );
+        let reader = ZipEntryReader::new_with_owned(cursor, stored_entry)?;
+
+        Ok(reader.with_options(&self.inner.options).into_with_entry(stored_entry))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, decrypting the entry's data with the provided password.
+    ///
+    /// Both traditional PKWARE (ZipCrypto) and WinZip AES encryption are supported, the latter requiring the `aes`
+    /// feature. The password is checked against the entry's encryption header before the reader is returned, erroring
+    /// with [`ZipError::WrongPassword`] if it's incorrect. The authentication code of AES-encrypted entries is then
+    /// verified once EOF is reached. Entries which aren't encrypted are read as normal.
+    pub async fn reader_with_entry_and_password(
+        &self,
+        index: usize,
+        password: &[u8],
+    ) -> Result<ZipEntryReader<'_, Cursor<&[u8]>, WithEntry<'_>>> {
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut cursor = BufReader::new(Cursor::new(&self.inner.data[..]));
+
+        stored_entry.seek_to_data_offset(&mut cursor).await?;
+
+        let reader = ZipEntryReader::new_with_password(OwnedReader::Owned(cursor), stored_entry, password).await?;
+
+        Ok(reader.with_options(&self.inner.options).into_with_entry(stored_entry))
+    }
+
+    /// Reads the entry at the provided index into a string, as per [`ZipEntryReader::read_to_string_checked()`].
+    ///
+    /// At most `max_bytes` bytes are decompressed, with [`ZipError::EntryTooLarge`] being returned if the entry holds
+    /// any more. Data which isn't valid UTF-8 is rejected with [`ZipError::InvalidUtf8`].
+    pub async fn read_entry_to_string(&self, index: usize, max_bytes: u64) -> Result<String> {
+        self.reader_with_entry(index).await?.read_to_string_capped(max_bytes).await
+    }
+
+    /// Verifies that every entry decompresses cleanly and matches its recorded CRC32 value and uncompressed size.
+    ///
+    /// Rather than returning the first error encountered, each entry's outcome is recorded within the returned
+    /// [`VerifyReport`], akin to `unzip -t`. The decompressed data is discarded. Encrypted entries are skipped unless a
+    /// password was provided via [`ReadOptions::password()`].
+    pub async fn verify(&self) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+
+        for (index, stored_entry) in self.inner.file.entries.iter().enumerate() {
+            if report.skip(index, &stored_entry.entry, &self.inner.options) {
+                continue;
+            }
+
+            let result = match self.reader_with_entry(index).await {
+                Ok(mut reader) => reader.verify_checked().await,
+                Err(err) => Err(err),
+            };
+            report.record(index, result);
+        }
+
+        Ok(report)
+    }
+
+    /// Returns a new entry reader for the entry with the provided name, as located by [`ZipFile::entry_index()`].
+    ///
+    /// [`ZipError::EntryNotFound`] is returned if no such entry exists.
+    pub async fn reader_with_entry_by_name(
+        &self,
+        name: &str,
+    ) -> Result<ZipEntryReader<'_, Cursor<&[u8]>, WithEntry<'_>>> {
+        let index = self.inner.file.entry_index(name).ok_or_else(|| ZipError::EntryNotFound(name.to_owned()))?;
+        self.reader_with_entry(index).await
+    }
+
+    /// Returns a reader over the compressed (and possibly encrypted) data of the entry at the provided index.
+    ///
+    /// The data is returned exactly as stored, so it can be copied into another ZIP file without being decompressed
+    /// and recompressed via [`ZipFileWriter::copy_entry_raw()`](crate::base::write::ZipFileWriter::copy_entry_raw).
+    pub async fn raw_reader_without_entry(&self, index: usize) -> Result<Take<BufReader<Cursor<&[u8]>>>> {
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut cursor = BufReader::new(Cursor::new(&self.inner.data[..]));
+
+        stored_entry.seek_to_data_offset(&mut cursor).await?;
+
+        Ok(cursor.take(stored_entry.entry.compressed_size()))
+    }
+
+    /// Returns a new entry reader over the data of the stored entry at the provided index, for random access.
+    ///
+    /// See [`seek::ZipFileReader::stored_reader()`] for details.
+    pub async fn stored_reader(&self, index: usize) -> Result<ZipEntryReader<'_, Cursor<&[u8]>, WithEntry<'_>>> {
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        stored_entry.entry.check_random_access()?;
+
+        self.reader_with_entry(index).await
+    }
+
+    /// Copies the data of the entry at the provided index into a synchronous writer, verifying its CRC32 value.
+    ///
+    /// Returns the number of bytes copied.
+    ///
+    /// # Runtime
+    /// This function drives the asynchronous read to completion on the current [`tokio`] runtime via
+    /// [`Handle::block_on()`](tokio::runtime::Handle::block_on), and therefore must be called from within the context
+    /// of a runtime but outside of an asynchronous context (eg. from within [`tokio::task::spawn_blocking()`]).
+    /// Calling it from elsewhere will panic.
+    #[cfg(feature = "tokio")]
+    pub fn blocking_copy_entry_to<W: std::io::Write>(&self, index: usize, mut writer: W) -> Result<u64> {
+        tokio::runtime::Handle::current().block_on(async {
+            let mut reader = self.reader_with_entry(index).await?;
+            reader.copy_to_sync_checked(&mut writer).await
+        })
+    }
+}
+
+/// Visits every entry of a ZIP file, recursing into any entries which are themselves ZIP files.
+///
+/// The visitor is called with the nesting depth of each entry (starting from zero for the provided reader's entries)
+/// and the entry itself. An entry is considered to be a nested ZIP file if its decompressed data begins with a local
+/// file header or end of central directory record signature, in which case its data is read into memory and parsed.
+/// Nested ZIP files are only opened up to a depth of `max_depth`, which guards against ZIP quines and similar bombs;
+/// any deeper nested ZIP files are visited as regular entries. Likewise, nested ZIP files whose decompressed size
+/// exceeds `max_nested_size` bytes aren't read into memory, and those which fail to parse are skipped with a warning
+/// rather than failing the whole walk.
+///
+/// Nested ZIP files are visited in a breadth-first order, and the CRC32 values of any which are opened are verified.
+///
+/// # Example
+/// ```no_run
+/// # use async_zip::base::read::mem::{scan_recursive, ZipFileReader};
+/// # use async_zip::error::Result;
+/// #
+/// # async fn run(reader: ZipFileReader) -> Result<()> {
+/// scan_recursive(&reader, 3, 64 * 1024 * 1024, |depth, entry| {
+///     println!("{}{:?}", "  ".repeat(depth), entry.filename());
+/// })
+/// .await?;
+/// #   Ok(())
+/// # }
+/// ```
+pub async fn scan_recursive<F>(
+    reader: &ZipFileReader,
+    max_depth: usize,
+    max_nested_size: u64,
+    mut visitor: F,
+) -> Result<()>
+where
+    F: FnMut(usize, &ZipEntry),
+{
+    let mut queue = VecDeque::from([(reader.clone(), 0)]);
+
+    while let Some((reader, depth)) = queue.pop_front() {
+        for (index, entry) in reader.file().entries().iter().enumerate() {
+            visitor(depth, entry);
+
+            if depth >= max_depth || entry.filename().as_bytes().ends_with(b"/") {
+                continue;
+            }
+
+            let nested = read_nested(&reader, index, max_nested_size).await.map_err(|err| err.in_entry(entry))?;
+            if let Some(nested) = nested {
+                queue.push_back((nested, depth + 1));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the entry at the provided index as a nested ZIP file, if its data starts with a ZIP signature.
+///
+/// Nested ZIP files larger than `max_size` bytes, or which fail to parse, are skipped.
+async fn read_nested(reader: &ZipFileReader, index: usize, max_size: u64) -> Result<Option<ZipFileReader>> {
+    let mut entry_reader = reader.reader_with_entry(index).await?;
+    let mut data = Vec::new();
+    (&mut entry_reader).take(SIGNATURE_LENGTH as u64).read_to_end(&mut data).await?;
+
+    let signature = data.as_slice().try_into().map(u32::from_le_bytes);
+    if !matches!(signature, Ok(LFH_SIGNATURE | EOCDR_SIGNATURE)) {
+        return Ok(None);
+    }
+
+    // The recorded size is checked first so that nothing is read needlessly, but the read is capped regardless.
+    if entry_reader.entry().uncompressed_size() <= max_size {
+        let limit = max_size.saturating_add(1).saturating_sub(data.len() as u64);
+        (&mut entry_reader).take(limit).read_to_end(&mut data).await?;
+    }
+
+    if entry_reader.entry().uncompressed_size() > max_size || data.len() as u64 > max_size {
+        log::warn!("skipping nested ZIP file at index {index} as it exceeds {max_size} bytes");
+        return Ok(None);
+    }
+    entry_reader.check_crc()?;
 
-        Ok(reader.into_with_entry(stored_entry))
+    match ZipFileReader::new(data).await {
+        Ok(nested) => Ok(Some(nested)),
+        Err(err) => {
+            log::warn!("skipping nested ZIP file at index {index} as it failed to parse: {err}");
+            Ok(None)
+        }
     }
 }
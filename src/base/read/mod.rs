@@ -5,22 +5,31 @@
 
 pub mod mem;
 pub mod seek;
+pub mod split;
 pub mod stream;
 
 pub(crate) mod io;
+pub(crate) mod options;
+pub(crate) mod repair;
+pub(crate) mod verify;
 
 use crate::ZipString;
 // Re-exported as part of the public API.
 pub use crate::base::read::io::entry::WithEntry;
 pub use crate::base::read::io::entry::WithoutEntry;
 pub use crate::base::read::io::entry::ZipEntryReader;
+pub use crate::base::read::options::ReadOptions;
+pub use crate::base::read::verify::VerifyReport;
 
 use crate::date::ZipDateTime;
 use crate::entry::{StoredZipEntry, ZipEntry};
 use crate::error::{Result, ZipError};
 use crate::file::ZipFile;
 use crate::spec::attribute::AttributeCompatibility;
-use crate::spec::consts::{CDH_SIGNATURE, LFH_SIGNATURE, NON_ZIP64_MAX_SIZE, SIGNATURE_LENGTH, ZIP64_EOCDL_LENGTH};
+use crate::spec::consts::{
+    CDH_LENGTH, CDH_SIGNATURE, EOCDR_LENGTH, LFH_LENGTH, LFH_SIGNATURE, NON_ZIP64_MAX_NUM_FILES, NON_ZIP64_MAX_SIZE,
+    SIGNATURE_LENGTH, ZIP64_EOCDL_LENGTH, ZIP64_EOCDR_LENGTH, ZIP64_EOCDR_SIGNATURE,
+};
 use crate::spec::header::InfoZipUnicodeCommentExtraField;
 use crate::spec::header::InfoZipUnicodePathExtraField;
 use crate::spec::header::{
@@ -34,11 +43,27 @@ use crate::base::read::io::CombinedCentralDirectoryRecord;
 use crate::spec::parse::parse_extra_fields;
 
 use futures_util::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, BufReader, SeekFrom};
+use std::ops::Range;
+use std::pin::Pin;
 
 /// The max buffer size used when parsing the central directory, equal to 20MiB.
 const MAX_CD_BUFFER_SIZE: usize = 20 * 1024 * 1024;
 
-pub(crate) async fn file<R>(mut reader: R) -> Result<ZipFile>
+/// The max number of entries preallocated for when parsing the central directory.
+const MAX_CD_PREALLOCATED_ENTRIES: usize = 1024;
+
+pub(crate) async fn file<R>(reader: R, options: &ReadOptions) -> Result<ZipFile>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    file_in_volumes(reader, options, &[]).await
+}
+
+/// Parses a ZIP file which may be split across multiple volumes, given the offset at which each volume starts within
+/// the reader (which reads the volumes in turn, as per [`split::SplitReader`]).
+///
+/// Unless more than one volume start is provided, the ZIP file is parsed as a single volume.
+pub(crate) async fn file_in_volumes<R>(mut reader: R, options: &ReadOptions, volume_starts: &[u64]) -> Result<ZipFile>
 where
     R: AsyncRead + AsyncSeek + Unpin,
 {
@@ -50,57 +75,257 @@ where
 
     let comment = io::read_string(&mut reader, eocdr.file_comm_length.into(), crate::StringEncoding::Utf8).await?;
 
+    // The comment should run up to the end of the ZIP file, so anything after it wasn't accounted for by the EOCDR.
+    let comment_end = eocdr_offset + EOCDR_LENGTH as u64 + u64::from(eocdr.file_comm_length);
+    let trailing_bytes = reader.seek(SeekFrom::End(0)).await?.saturating_sub(comment_end);
+
     // Check the 20 bytes before the EOCDR for the Zip64 EOCDL, plus an extra 4 bytes because the offset
     // does not include the signature. If the ECODL exists we are dealing with a Zip64 file.
-    let (eocdr, zip64) = match eocdr_offset.checked_sub(ZIP64_EOCDL_LENGTH + SIGNATURE_LENGTH as u64) {
-        None => (CombinedCentralDirectoryRecord::from(&eocdr), false),
+    //
+    // Alongside the record, the actual offset of whichever record immediately follows the central directory is kept.
+    let eocdr_start = eocdr_offset - SIGNATURE_LENGTH as u64;
+    let (eocdr, zip64, directory_end) = match eocdr_start.checked_sub(ZIP64_EOCDL_LENGTH) {
+        None => (CombinedCentralDirectoryRecord::from(&eocdr), false, eocdr_start),
         Some(offset) => {
             reader.seek(SeekFrom::Start(offset)).await?;
             let zip64_locator = Zip64EndOfCentralDirectoryLocator::try_from_reader(&mut reader).await?;
 
             match zip64_locator {
+                Some(_) if options.force_no_zip64 => return Err(ZipError::Zip64Required),
                 Some(locator) => {
-                    reader.seek(SeekFrom::Start(locator.relative_offset + SIGNATURE_LENGTH as u64)).await?;
+                    let zip64_eocdr_start = locate_zip64_eocdr(&mut reader, &locator, offset, volume_starts).await?;
+                    reader.seek(SeekFrom::Start(zip64_eocdr_start + SIGNATURE_LENGTH as u64)).await?;
                     let zip64_eocdr = Zip64EndOfCentralDirectoryRecord::from_reader(&mut reader).await?;
-                    (CombinedCentralDirectoryRecord::combine(eocdr, zip64_eocdr), true)
+                    (CombinedCentralDirectoryRecord::combine(eocdr, zip64_eocdr), true, zip64_eocdr_start)
                 }
-                None => (CombinedCentralDirectoryRecord::from(&eocdr), false),
+                None => (CombinedCentralDirectoryRecord::from(&eocdr), false, eocdr_start),
             }
         }
     };
 
-    // Outdated feature so unlikely to ever make it into this crate.
-    if eocdr.disk_number != eocdr.disk_number_start_of_cd
-        || eocdr.num_entries_in_directory != eocdr.num_entries_in_directory_on_disk
-    {
+    let spanned = eocdr.disk_number != 0
+        || eocdr.disk_number_start_of_cd != 0
+        || eocdr.num_entries_in_directory != eocdr.num_entries_in_directory_on_disk;
+    if spanned && volume_starts.len() < 2 {
         return Err(ZipError::FeatureNotSupported("Spanned/split files"));
     }
 
+    // Offsets are relative to the start of the volume they fall within.
+    let directory_start =
+        volume_start(volume_starts, eocdr.disk_number_start_of_cd)? + eocdr.offset_of_start_of_directory;
+
+    // Any bytes prepended to the ZIP file (eg. a self-extracting stub) shift every record from where the recorded
+    // offsets say it should be, so the shift is found by comparing where the central directory actually ends.
+    let prepended_bytes = directory_end
+        .checked_sub(directory_start)
+        .and_then(|offset| offset.checked_sub(eocdr.directory_size))
+        .unwrap_or(0);
+
+    if options.force_no_zip64
+        && (eocdr.num_entries_in_directory == NON_ZIP64_MAX_NUM_FILES as u64
+            || eocdr.directory_size == NON_ZIP64_MAX_SIZE as u64
+            || eocdr.offset_of_start_of_directory == NON_ZIP64_MAX_SIZE as u64)
+    {
+        return Err(ZipError::Zip64Required);
+    }
+
     // Find and parse the central directory.
-    reader.seek(SeekFrom::Start(eocdr.offset_of_start_of_directory)).await?;
+    reader.seek(SeekFrom::Start(directory_start + prepended_bytes)).await?;
 
     // To avoid lots of small reads to `reader` when parsing the central directory, we use a BufReader that can read the whole central directory at once.
     // Because `eocdr.offset_of_start_of_directory` is a u64, we use MAX_CD_BUFFER_SIZE to prevent very large buffer sizes.
     let buf =
         BufReader::with_capacity(std::cmp::min(eocdr.offset_of_start_of_directory as _, MAX_CD_BUFFER_SIZE), reader);
-    let entries = crate::base::read::cd(buf, eocdr.num_entries_in_directory, zip64).await?;
+    let (mut entries, partial) =
+        crate::base::read::cd(buf, eocdr.directory_size, zip64, options, volume_starts).await?;
+
+    for entry in entries.iter_mut() {
+        entry.file_offset += prepended_bytes;
+    }
+
+    let declared_entry_count = match eocdr.num_entries_in_directory {
+        declared if declared != entries.len() as u64 && !partial => {
+            log::warn!("EOCDR declares {declared} entries but {} were found in the central directory", entries.len());
+            Some(declared)
+        }
+        _ => None,
+    };
+
+    Ok(ZipFile {
+        entries,
+        comment,
+        zip64,
+        central_directory_offset: directory_start,
+        central_directory_size: eocdr.directory_size,
+        prepended_bytes,
+        trailing_bytes,
+        declared_entry_count,
+        partial,
+        name_index: Default::default(),
+        folded_name_index: Default::default(),
+    })
+}
+
+/// Returns the offset at which the volume (disk) with the provided number starts, or zero if not split into volumes.
+fn volume_start(volume_starts: &[u64], disk: u32) -> Result<u64> {
+    if volume_starts.len() < 2 {
+        return Ok(0);
+    }
+
+    volume_starts.get(disk as usize).copied().ok_or(ZipError::MissingVolume(disk))
+}
+
+/// Returns the actual offset of the zip64 EOCDR, given its locator and the locator's actual offset.
+///
+/// The record usually immediately precedes its locator, where it's looked for first so that any bytes prepended to the
+/// ZIP file are accounted for. If it isn't found there (eg. due to an extensible data sector), the locator's recorded
+/// offset is trusted as-is.
+async fn locate_zip64_eocdr<R>(
+    mut reader: R,
+    locator: &Zip64EndOfCentralDirectoryLocator,
+    locator_start: u64,
+    volume_starts: &[u64],
+) -> Result<u64>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    if let Some(offset) = locator_start.checked_sub(ZIP64_EOCDR_LENGTH) {
+        let mut signature = [0; SIGNATURE_LENGTH];
+        reader.seek(SeekFrom::Start(offset)).await?;
+        reader.read_exact(&mut signature).await?;
 
-    Ok(ZipFile { entries, comment, zip64 })
+        if u32::from_le_bytes(signature) == ZIP64_EOCDR_SIGNATURE {
+            return Ok(offset);
+        }
+    }
+
+    let disk = locator.number_of_disk_with_start_of_zip64_end_of_central_directory;
+    Ok(volume_start(volume_starts, disk)? + locator.relative_offset)
+}
+
+/// Reads the raw bytes of a ZIP file's central directory using the offset and size recorded in its EOCDR.
+pub(crate) async fn central_directory_bytes<R>(mut reader: R, file: &ZipFile) -> Result<Vec<u8>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let size = file.central_directory_size;
+    let mut buffer = Vec::with_capacity(size.try_into().map_err(|_| ZipError::TargetZip64NotSupported)?);
+
+    reader.seek(SeekFrom::Start(file.central_directory_offset + file.prepended_bytes)).await?;
+    (&mut reader).take(size).read_to_end(&mut buffer).await?;
+
+    if buffer.len() as u64 != size {
+        return Err(ZipError::UpstreamReadError(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+
+    Ok(buffer)
+}
+
+/// The buffer size used when sweeping over the local file headers of a ZIP file, equal to 64KiB.
+const LFH_SWEEP_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Resolves the absolute range of each entry's compressed data, returned alongside the entries in their original order.
+///
+/// Local file headers are visited in order of their offsets through a single buffered reader, so headers which lie
+/// close together (eg. those of small entries) are served from the same buffered read rather than a seek each.
+pub(crate) async fn entries_with_ranges<R>(reader: R, file: &ZipFile) -> Result<Vec<(ZipEntry, Range<u64>)>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let entries = file.entries();
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&index| entries[index].file_offset);
+
+    let mut reader = BufReader::with_capacity(LFH_SWEEP_BUFFER_SIZE, reader);
+    let mut position = None;
+    let mut ranges = vec![0..0; entries.len()];
+
+    for index in order {
+        let entry = &entries[index];
+        let range = local_data_range(&mut reader, &mut position, entry).await.map_err(|err| err.in_entry(entry))?;
+        ranges[index] = range;
+    }
+
+    Ok(entries.iter().zip(ranges).map(|(entry, range)| (entry.entry.clone(), range)).collect())
 }
 
-pub(crate) async fn cd<R>(mut reader: R, num_of_entries: u64, zip64: bool) -> Result<Vec<StoredZipEntry>>
+/// Reads an entry's local file header from the current sweep position, returning the range of its compressed data.
+async fn local_data_range<R>(
+    reader: &mut BufReader<R>,
+    position: &mut Option<u64>,
+    entry: &StoredZipEntry,
+) -> Result<Range<u64>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let offset = entry.file_offset;
+    match *position {
+        // A relative seek retains the buffered data when the target lies within it.
+        Some(current) => {
+            let delta =
+                i64::try_from(offset as i128 - current as i128).map_err(|_| ZipError::TargetZip64NotSupported)?;
+            Pin::new(&mut *reader).seek_relative(delta).await?;
+        }
+        None => {
+            reader.seek(SeekFrom::Start(offset)).await?;
+        }
+    }
+
+    let signature = {
+        let mut buffer = [0; 4];
+        reader.read_exact(&mut buffer).await?;
+        u32::from_le_bytes(buffer)
+    };
+
+    if signature != LFH_SIGNATURE {
+        return Err(ZipError::UnexpectedHeaderError(signature, LFH_SIGNATURE));
+    }
+
+    let header = LocalFileHeader::from_reader(&mut *reader).await?;
+    let start = offset
+        + (SIGNATURE_LENGTH + LFH_LENGTH) as u64
+        + header.file_name_length as u64
+        + header.extra_field_length as u64;
+    *position = Some(offset + (SIGNATURE_LENGTH + LFH_LENGTH) as u64);
+
+    Ok(start..start + entry.entry.compressed_size())
+}
+
+/// Parses all central directory records found within the provided size of the central directory, up to the maximum
+/// number of entries set within the options.
+///
+/// The declared entry count isn't used here as it may disagree with the records actually present. Alongside the
+/// entries, whether or not any records were left unparsed due to that maximum is returned.
+pub(crate) async fn cd<R>(
+    reader: R,
+    size: u64,
+    zip64: bool,
+    options: &ReadOptions,
+    volume_starts: &[u64],
+) -> Result<(Vec<StoredZipEntry>, bool)>
 where
     R: AsyncRead + Unpin,
 {
-    let num_of_entries = num_of_entries.try_into().map_err(|_| ZipError::TargetZip64NotSupported)?;
-    let mut entries = Vec::with_capacity(num_of_entries);
+    const MIN_RECORD_LENGTH: u64 = (SIGNATURE_LENGTH + CDH_LENGTH) as u64;
+
+    // The declared size is untrusted, so only a bounded number of entries are preallocated for.
+    let max_entries = (size / MIN_RECORD_LENGTH).try_into().unwrap_or(usize::MAX);
+    let max_entries = std::cmp::min(max_entries, options.max_entries.unwrap_or(usize::MAX));
+    let mut entries = Vec::with_capacity(std::cmp::min(max_entries, MAX_CD_PREALLOCATED_ENTRIES));
+    let mut reader = reader.take(size);
+
+    // Any trailing bytes too short to hold a record are ignored.
+    while reader.limit() >= MIN_RECORD_LENGTH {
+        if options.max_entries.is_some_and(|max_entries| entries.len() >= max_entries) {
+            return Ok((entries, true));
+        }
 
-    for _ in 0..num_of_entries {
-        let entry = cd_record(&mut reader, zip64).await?;
+        let mut entry = cd_record(&mut reader, zip64, options, volume_starts).await?;
+        entry.entry.index = entries.len();
         entries.push(entry);
     }
 
-    Ok(entries)
+    Ok((entries, false))
 }
 
 pub(crate) fn get_zip64_extra_field(extra_fields: &[ExtraField]) -> Option<&Zip64ExtendedInformationExtraField> {
@@ -128,30 +353,47 @@ fn get_combined_sizes(
     compressed_size: u32,
     extra_field: &Option<&Zip64ExtendedInformationExtraField>,
 ) -> Result<(u64, u64)> {
-    let mut uncompressed_size = uncompressed_size as u64;
-    let mut compressed_size = compressed_size as u64;
-
-    if let Some(extra_field) = extra_field {
-        if let Some(s) = extra_field.uncompressed_size {
-            uncompressed_size = s;
-        }
-        if let Some(s) = extra_field.compressed_size {
-            compressed_size = s;
-        }
-    }
+    let zip64_uncompressed_size = extra_field.and_then(|field| field.uncompressed_size);
+    let zip64_compressed_size = extra_field.and_then(|field| field.compressed_size);
+
+    // A sentinel value must be accompanied by its ZIP64 counterpart, otherwise we'd read up to 4GiB too much.
+    let uncompressed_size = match (uncompressed_size, zip64_uncompressed_size) {
+        (_, Some(size)) => size,
+        (NON_ZIP64_MAX_SIZE, None) => return Err(ZipError::MissingZip64ExtraField),
+        (size, None) => size as u64,
+    };
+    let compressed_size = match (compressed_size, zip64_compressed_size) {
+        (_, Some(size)) => size,
+        (NON_ZIP64_MAX_SIZE, None) => return Err(ZipError::MissingZip64ExtraField),
+        (size, None) => size as u64,
+    };
 
     Ok((uncompressed_size, compressed_size))
 }
 
-pub(crate) async fn cd_record<R>(mut reader: R, _zip64: bool) -> Result<StoredZipEntry>
+pub(crate) async fn cd_record<R>(
+    mut reader: R,
+    _zip64: bool,
+    options: &ReadOptions,
+    volume_starts: &[u64],
+) -> Result<StoredZipEntry>
 where
     R: AsyncRead + Unpin,
 {
     crate::utils::assert_signature(&mut reader, CDH_SIGNATURE).await?;
 
     let header = CentralDirectoryRecord::from_reader(&mut reader).await?;
+
+    if options.force_no_zip64
+        && (header.uncompressed_size == NON_ZIP64_MAX_SIZE
+            || header.compressed_size == NON_ZIP64_MAX_SIZE
+            || header.lh_offset == NON_ZIP64_MAX_SIZE)
+    {
+        return Err(ZipError::Zip64Required);
+    }
+
     let filename_basic = io::read_bytes(&mut reader, header.file_name_length.into()).await?;
-    let compression = Compression::try_from(header.compression)?;
+    let compression = Compression::from_raw(header.compression);
     let extra_field = io::read_bytes(&mut reader, header.extra_field_length.into()).await?;
     let extra_fields = parse_extra_fields(extra_field, header.uncompressed_size, header.compressed_size)?;
     let comment_basic = io::read_bytes(reader, header.file_comment_length.into()).await?;
@@ -160,17 +402,20 @@ where
     let (uncompressed_size, compressed_size) =
         get_combined_sizes(header.uncompressed_size, header.compressed_size, &zip64_extra_field)?;
 
-    let mut file_offset = header.lh_offset as u64;
-    if let Some(zip64_extra_field) = zip64_extra_field {
-        if file_offset == NON_ZIP64_MAX_SIZE as u64 {
-            if let Some(offset) = zip64_extra_field.relative_header_offset {
-                file_offset = offset;
-            }
-        }
-    }
+    let file_offset = match (header.lh_offset, zip64_extra_field.and_then(|field| field.relative_header_offset)) {
+        (NON_ZIP64_MAX_SIZE, Some(offset)) => offset,
+        (NON_ZIP64_MAX_SIZE, None) => return Err(ZipError::MissingZip64ExtraField),
+        (offset, _) => offset as u64,
+    };
+    let file_offset = volume_start(volume_starts, header.disk_start.into())? + file_offset;
 
     let filename = detect_filename(filename_basic, header.flags.filename_unicode, extra_fields.as_ref());
     let comment = detect_comment(comment_basic, header.flags.filename_unicode, extra_fields.as_ref());
+    #[cfg(feature = "encoding")]
+    let (filename, comment) = match options.fallback_encoding {
+        Some(encoding) => (filename.decode_legacy(encoding), comment.decode_legacy(encoding)),
+        None => (filename, comment),
+    };
 
     let entry = ZipEntry {
         filename,
@@ -184,19 +429,27 @@ where
             feature = "deflate64"
         ))]
         compression_level: async_compression::Level::Default,
-        attribute_compatibility: AttributeCompatibility::Unix,
-        /// FIXME: Default to Unix for the moment
+        attribute_compatibility: AttributeCompatibility::try_from(header.v_made_by >> 8)?,
         crc32: header.crc,
         uncompressed_size,
         compressed_size,
         last_modification_date: ZipDateTime { date: header.mod_date, time: header.mod_time },
+        date_set: true,
         internal_file_attribute: header.inter_attr,
         external_file_attribute: header.exter_attr,
         extra_fields,
         comment,
+        general_purpose_flag: header.flags,
+        alignment: 0,
+        store_if_empty: true,
+        local_extra_fields: None,
+        index: 0,
+        #[cfg(feature = "deflate-dictionary")]
+        deflate_dictionary: None,
+        #[cfg(feature = "aes")]
+        aes_encryption: None,
     };
 
-    // general_purpose_flag: header.flags,
     Ok(StoredZipEntry { entry, file_offset })
 }
 
@@ -215,17 +468,14 @@ where
         actual => return Err(ZipError::UnexpectedHeaderError(actual, LFH_SIGNATURE)),
     };
 
-    let header = LocalFileHeader::from_reader(&mut reader).await?;
-    let filename_basic = io::read_bytes(&mut reader, header.file_name_length.into()).await?;
-    let compression = Compression::try_from(header.compression)?;
-    let extra_field = io::read_bytes(&mut reader, header.extra_field_length.into()).await?;
-    let extra_fields = parse_extra_fields(extra_field, header.uncompressed_size, header.compressed_size)?;
-
-    let zip64_extra_field = get_zip64_extra_field(&extra_fields);
-    let (uncompressed_size, compressed_size) =
-        get_combined_sizes(header.uncompressed_size, header.compressed_size, &zip64_extra_field)?;
+    let (header, entry) = lfh_entry(&mut reader).await?;
 
     if header.flags.data_descriptor {
+        // Without a compressed stream's end marker, there's no way to know where the data of a stored entry ends.
+        if entry.compression == Compression::Stored && entry.compressed_size == 0 {
+            return Err(ZipError::StreamingStoredWithoutSize);
+        }
+
         return Err(ZipError::FeatureNotSupported(
             "stream reading entries with data descriptors (planned to be reintroduced)",
         ));
@@ -234,6 +484,28 @@ where
         return Err(ZipError::FeatureNotSupported("encryption"));
     }
 
+    entry.check_stored_sizes()?;
+
+    Ok(Some(entry))
+}
+
+/// Parses a local file header (following its signature) and its trailing filename and extra field into an entry.
+///
+/// The sizes and CRC32 value are taken as-is, so they'll be zero if the entry was written with a data descriptor.
+pub(crate) async fn lfh_entry<R>(mut reader: R) -> Result<(LocalFileHeader, ZipEntry)>
+where
+    R: AsyncRead + Unpin,
+{
+    let header = LocalFileHeader::from_reader(&mut reader).await?;
+    let filename_basic = io::read_bytes(&mut reader, header.file_name_length.into()).await?;
+    let compression = Compression::from_raw(header.compression);
+    let extra_field = io::read_bytes(&mut reader, header.extra_field_length.into()).await?;
+    let extra_fields = parse_extra_fields(extra_field, header.uncompressed_size, header.compressed_size)?;
+
+    let zip64_extra_field = get_zip64_extra_field(&extra_fields);
+    let (uncompressed_size, compressed_size) =
+        get_combined_sizes(header.uncompressed_size, header.compressed_size, &zip64_extra_field)?;
+
     let filename = detect_filename(filename_basic, header.flags.filename_unicode, extra_fields.as_ref());
 
     let entry = ZipEntry {
@@ -248,19 +520,29 @@ where
             feature = "deflate64"
         ))]
         compression_level: async_compression::Level::Default,
+        // Local file headers don't record the host, though their external file attribute is always zero anyway.
         attribute_compatibility: AttributeCompatibility::Unix,
-        /// FIXME: Default to Unix for the moment
         crc32: header.crc,
         uncompressed_size,
         compressed_size,
         last_modification_date: ZipDateTime { date: header.mod_date, time: header.mod_time },
+        date_set: true,
         internal_file_attribute: 0,
         external_file_attribute: 0,
         extra_fields,
         comment: String::new().into(),
+        general_purpose_flag: header.flags,
+        alignment: 0,
+        store_if_empty: true,
+        local_extra_fields: None,
+        index: 0,
+        #[cfg(feature = "deflate-dictionary")]
+        deflate_dictionary: None,
+        #[cfg(feature = "aes")]
+        aes_encryption: None,
     };
 
-    Ok(Some(entry))
+    Ok((header, entry))
 }
 
 fn detect_comment(basic: Vec<u8>, basic_is_utf8: bool, extra_fields: &[ExtraField]) -> ZipString {
@@ -0,0 +1,150 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+/// A set of options which control how a ZIP file is parsed and read.
+///
+/// These options are passed to a reader's `with_options()` constructor, whereas the plain `new()` constructors use
+/// the defaults.
+///
+/// # Example
+/// ```no_run
+/// # use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+/// # use async_zip::error::Result;
+/// #
+/// # async fn run() -> Result<()> {
+/// let options = ReadOptions::new().force_no_zip64();
+/// let reader = ZipFileReader::with_options(Vec::new(), options).await?;
+/// #   Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ReadOptions {
+    pub(crate) force_no_zip64: bool,
+    pub(crate) trust_zero_crc: bool,
+    #[cfg(feature = "deflate-dictionary")]
+    pub(crate) deflate_dictionary: Option<Vec<u8>>,
+    #[cfg(feature = "zstd")]
+    pub(crate) zstd_max_window_log: Option<u32>,
+    pub(crate) password: Option<Vec<u8>>,
+    pub(crate) max_entries: Option<usize>,
+    pub(crate) read_unknown_sizes_to_end: bool,
+    #[cfg(feature = "encoding")]
+    pub(crate) fallback_encoding: Option<&'static encoding_rs::Encoding>,
+    #[cfg(feature = "tokio-fs")]
+    pub(crate) spill_threshold: Option<u64>,
+}
+
+impl ReadOptions {
+    /// Constructs a new set of options with their default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Force the reader to operate in non-ZIP64 mode.
+    ///
+    /// If the ZIP file contains any ZIP64 records, or any sentinel values which would require them, an error will be
+    /// raised.
+    pub fn force_no_zip64(mut self) -> Self {
+        self.force_no_zip64 = true;
+        self
+    }
+
+    /// Trust the computed CRC32 value of an entry when its stored value is zero.
+    ///
+    /// Some producers write stored entries without ever computing their CRC32 value, leaving it as zero even though
+    /// the data is intact. With this option set, the checked read functions (eg.
+    /// [`ZipEntryReader::read_to_end_checked()`](crate::base::read::ZipEntryReader::read_to_end_checked)) accept such
+    /// entries and log a warning rather than returning [`ZipError::CRC32CheckError`](crate::error::ZipError).
+    pub fn trust_zero_crc(mut self) -> Self {
+        self.trust_zero_crc = true;
+        self
+    }
+
+    /// Set a preset dictionary used when decompressing entries with the Deflate compression method.
+    ///
+    /// This must be the same dictionary provided when writing via
+    /// [`ZipEntryBuilder::deflate_dictionary()`](crate::ZipEntryBuilder::deflate_dictionary). Entries which were
+    /// compressed without a dictionary are still decompressed correctly.
+    #[cfg(feature = "deflate-dictionary")]
+    pub fn deflate_dictionary(mut self, dictionary: Vec<u8>) -> Self {
+        self.deflate_dictionary = Some(dictionary);
+        self
+    }
+
+    /// Set the maximum window size (as a power of two) which entries with the Zstd compression method may require.
+    ///
+    /// Entries requiring a larger window fail to decompress with [`ZipError::WindowTooLarge`], which bounds the memory
+    /// that decompressing an untrusted entry can allocate. This is determined from the header of an entry's first frame
+    /// before any data is decompressed, whereas any later frames exceeding the limit fail with a generic decompression
+    /// error. Without this option, zstd's own default limit of 2^27 bytes (128MiB) applies.
+    ///
+    /// [`ZipError::WindowTooLarge`]: crate::error::ZipError::WindowTooLarge
+    #[cfg(feature = "zstd")]
+    pub fn zstd_max_window_log(mut self, max_window_log: u32) -> Self {
+        self.zstd_max_window_log = Some(max_window_log);
+        self
+    }
+
+    /// Set a password used to decrypt encrypted entries.
+    ///
+    /// The password is applied to all entries opened via a reader's `reader_with_entry()` method, whereas a
+    /// different password can be provided per entry via `reader_with_entry_and_password()` (eg.
+    /// [`seek::ZipFileReader::reader_with_entry_and_password()`](crate::base::read::seek::ZipFileReader::reader_with_entry_and_password)).
+    /// Entries which aren't encrypted are read as normal.
+    pub fn password(mut self, password: Vec<u8>) -> Self {
+        self.password = Some(password);
+        self
+    }
+
+    /// Stop parsing the central directory once the provided number of entries have been read.
+    ///
+    /// This bounds the cost of opening a ZIP file with a very large number of entries when only the first few are
+    /// needed (eg. to preview its contents). If any records were left unparsed, the resulting
+    /// [`ZipFile`](crate::ZipFile) only holds the entries read so far and is flagged via
+    /// [`ZipFile::is_partial()`](crate::ZipFile::is_partial).
+    pub fn max_entries_to_parse(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Decompress entries whose sizes are unknown until their compressed data ends, rather than reading nothing.
+    ///
+    /// Some streaming producers set an entry's data descriptor flag but leave both of its sizes as zero within the
+    /// central directory, even though it holds data. With this option set, such entries are instead decompressed until
+    /// the compression method itself marks the end of their data (eg. Deflate's final block), with the CRC32 value
+    /// still being verified as normal. This doesn't apply to stored or encrypted entries, as the end of their data can't
+    /// be determined without a size.
+    pub fn read_unknown_sizes_to_end(mut self) -> Self {
+        self.read_unknown_sizes_to_end = true;
+        self
+    }
+
+    /// Set the legacy codepage which entries' filenames and comments are decoded from when the UTF-8 flag (bit 11)
+    /// isn't set.
+    ///
+    /// Such names are decoded whilst parsing the central directory, with their raw bytes kept as
+    /// [`ZipString::alternative()`](crate::ZipString::alternative). Without this option, they're left undecoded and
+    /// are interpreted as IBM Code Page 437 (as the specification defines) by
+    /// [`ZipString::as_str_cp437()`](crate::ZipString::as_str_cp437). Names with the flag set, or which carry an Info-ZIP
+    /// Unicode extra field, are unaffected.
+    #[cfg(feature = "encoding")]
+    pub fn fallback_encoding(mut self, encoding: &'static encoding_rs::Encoding) -> Self {
+        self.fallback_encoding = Some(encoding);
+        self
+    }
+
+    /// Decompress entries whose uncompressed size exceeds the provided number of bytes into an anonymous temporary
+    /// file, rather than as they're read.
+    ///
+    /// The entry's data is decompressed and its CRC32 value verified when its reader is opened, after which the reader
+    /// reads the decompressed data back from the temporary file, so no decompressor state is held whilst the data is
+    /// consumed. The file is deleted once the reader is dropped. This only applies to the entry readers of
+    /// [`tokio::read::fs::ZipFileReader`](crate::tokio::read::fs::ZipFileReader).
+    ///
+    /// Note that this requires the `tokio-fs` feature.
+    #[cfg(feature = "tokio-fs")]
+    pub fn spill_threshold(mut self, threshold: u64) -> Self {
+        self.spill_threshold = Some(threshold);
+        self
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! Reconstruction of a ZIP file's information from its local file headers, for when its central directory is missing
+//! or corrupt.
+
+use crate::base::read::get_zip64_extra_field;
+use crate::base::read::io::entry::ZipEntryReader;
+use crate::entry::{StoredZipEntry, ZipEntry};
+use crate::error::{Result, ZipError};
+use crate::file::{builder::ZipFileBuilder, ZipFile};
+use crate::spec::consts::{DATA_DESCRIPTOR_SIGNATURE, LFH_LENGTH, LFH_SIGNATURE, SIGNATURE_LENGTH};
+use crate::spec::Compression;
+
+use futures_util::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, BufReader, SeekFrom};
+use std::pin::Pin;
+
+/// The size of each chunk read whilst scanning an entry's data for its data descriptor, equal to 64KiB.
+const SCAN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The length of a data descriptor with 4-byte sizes, including its signature.
+const DESCRIPTOR_LENGTH: usize = SIGNATURE_LENGTH + 12;
+/// The length of a data descriptor with 8-byte sizes, including its signature.
+const ZIP64_DESCRIPTOR_LENGTH: usize = SIGNATURE_LENGTH + 20;
+
+/// The values recorded within a data descriptor.
+struct DataDescriptor {
+    crc32: u32,
+    compressed_size: u64,
+    /// The uncompressed size, unless the descriptor only held placeholder values.
+    uncompressed_size: Option<u64>,
+}
+
+/// Reconstructs a ZIP file's information by walking the local file headers from the start of the source.
+///
+/// Walking stops at the first record which isn't a local file header (eg. the start of the central directory, or any
+/// corrupt data), or at the end of the source. The sizes and CRC32 value of an entry written with a data descriptor are
+/// taken from that descriptor, which is located by scanning the entry's data where the sizes aren't also recorded
+/// within its local file header.
+pub(crate) async fn repair<R>(reader: R) -> Result<ZipFile>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut entries = Vec::new();
+    let mut zip64 = false;
+    let mut offset = 0;
+
+    reader.seek(SeekFrom::Start(0)).await?;
+
+    while read_signature(&mut reader).await? == Some(LFH_SIGNATURE) {
+        let (header, mut entry) = crate::base::read::lfh_entry(&mut reader).await?;
+        let data_offset = offset
+            + (SIGNATURE_LENGTH + LFH_LENGTH) as u64
+            + header.file_name_length as u64
+            + header.extra_field_length as u64;
+
+        let has_zip64_extra_field = get_zip64_extra_field(&entry.extra_fields).is_some();
+        zip64 |= has_zip64_extra_field;
+
+        // The position of the reader, and the offset at which the next local file header is expected.
+        let (position, next_offset) = if !header.flags.data_descriptor {
+            (data_offset, data_offset + entry.compressed_size)
+        } else if entry.compressed_size == 0 {
+            let (descriptor, descriptor_end, position) =
+                find_descriptor(&mut reader, data_offset, has_zip64_extra_field)
+                    .await
+                    .map_err(|err| err.in_entry(&entry))?;
+            entry.crc32 = descriptor.crc32;
+            entry.compressed_size = descriptor.compressed_size;
+
+            let position = match descriptor.uncompressed_size {
+                Some(uncompressed_size) => {
+                    entry.uncompressed_size = uncompressed_size;
+                    position
+                }
+                None => {
+                    seek_relative(&mut reader, position, data_offset).await?;
+                    let (uncompressed_size, consumed) =
+                        uncompressed_size(&mut reader, &entry).await.map_err(|err| err.in_entry(&entry))?;
+                    entry.uncompressed_size = uncompressed_size;
+                    data_offset + consumed
+                }
+            };
+            (position, descriptor_end)
+        } else {
+            let data_end = data_offset + entry.compressed_size;
+            seek_relative(&mut reader, data_offset, data_end).await?;
+            let (descriptor, length) = read_descriptor(&mut reader, has_zip64_extra_field).await?;
+            entry.crc32 = descriptor.crc32;
+            (data_end + length, data_end + length)
+        };
+
+        entry.index = entries.len();
+        entry.check_stored_sizes().map_err(|err| err.in_entry(&entry))?;
+        entries.push(StoredZipEntry { entry, file_offset: offset });
+
+        seek_relative(&mut reader, position, next_offset).await?;
+        offset = next_offset;
+    }
+
+    let mut file = ZipFileBuilder::new().build();
+    file.entries = entries;
+    file.zip64 = zip64;
+    file.central_directory_offset = offset;
+
+    Ok(file)
+}
+
+/// Determines an entry's uncompressed size from its data (starting at the reader's position), verifying its CRC32
+/// value in the process.
+///
+/// The data of encrypted entries can't be read without a password, so their uncompressed size is left as zero unless
+/// they're stored. Alongside the size, the number of bytes consumed from the reader is returned.
+async fn uncompressed_size<R: AsyncRead + Unpin>(reader: &mut R, entry: &ZipEntry) -> Result<(u64, u64)> {
+    if entry.compression == Compression::Stored {
+        return Ok((entry.compressed_size, 0));
+    } else if entry.general_purpose_flag.encrypted {
+        return Ok((0, 0));
+    }
+
+    let mut data = reader.take(entry.compressed_size);
+    let mut entry_reader = ZipEntryReader::new_with_borrow(BufReader::new(&mut data), entry)?.expect_crc(entry.crc32);
+    let mut buffer = vec![0; SCAN_CHUNK_SIZE];
+    let mut size = 0;
+
+    loop {
+        match entry_reader.read(&mut buffer).await? {
+            0 => break,
+            read => size += read as u64,
+        }
+    }
+
+    drop(entry_reader);
+    Ok((size, entry.compressed_size - data.limit()))
+}
+
+/// Reads the next signature, returning `None` if the end of the source has been reached instead.
+async fn read_signature<R: AsyncRead + Unpin>(mut reader: R) -> Result<Option<u32>> {
+    let mut buffer = [0; SIGNATURE_LENGTH];
+
+    match reader.read_exact(&mut buffer).await {
+        Ok(()) => Ok(Some(u32::from_le_bytes(buffer))),
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Seeks from one offset to another, retaining any buffered data where the target lies within it.
+async fn seek_relative<R: AsyncRead + AsyncSeek + Unpin>(reader: &mut BufReader<R>, from: u64, to: u64) -> Result<()> {
+    let delta = i64::try_from(to as i128 - from as i128).map_err(|_| ZipError::TargetZip64NotSupported)?;
+    Pin::new(reader).seek_relative(delta).await?;
+    Ok(())
+}
+
+/// Reads a data descriptor (whose signature is optional) which immediately follows an entry's data.
+///
+/// Alongside the descriptor, its length in bytes is returned.
+async fn read_descriptor<R: AsyncRead + Unpin>(mut reader: R, zip64: bool) -> Result<(DataDescriptor, u64)> {
+    let length = 4 + 2 * if zip64 { 8 } else { 4 };
+    let mut buffer = [0; 4 + 8 + 8];
+    reader.read_exact(&mut buffer[..SIGNATURE_LENGTH]).await?;
+
+    if buffer[..SIGNATURE_LENGTH] == DATA_DESCRIPTOR_SIGNATURE.to_le_bytes() {
+        reader.read_exact(&mut buffer[..length]).await?;
+        Ok((parse_descriptor(&buffer, zip64), (SIGNATURE_LENGTH + length) as u64))
+    } else {
+        reader.read_exact(&mut buffer[SIGNATURE_LENGTH..length]).await?;
+        Ok((parse_descriptor(&buffer, zip64), length as u64))
+    }
+}
+
+/// Scans an entry's data (starting at the reader's position) for its data descriptor, which is located by its
+/// signature and recorded compressed size matching its own offset from the start of the data.
+///
+/// Descriptors with either 4 or 8-byte sizes are accepted regardless of whether the entry has a zip64 extended
+/// information extra field, as producers disagree. So are those whose sizes are both `0xFFFFFFFF` (as written when
+/// streaming entries of unknown size), in which case the uncompressed size is left unknown. Only descriptors with a
+/// signature can be located. Alongside the descriptor, the offsets of its end and of the reader's resulting position
+/// are returned.
+async fn find_descriptor<R: AsyncRead + Unpin>(
+    mut reader: R,
+    data_offset: u64,
+    zip64: bool,
+) -> Result<(DataDescriptor, u64, u64)> {
+    let signature = DATA_DESCRIPTOR_SIGNATURE.to_le_bytes();
+    let size_lengths = if zip64 { [8, 4] } else { [4, 8] };
+
+    let mut chunk = vec![0; SCAN_CHUNK_SIZE];
+    let mut window = Vec::new();
+    let mut window_offset = data_offset;
+    let mut eof = false;
+
+    while !eof {
+        let read = reader.read(&mut chunk).await?;
+        window.extend_from_slice(&chunk[..read]);
+        eof = read == 0;
+
+        // Until the end of the source, candidates are only checked once the longest descriptor would fit.
+        let required = if eof { DESCRIPTOR_LENGTH } else { ZIP64_DESCRIPTOR_LENGTH };
+        let mut index = 0;
+
+        while index + required <= window.len() {
+            if window[index..index + SIGNATURE_LENGTH] == signature {
+                let offset = window_offset + index as u64;
+                let fields = &window[index + SIGNATURE_LENGTH..];
+
+                for size_length in size_lengths {
+                    let length = SIGNATURE_LENGTH + 4 + 2 * size_length;
+                    if index + length > window.len() {
+                        continue;
+                    }
+
+                    let descriptor = parse_descriptor(fields, size_length == 8);
+                    if descriptor.compressed_size == offset - data_offset {
+                        return Ok((descriptor, offset + length as u64, window_offset + window.len() as u64));
+                    }
+                }
+
+                if fields[4..12] == [0xFF; 8] {
+                    let crc32 = u32::from_le_bytes(fields[0..4].try_into().unwrap());
+                    let descriptor =
+                        DataDescriptor { crc32, compressed_size: offset - data_offset, uncompressed_size: None };
+                    return Ok((descriptor, offset + DESCRIPTOR_LENGTH as u64, window_offset + window.len() as u64));
+                }
+            }
+            index += 1;
+        }
+
+        window.drain(..index);
+        window_offset += index as u64;
+    }
+
+    Err(ZipError::DataDescriptorNotFound)
+}
+
+/// Parses the CRC32 value and sizes of a data descriptor, following its signature.
+fn parse_descriptor(buffer: &[u8], zip64: bool) -> DataDescriptor {
+    let crc32 = u32::from_le_bytes(buffer[0..4].try_into().unwrap());
+
+    let (compressed_size, uncompressed_size) = if zip64 {
+        (u64::from_le_bytes(buffer[4..12].try_into().unwrap()), u64::from_le_bytes(buffer[12..20].try_into().unwrap()))
+    } else {
+        (
+            u32::from_le_bytes(buffer[4..8].try_into().unwrap()).into(),
+            u32::from_le_bytes(buffer[8..12].try_into().unwrap()).into(),
+        )
+    };
+
+    DataDescriptor { crc32, compressed_size, uncompressed_size: Some(uncompressed_size) }
+}
@@ -26,13 +26,19 @@
 //! ```
 
 use crate::base::read::io::entry::ZipEntryReader;
+use crate::base::read::io::owned::OwnedReader;
+use crate::base::read::split::SplitReader;
+use crate::base::read::{ReadOptions, VerifyReport};
+use crate::entry::ZipEntry;
 use crate::error::{Result, ZipError};
 use crate::file::ZipFile;
 
 #[cfg(feature = "tokio")]
 use crate::tokio::read::seek::ZipFileReader as TokioZipFileReader;
 
-use futures_util::io::{AsyncRead, AsyncSeek, BufReader};
+use futures_util::io::{AsyncRead, AsyncReadExt, AsyncSeek, BufReader, Take};
+use std::borrow::Cow;
+use std::ops::Range;
 
 #[cfg(feature = "tokio")]
 use tokio_util::compat::{Compat, TokioAsyncReadCompatExt};
@@ -44,6 +50,7 @@ use super::io::entry::{WithEntry, WithoutEntry};
 pub struct ZipFileReader<R> {
     reader: R,
     file: ZipFile,
+    options: ReadOptions,
 }
 
 impl<R> ZipFileReader<R>
@@ -51,16 +58,32 @@ where
     R: AsyncRead + AsyncSeek + Unpin,
 {
     /// Constructs a new ZIP reader from a seekable source.
-    pub async fn new(mut reader: R) -> Result<ZipFileReader<R>> {
-        let file = crate::base::read::file(&mut reader).await?;
-        Ok(ZipFileReader::from_raw_parts(reader, file))
+    pub async fn new(reader: R) -> Result<ZipFileReader<R>> {
+        Self::with_options(reader, ReadOptions::default()).await
+    }
+
+    /// Constructs a new ZIP reader from a seekable source with the provided options.
+    pub async fn with_options(mut reader: R, options: ReadOptions) -> Result<ZipFileReader<R>> {
+        let file = crate::base::read::file(&mut reader, &options).await?;
+        Ok(ZipFileReader { reader, file, options })
+    }
+
+    /// Constructs a new ZIP reader from a seekable source whose central directory is missing or corrupt.
+    ///
+    /// Rather than parsing the central directory, the entries are reconstructed by walking the local file headers from
+    /// the start of the source, stopping at the first record which isn't one. As such, any information only held within
+    /// the central directory (eg. entry comments and file attributes) is unavailable, and the ZIP file's comment is
+    /// empty. The sizes and CRC32 value of an entry written with a data descriptor are taken from that descriptor.
+    pub async fn repair(mut reader: R) -> Result<ZipFileReader<R>> {
+        let file = crate::base::read::repair::repair(&mut reader).await?;
+        Ok(ZipFileReader { reader, file, options: ReadOptions::default() })
     }
 
     /// Constructs a ZIP reader from a seekable source and ZIP file information derived from that source.
     ///
     /// Providing a [`ZipFile`] that wasn't derived from that source may lead to inaccurate parsing.
     pub fn from_raw_parts(reader: R, file: ZipFile) -> ZipFileReader<R> {
-        ZipFileReader { reader, file }
+        ZipFileReader { reader, file, options: ReadOptions::default() }
     }
 
     /// Returns this ZIP file's information.
@@ -68,6 +91,17 @@ where
         &self.file
     }
 
+    /// Returns an iterator over the decoded names of this ZIP file's entries, as per [`ZipFile::entry_names()`].
+    pub fn entry_names(&self) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+        self.file().entry_names()
+    }
+
+    /// Returns whether or not any bytes follow the end of this ZIP file's comment, as per
+    /// [`ZipFile::has_trailing_data()`].
+    pub fn has_trailing_data(&self) -> bool {
+        self.file().has_trailing_data()
+    }
+
     /// Returns a mutable reference to the inner seekable source.
     ///
     /// Swapping the source (eg. via std::mem operations) may lead to inaccurate parsing.
@@ -80,34 +114,163 @@ where
         self.reader
     }
 
+    /// Returns the raw bytes of this ZIP file's central directory.
+    pub async fn central_directory_bytes(&mut self) -> Result<Vec<u8>> {
+        crate::base::read::central_directory_bytes(&mut self.reader, &self.file).await
+    }
+
+    /// Returns each entry alongside the absolute range of its compressed data within the underlying reader.
+    ///
+    /// Every local file header is read (in order of their offsets) to account for any differences in length from the
+    /// central directory, so this is preferable to calling [`StoredZipEntry::data_offset()`] for each entry.
+    ///
+    /// [`StoredZipEntry::data_offset()`]: crate::StoredZipEntry::data_offset
+    pub async fn entries_with_ranges(&mut self) -> Result<Vec<(ZipEntry, Range<u64>)>> {
+        crate::base::read::entries_with_ranges(&mut self.reader, &self.file).await
+    }
+
     /// Returns a new entry reader if the provided index is valid.
+    ///
+    /// Each entry reader holds its own decompressor and seeks the inner source to the entry's data before reading, so
+    /// entries of differing compression methods may be read in any order, even if a previous read didn't reach EOF.
     pub async fn reader_without_entry(&mut self, index: usize) -> Result<ZipEntryReader<'_, R, WithoutEntry>> {
         let stored_entry = self.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
         let mut reader = BufReader::new(&mut self.reader);
 
         stored_entry.seek_to_data_offset(&mut reader).await?;
 
-        Ok(ZipEntryReader::new_with_borrow(
-            reader,
-            stored_entry.entry.compression(),
-            stored_entry.entry.compressed_size(),
-        ))
+        let reader = ZipEntryReader::new_with_borrow(reader, stored_entry)?;
+
+        Ok(reader.with_options(&self.options).expect_crc(stored_entry.entry.crc32()))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, without verifying the entry's CRC32 value at EOF.
+    ///
+    /// See [`ZipEntryReader::unchecked()`].
+    pub async fn reader_without_entry_unchecked(
+        &mut self,
+        index: usize,
+    ) -> Result<ZipEntryReader<'_, R, WithoutEntry>> {
+        Ok(self.reader_without_entry(index).await?.unchecked())
     }
 
     /// Returns a new entry reader if the provided index is valid.
     pub async fn reader_with_entry(&mut self, index: usize) -> Result<ZipEntryReader<'_, R, WithEntry<'_>>> {
+        if let Some(password) = self.options.password.clone() {
+            return self.reader_with_entry_and_password(index, &password).await;
+        }
+
+        let stored_entry = self.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut reader = BufReader::new(&mut self.reader);
+
+        stored_entry.seek_to_data_offset(&mut reader).await?;
+
+        let reader = ZipEntryReader::new_with_borrow(reader, stored_entry)?;
+
+        Ok(reader.with_options(&self.options).into_with_entry(stored_entry))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, decrypting the entry's data with the provided password.
+    ///
+    /// Both traditional PKWARE (ZipCrypto) and WinZip AES encryption are supported, the latter requiring the `aes`
+    /// feature. The password is checked against the entry's encryption header before the reader is returned, erroring
+    /// with [`ZipError::WrongPassword`] if it's incorrect. The authentication code of AES-encrypted entries is then
+    /// verified once EOF is reached. Entries which aren't encrypted are read as normal.
+    pub async fn reader_with_entry_and_password(
+        &mut self,
+        index: usize,
+        password: &[u8],
+    ) -> Result<ZipEntryReader<'_, R, WithEntry<'_>>> {
+        let stored_entry = self.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut reader = BufReader::new(&mut self.reader);
+
+        stored_entry.seek_to_data_offset(&mut reader).await?;
+
+        let reader = ZipEntryReader::new_with_password(OwnedReader::Borrow(reader), stored_entry, password).await?;
+
+        Ok(reader.with_options(&self.options).into_with_entry(stored_entry))
+    }
+
+    /// Reads the entry at the provided index into a string, as per [`ZipEntryReader::read_to_string_checked()`].
+    ///
+    /// At most `max_bytes` bytes are decompressed, with [`ZipError::EntryTooLarge`] being returned if the entry holds
+    /// any more. Data which isn't valid UTF-8 is rejected with [`ZipError::InvalidUtf8`].
+    pub async fn read_entry_to_string(&mut self, index: usize, max_bytes: u64) -> Result<String> {
+        self.reader_with_entry(index).await?.read_to_string_capped(max_bytes).await
+    }
+
+    /// Verifies that every entry decompresses cleanly and matches its recorded CRC32 value and uncompressed size.
+    ///
+    /// Rather than returning the first error encountered, each entry's outcome is recorded within the returned
+    /// [`VerifyReport`], akin to `unzip -t`. The decompressed data is discarded. Encrypted entries are skipped unless a
+    /// password was provided via [`ReadOptions::password()`].
+    pub async fn verify(&mut self) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+
+        for index in 0..self.file.entries.len() {
+            if report.skip(index, &self.file.entries[index].entry, &self.options) {
+                continue;
+            }
+
+            let result = match self.reader_with_entry(index).await {
+                Ok(mut reader) => reader.verify_checked().await,
+                Err(err) => Err(err),
+            };
+            report.record(index, result);
+        }
+
+        Ok(report)
+    }
+
+    /// Returns a new entry reader for the entry with the provided name, as located by [`ZipFile::entry_index()`].
+    ///
+    /// [`ZipError::EntryNotFound`] is returned if no such entry exists.
+    pub async fn reader_with_entry_by_name(&mut self, name: &str) -> Result<ZipEntryReader<'_, R, WithEntry<'_>>> {
+        let index = self.file.entry_index(name).ok_or_else(|| ZipError::EntryNotFound(name.to_owned()))?;
+        self.reader_with_entry(index).await
+    }
+
+    /// Returns a reader over the compressed (and possibly encrypted) data of the entry at the provided index.
+    ///
+    /// The data is returned exactly as stored, so it can be copied into another ZIP file without being decompressed
+    /// and recompressed via [`ZipFileWriter::copy_entry_raw()`](crate::base::write::ZipFileWriter::copy_entry_raw).
+    pub async fn raw_reader_without_entry(&mut self, index: usize) -> Result<Take<BufReader<&mut R>>> {
         let stored_entry = self.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
         let mut reader = BufReader::new(&mut self.reader);
 
         stored_entry.seek_to_data_offset(&mut reader).await?;
 
-        let reader = ZipEntryReader::new_with_borrow(
-            reader,
-            stored_entry.entry.compression(),
-            stored_entry.entry.compressed_size(),
-        );
+        Ok(reader.take(stored_entry.entry.compressed_size()))
+    }
+
+    /// Returns a new entry reader over the data of the stored entry at the provided index, for random access.
+    ///
+    /// This is [`ZipFileReader::reader_with_entry()`], except that an error is returned upfront if the entry can't be
+    /// seeked within, as it's compressed or encrypted. The returned reader implements [`AsyncSeek`], so arbitrary ranges
+    /// of large uncompressed entries can be read without reading any of the data before them. See
+    /// [`ZipEntryReader`]'s seeking documentation for details.
+    pub async fn stored_reader(&mut self, index: usize) -> Result<ZipEntryReader<'_, R, WithEntry<'_>>> {
+        let stored_entry = self.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        stored_entry.entry.check_random_access()?;
 
-        Ok(reader.into_with_entry(stored_entry))
+        self.reader_with_entry(index).await
+    }
+
+    /// Copies the data of the entry at the provided index into a synchronous writer, verifying its CRC32 value.
+    ///
+    /// Returns the number of bytes copied.
+    ///
+    /// # Runtime
+    /// This function drives the asynchronous read to completion on the current [`tokio`] runtime via
+    /// [`Handle::block_on()`](tokio::runtime::Handle::block_on), and therefore must be called from within the context
+    /// of a runtime but outside of an asynchronous context (eg. from within [`tokio::task::spawn_blocking()`]).
+    /// Calling it from elsewhere will panic.
+    #[cfg(feature = "tokio")]
+    pub fn blocking_copy_entry_to<W: std::io::Write>(&mut self, index: usize, mut writer: W) -> Result<u64> {
+        tokio::runtime::Handle::current().block_on(async {
+            let mut reader = self.reader_with_entry(index).await?;
+            reader.copy_to_sync_checked(&mut writer).await
+        })
     }
 
     /// Returns a new entry reader if the provided index is valid.
@@ -121,11 +284,27 @@ where
 
         stored_entry.seek_to_data_offset(&mut reader).await?;
 
-        Ok(ZipEntryReader::new_with_owned(
-            reader,
-            stored_entry.entry.compression(),
-            stored_entry.entry.compressed_size(),
-        ))
+        let reader = ZipEntryReader::new_with_owned(reader, stored_entry)?;
+
+        Ok(reader.with_options(&self.options).expect_crc(stored_entry.entry.crc32()))
+    }
+}
+
+impl<R> ZipFileReader<SplitReader<R>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Constructs a new ZIP reader from the volumes of a split ZIP file, provided in order (ie. `.z01`, `.z02`, ...,
+    /// with the `.zip` volume last).
+    ///
+    /// The volumes are read as though they were one via a [`SplitReader`], with the offsets recorded relative to each
+    /// volume translated accordingly. A single volume is read as per [`ZipFileReader::new()`].
+    pub async fn new_split(volumes: Vec<R>) -> Result<ZipFileReader<SplitReader<R>>> {
+        let mut reader = SplitReader::new(volumes).await?;
+        let options = ReadOptions::default();
+        let starts = reader.volume_starts().to_vec();
+        let file = crate::base::read::file_in_volumes(&mut reader, &options, &starts).await?;
+        Ok(ZipFileReader { reader, file, options })
     }
 }
 
@@ -137,7 +316,18 @@ where
     /// Constructs a new tokio-specific ZIP reader from a seekable source.
     pub async fn with_tokio(reader: R) -> Result<TokioZipFileReader<R>> {
         let mut reader = reader.compat();
-        let file = crate::base::read::file(&mut reader).await?;
+        let file = crate::base::read::file(&mut reader, &ReadOptions::default()).await?;
         Ok(ZipFileReader::from_raw_parts(reader, file))
     }
 }
+
+#[cfg(feature = "tokio-fs")]
+impl ZipFileReader<Compat<tokio::fs::File>> {
+    /// Constructs a new tokio-specific ZIP reader from an already-opened blocking file.
+    ///
+    /// The file is converted via [`tokio::fs::File::from_std()`], which offloads its blocking operations to tokio's
+    /// blocking thread pool, so this requires a running tokio runtime. The file's current cursor position is ignored.
+    pub async fn new_from_std_file(file: std::fs::File) -> Result<TokioZipFileReader<tokio::fs::File>> {
+        Self::with_tokio(tokio::fs::File::from_std(file)).await
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A reader which joins the volumes of a split ZIP file together.
+//!
+//! ### Example
+//! ```no_run
+//! # use async_zip::base::read::seek::ZipFileReader;
+//! # use async_zip::error::Result;
+//! # use futures_util::io::AsyncReadExt;
+//! # use tokio::fs::File;
+//! # use tokio_util::compat::TokioAsyncReadCompatExt;
+//! #
+//! async fn run() -> Result<()> {
+//!     let mut volumes = Vec::new();
+//!     for path in ["./foo.z01", "./foo.z02", "./foo.zip"] {
+//!         volumes.push(File::open(path).await?.compat());
+//!     }
+//!     let mut reader = ZipFileReader::new_split(volumes).await?;
+//!
+//!     let mut data = Vec::new();
+//!     let mut entry = reader.reader_without_entry(0).await?;
+//!     entry.read_to_end(&mut data).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::io::{Error, ErrorKind, SeekFrom};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::io::{AsyncRead, AsyncSeek, AsyncSeekExt};
+
+use crate::error::Result;
+
+/// A seekable reader over the volumes of a split ZIP file, which reads them in turn as though they were one.
+///
+/// This is constructed by [`ZipFileReader::new_split()`], which translates the offsets recorded relative to each
+/// volume.
+///
+/// [`ZipFileReader::new_split()`]: crate::base::read::seek::ZipFileReader::new_split
+pub struct SplitReader<R> {
+    volumes: Vec<R>,
+    /// The offset at which each volume starts, followed by the total length of all volumes.
+    starts: Vec<u64>,
+    position: u64,
+    /// The volume which is positioned at `position`, if any is.
+    current: Option<usize>,
+}
+
+impl<R> SplitReader<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Constructs a new reader from the volumes of a split ZIP file, provided in order.
+    pub(crate) async fn new(mut volumes: Vec<R>) -> Result<Self> {
+        let mut starts = vec![0];
+        for volume in volumes.iter_mut() {
+            let length = volume.seek(SeekFrom::End(0)).await?;
+            starts.push(starts[starts.len() - 1] + length);
+        }
+
+        Ok(Self { volumes, starts, position: 0, current: None })
+    }
+
+    /// Returns the offset at which each volume starts.
+    pub(crate) fn volume_starts(&self) -> &[u64] {
+        &self.starts[..self.volumes.len()]
+    }
+
+    /// Consumes this reader and returns the inner volumes.
+    pub fn into_inner(self) -> Vec<R> {
+        self.volumes
+    }
+}
+
+impl<R> AsyncRead for SplitReader<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, c: &mut Context<'_>, b: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+
+        // Empty volumes are skipped over by picking the last volume which starts at or before the position.
+        let index = this.starts.partition_point(|start| *start <= this.position).saturating_sub(1);
+        if index >= this.volumes.len() || b.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        if this.current != Some(index) {
+            let relative = SeekFrom::Start(this.position - this.starts[index]);
+            ready!(Pin::new(&mut this.volumes[index]).poll_seek(c, relative))?;
+            this.current = Some(index);
+        }
+
+        let remaining = this.starts[index + 1] - this.position;
+        let length = b.len().min(remaining.try_into().unwrap_or(usize::MAX));
+        let read = ready!(Pin::new(&mut this.volumes[index]).poll_read(c, &mut b[..length]))?;
+        if read == 0 {
+            return Poll::Ready(Err(Error::new(ErrorKind::UnexpectedEof, "volume ended before its original length")));
+        }
+
+        this.position += read as u64;
+        if read as u64 == remaining {
+            this.current = None;
+        }
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl<R> AsyncSeek for SplitReader<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    fn poll_seek(self: Pin<&mut Self>, _: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+        let length = this.starts[this.starts.len() - 1];
+
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => this.position.checked_add_signed(offset),
+        };
+        let Some(position) = position else {
+            return Poll::Ready(Err(Error::new(ErrorKind::InvalidInput, "invalid seek to a negative position")));
+        };
+
+        if position != this.position {
+            this.position = position;
+            this.current = None;
+        }
+
+        Poll::Ready(Ok(position))
+    }
+}
@@ -27,6 +27,10 @@
 //!     - compressed size
 //!     - uncompressed size
 //!
+//! Where the underlying source is also seekable, the entries discovered via the local file headers can be cross-checked
+//! against the central directory once reading has finished via [`ZipFileReader::finish_verified()`] (or
+//! [`verify_against_central_directory()`] where the source is borrowed).
+//!
 //! # Example
 //! ```no_run
 //! # use futures_util::io::Cursor;
@@ -46,16 +50,20 @@
 //! # }
 //! ```
 
-use crate::base::read::io::entry::ZipEntryReader;
+use crate::base::read::io::entry::{EntryState, ZipEntryReader};
+use crate::base::read::ReadOptions;
+use crate::entry::ZipEntry;
 use crate::error::Result;
 use crate::error::ZipError;
 
+use std::collections::{HashMap, VecDeque};
+
 #[cfg(feature = "tokio")]
 use crate::tokio::read::stream::Ready as TokioReady;
 
 use futures_util::io::AsyncReadExt;
 use futures_util::io::Take;
-use futures_util::io::{AsyncRead, BufReader};
+use futures_util::io::{AsyncRead, AsyncSeek, BufReader};
 
 #[cfg(feature = "tokio")]
 use tokio_util::compat::TokioAsyncReadCompatExt;
@@ -64,10 +72,12 @@ use super::io::entry::WithEntry;
 use super::io::entry::WithoutEntry;
 
 /// A type which encodes that [`ZipFileReader`] is ready to open a new entry.
-pub struct Ready<R>(R);
+///
+/// The entries opened so far are recorded alongside the source for [`ZipFileReader::finish_verified()`].
+pub struct Ready<R>(R, Vec<ZipEntry>);
 
 /// A type which encodes that [`ZipFileReader`] is currently reading an entry.
-pub struct Reading<'a, R, E>(ZipEntryReader<'a, R, E>);
+pub struct Reading<'a, R, E>(ZipEntryReader<'a, R, E>, Vec<ZipEntry>);
 
 /// A ZIP reader which acts over a non-seekable source.
 ///
@@ -81,7 +91,7 @@ where
 {
     /// Constructs a new ZIP reader from a non-seekable source.
     pub fn new(reader: R) -> Self {
-        Self(Ready(reader))
+        Self(Ready(reader, Vec::new()))
     }
 
     /// Opens the next entry for reading if the central directory hasn’t yet been reached.
@@ -92,9 +102,12 @@ where
         };
 
         let reader = BufReader::new(self.0 .0.take(entry.compressed_size));
-        let reader = ZipEntryReader::new_with_owned(reader, entry.compression, entry.compressed_size);
+        let reader = ZipEntryReader::new_with_owned(reader, &entry)?;
+
+        let reader = reader.expect_crc(entry.crc32);
+        self.0 .1.push(entry);
 
-        Ok(Some(ZipFileReader(Reading(reader))))
+        Ok(Some(ZipFileReader(Reading(reader, self.0 .1))))
     }
 
     /// Opens the next entry for reading if the central directory hasn’t yet been reached.
@@ -105,9 +118,11 @@ where
         };
 
         let reader = BufReader::new(self.0 .0.take(entry.compressed_size));
-        let reader = ZipEntryReader::new_with_owned(reader, entry.compression, entry.compressed_size);
+        let reader = ZipEntryReader::new_with_owned(reader, &entry)?;
 
-        Ok(Some(ZipFileReader(Reading(reader.into_with_entry_owned(entry)))))
+        self.0 .1.push(entry.clone());
+
+        Ok(Some(ZipFileReader(Reading(reader.into_with_entry_owned(entry), self.0 .1))))
     }
 
     /// Consumes the `ZipFileReader` returning the original `reader`
@@ -116,6 +131,33 @@ where
     }
 }
 
+impl<R> ZipFileReader<Ready<R>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Reads past any remaining entries, then cross-checks every entry against the central directory.
+    ///
+    /// Each entry opened by this reader (and any read past here) is checked as per
+    /// [`verify_against_central_directory()`], so this should be called before
+    /// [`next_with_entry()`](Self::next_with_entry) or [`next_without_entry()`](Self::next_without_entry) reach the
+    /// central directory. The source is returned once the
+    /// entries have been verified.
+    pub async fn finish_verified(mut self) -> Result<R> {
+        while let Some(entry) = crate::base::read::lfh(&mut self.0 .0).await? {
+            let mut data = (&mut self.0 .0).take(entry.compressed_size);
+            futures_util::io::copy(&mut data, &mut futures_util::io::sink()).await?;
+            if data.limit() != 0 {
+                return Err(ZipError::UpstreamReadError(std::io::ErrorKind::UnexpectedEof.into()));
+            }
+
+            self.0 .1.push(entry);
+        }
+
+        verify_against_central_directory(&mut self.0 .0, &self.0 .1).await?;
+        Ok(self.0 .0)
+    }
+}
+
 #[cfg(feature = "tokio")]
 impl<R> ZipFileReader<TokioReady<R>>
 where
@@ -123,13 +165,14 @@ where
 {
     /// Constructs a new tokio-specific ZIP reader from a non-seekable source.
     pub fn with_tokio(reader: R) -> ZipFileReader<TokioReady<R>> {
-        Self(Ready(reader.compat()))
+        Self(Ready(reader.compat(), Vec::new()))
     }
 }
 
 impl<'a, R, E> ZipFileReader<Reading<'a, Take<R>, E>>
 where
     R: AsyncRead + Unpin,
+    E: EntryState,
 {
     /// Returns an immutable reference to the inner entry reader.
     pub fn reader(&self) -> &ZipEntryReader<'a, Take<R>, E> {
@@ -147,12 +190,78 @@ where
             return Err(ZipError::EOFNotReached);
         }
 
-        Ok(ZipFileReader(Ready(self.0 .0.into_inner().into_inner())))
+        Ok(ZipFileReader(Ready(self.0 .0.into_inner().into_inner(), self.0 .1)))
     }
 
     /// Reads until EOF and converts the reader back into the Ready state.
-    pub async fn skip(mut self) -> Result<ZipFileReader<Ready<R>>> {
-        while self.0 .0.read(&mut [0; 2048]).await? != 0 {}
-        Ok(ZipFileReader(Ready(self.0 .0.into_inner().into_inner())))
+    pub async fn skip(self) -> Result<ZipFileReader<Ready<R>>> {
+        let mut reader = self.0 .0.unchecked();
+        while reader.read(&mut [0; 2048]).await? != 0 {}
+        Ok(ZipFileReader(Ready(reader.into_inner().into_inner(), self.0 .1)))
+    }
+}
+
+/// Cross-checks entries discovered by a streaming read against the central directory of the same seekable source.
+///
+/// Entries are matched by filename, and must agree on their compression method, CRC32 value, and sizes. Zeroed CRC32
+/// values and sizes within a local file header are only accepted where they're deferred to a data descriptor. The
+/// central directory must also list exactly the same set of entries. This catches ZIP files whose local file headers
+/// have been tampered with so that they disagree with the central directory, which other readers rely upon.
+///
+/// As the stream reader takes ownership of its source, a mutable reference to the source should be provided to it so
+/// that the source can be reused here.
+///
+/// # Example
+/// ```no_run
+/// # use futures_util::io::Cursor;
+/// # use async_zip::error::Result;
+/// # use async_zip::base::read::stream::{verify_against_central_directory, ZipFileReader};
+/// #
+/// # async fn run() -> Result<()> {
+/// let mut source = Cursor::new(Vec::new());
+/// let mut zip = ZipFileReader::new(&mut source);
+/// let mut entries = Vec::new();
+///
+/// while let Some(entry) = zip.next_with_entry().await? {
+///     entries.push(entry.reader().entry().clone());
+///     zip = entry.skip().await?;
+/// }
+///
+/// verify_against_central_directory(&mut source, &entries).await?;
+/// #     Ok(())
+/// # }
+/// ```
+pub async fn verify_against_central_directory<R>(reader: R, entries: &[ZipEntry]) -> Result<()>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let file = crate::base::read::file(reader, &ReadOptions::default()).await?;
+
+    let mut central: HashMap<&[u8], VecDeque<&ZipEntry>> = HashMap::new();
+    for stored_entry in file.entries() {
+        central.entry(stored_entry.filename().as_bytes()).or_default().push_back(stored_entry);
     }
+
+    for (index, local) in entries.iter().enumerate() {
+        let central = central.get_mut(local.filename().as_bytes()).and_then(VecDeque::pop_front);
+        let matches = central.is_some_and(|central| {
+            let deferred = local.general_purpose_flag.data_descriptor;
+            let known = |local: u64, central: u64| local == central || (deferred && local == 0);
+
+            local.compression() == central.compression()
+                && known(local.crc32().into(), central.crc32().into())
+                && known(local.compressed_size(), central.compressed_size())
+                && known(local.uncompressed_size(), central.uncompressed_size())
+        });
+
+        if !matches {
+            return Err(ZipError::CentralDirectoryMismatch(index));
+        }
+    }
+
+    if file.entries().len() != entries.len() {
+        return Err(ZipError::CentralDirectoryMismatch(entries.len()));
+    }
+
+    Ok(())
 }
@@ -0,0 +1,61 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::base::read::ReadOptions;
+use crate::entry::ZipEntry;
+use crate::error::{Result, ZipError};
+
+/// The outcome of verifying every entry of a ZIP file, as returned by a reader's `verify()` method (eg.
+/// [`seek::ZipFileReader::verify()`](crate::base::read::seek::ZipFileReader::verify)).
+///
+/// Each entry is either verified, failed (alongside the reason why), or skipped as it's encrypted and no password was
+/// provided via [`ReadOptions::password()`].
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    verified: Vec<usize>,
+    failed: Vec<(usize, ZipError)>,
+    skipped: Vec<usize>,
+}
+
+impl VerifyReport {
+    /// Returns whether or not no entries failed verification.
+    ///
+    /// Skipped entries don't count as failures.
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Returns the indices of the entries which decompressed cleanly and matched their CRC32 value and size.
+    pub fn verified(&self) -> &[usize] {
+        &self.verified
+    }
+
+    /// Returns the indices of the entries which failed verification, alongside the reason why.
+    pub fn failed(&self) -> &[(usize, ZipError)] {
+        &self.failed
+    }
+
+    /// Returns the indices of the encrypted entries which were skipped as no password was provided.
+    pub fn skipped(&self) -> &[usize] {
+        &self.skipped
+    }
+
+    /// Returns whether or not the entry should be skipped as it's encrypted and no password was provided, recording it
+    /// as such.
+    pub(crate) fn skip(&mut self, index: usize, entry: &ZipEntry, options: &ReadOptions) -> bool {
+        let skip = entry.is_encrypted() && options.password.is_none();
+        if skip {
+            self.skipped.push(index);
+        }
+
+        skip
+    }
+
+    /// Records the result of verifying the entry at the provided index.
+    pub(crate) fn record(&mut self, index: usize, result: Result<u64>) {
+        match result {
+            Ok(_) => self.verified.push(index),
+            Err(err) => self.failed.push((index, err)),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
+use crate::base::write::alignment_extra_field;
 use crate::base::write::compressed_writer::CompressedAsyncWriter;
 use crate::base::write::get_or_put_info_zip_unicode_comment_extra_field_mut;
 use crate::base::write::get_or_put_info_zip_unicode_path_extra_field_mut;
@@ -16,6 +17,7 @@ use crate::spec::header::{
     CentralDirectoryRecord, ExtraField, GeneralPurposeFlag, HeaderId, LocalFileHeader,
     Zip64ExtendedInformationExtraField,
 };
+use crate::spec::Compression;
 use crate::string::StringEncoding;
 
 use std::io::Error;
@@ -23,7 +25,9 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use crate::base::read::get_zip64_extra_field_mut;
-use crate::spec::consts::{NON_ZIP64_MAX_NUM_FILES, NON_ZIP64_MAX_SIZE};
+use crate::spec::consts::{
+    LFH_LENGTH, LZMA_COMPRESSION_METHOD, NON_ZIP64_MAX_NUM_FILES, NON_ZIP64_MAX_SIZE, SIGNATURE_LENGTH,
+};
 use crc32fast::Hasher;
 use futures_util::io::{AsyncWrite, AsyncWriteExt};
 
@@ -36,6 +40,8 @@ use futures_util::io::{AsyncWrite, AsyncWriteExt};
 pub struct EntryStreamWriter<'b, W: AsyncWrite + Unpin> {
     writer: AsyncOffsetWriter<CompressedAsyncWriter<'b, W>>,
     cd_entries: &'b mut Vec<CentralDirectoryEntry>,
+    /// The number of entries whose central directory records have already been spilled.
+    spilled_entries: usize,
     entry: ZipEntry,
     hasher: Hasher,
     lfh: LocalFileHeader,
@@ -51,18 +57,34 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
         writer: &'b mut ZipFileWriter<W>,
         mut entry: ZipEntry,
     ) -> Result<EntryStreamWriter<'b, W>> {
+        if let Compression::Unsupported(value) = entry.compression() {
+            return Err(ZipError::CompressionNotSupported(value));
+        }
+
+        #[cfg(feature = "deflate-dictionary")]
+        if entry.compression() == Compression::Deflate && entry.deflate_dictionary.is_some() {
+            return Err(ZipError::FeatureNotSupported("Streaming entries with a deflate dictionary"));
+        }
+
+        #[cfg(feature = "aes")]
+        if entry.aes_encryption.is_some() {
+            return Err(ZipError::FeatureNotSupported("Streaming entries with AES encryption"));
+        }
+
         let lfh_offset = writer.writer.offset();
         let lfh = EntryStreamWriter::write_lfh(writer, &mut entry).await?;
         let data_offset = writer.writer.offset();
         let force_no_zip64 = writer.force_no_zip64;
 
+        let spilled_entries = writer.num_entries() - writer.cd_entries.len();
         let cd_entries = &mut writer.cd_entries;
         let is_zip64 = &mut writer.is_zip64;
-        let writer = AsyncOffsetWriter::new(CompressedAsyncWriter::from_raw(&mut writer.writer, entry.compression()));
+        let writer = AsyncOffsetWriter::new(CompressedAsyncWriter::from_raw(&mut writer.writer, &entry));
 
         Ok(EntryStreamWriter {
             writer,
             cd_entries,
+            spilled_entries,
             entry,
             lfh,
             lfh_offset,
@@ -130,16 +152,18 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
 
         let filename_basic = entry.filename().alternative().unwrap_or_else(|| entry.filename().as_bytes());
 
+        // Any alignment padding is only written to the local file header's extra field.
+        let mut lfh_extra_field = (&*entry.local_extra_fields()).as_bytes();
+        let unpadded_data_offset =
+            writer.writer.offset() + SIGNATURE_LENGTH + LFH_LENGTH + filename_basic.len() + lfh_extra_field.len();
+        lfh_extra_field.extend(alignment_extra_field(unpadded_data_offset, entry.alignment));
+
         let lfh = LocalFileHeader {
             compressed_size: lfh_compressed,
             uncompressed_size: lfh_uncompressed,
             compression: entry.compression().into(),
             crc: entry.crc32,
-            extra_field_length: entry
-                .extra_fields()
-                .count_bytes()
-                .try_into()
-                .map_err(|_| ZipError::ExtraFieldTooLarge)?,
+            extra_field_length: lfh_extra_field.len().try_into().map_err(|_| ZipError::ExtraFieldTooLarge)?,
             file_name_length: filename_basic.len().try_into().map_err(|_| ZipError::FileNameTooLarge)?,
             mod_time: entry.last_modification_date().time,
             mod_date: entry.last_modification_date().date,
@@ -147,14 +171,18 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
             flags: GeneralPurposeFlag {
                 data_descriptor: true,
                 encrypted: false,
+                lzma_eos_marker: u16::from(entry.compression()) == LZMA_COMPRESSION_METHOD
+                    || entry.general_purpose_flag.lzma_eos_marker,
                 filename_unicode: utf8_without_alternative,
+                other: entry.general_purpose_flag.other,
             },
         };
 
+        writer.begin_record(SIGNATURE_LENGTH + LFH_LENGTH + filename_basic.len() + lfh_extra_field.len());
         writer.writer.write_all(&crate::spec::consts::LFH_SIGNATURE.to_le_bytes()).await?;
         writer.writer.write_all(&lfh.as_slice()).await?;
         writer.writer.write_all(filename_basic).await?;
-        writer.writer.write_all(&entry.extra_fields().as_bytes()).await?;
+        writer.writer.write_all(&lfh_extra_field).await?;
 
         Ok(lfh)
     }
@@ -196,8 +224,6 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
                             disk_start_number: None,
                         },
                     ));
-                    self.lfh.extra_field_length =
-                        self.entry.extra_fields().count_bytes().try_into().map_err(|_| ZipError::ExtraFieldTooLarge)?;
                 }
                 Some(zip64) => {
                     zip64.uncompressed_size = Some(uncompressed_size);
@@ -219,10 +245,15 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
             compressed_size: cdr_compressed_size,
             uncompressed_size: cdr_uncompressed_size,
             crc,
-            v_made_by: crate::spec::version::as_made_by(),
+            v_made_by: crate::spec::version::as_made_by(self.entry.attribute_compatibility),
             v_needed: self.lfh.version,
             compression: self.lfh.compression,
-            extra_field_length: self.lfh.extra_field_length,
+            extra_field_length: self
+                .entry
+                .extra_fields()
+                .count_bytes()
+                .try_into()
+                .map_err(|_| ZipError::ExtraFieldTooLarge)?,
             file_name_length: self.lfh.file_name_length,
             file_comment_length: comment_basic.len().try_into().map_err(|_| ZipError::CommentTooLarge)?,
             mod_time: self.lfh.mod_time,
@@ -236,7 +267,7 @@ impl<'b, W: AsyncWrite + Unpin> EntryStreamWriter<'b, W> {
 
         self.cd_entries.push(CentralDirectoryEntry { header: cdh, entry: self.entry });
         // Ensure that we can fit this many files in this archive if forcing no zip64
-        if self.cd_entries.len() > NON_ZIP64_MAX_NUM_FILES as usize {
+        if self.spilled_entries + self.cd_entries.len() > NON_ZIP64_MAX_NUM_FILES as usize {
             if self.force_no_zip64 {
                 return Err(ZipError::Zip64Needed(Zip64ErrorCase::TooManyFiles));
             }
@@ -3,7 +3,11 @@
 
 use crate::base::write::get_or_put_info_zip_unicode_comment_extra_field_mut;
 use crate::base::write::get_or_put_info_zip_unicode_path_extra_field_mut;
-use crate::base::write::{CentralDirectoryEntry, ZipFileWriter};
+#[cfg(feature = "lzma")]
+use crate::base::write::io::lzma::LzmaHeaderWriter;
+use crate::base::write::{alignment_extra_field, CentralDirectoryEntry, ZipFileWriter};
+#[cfg(feature = "aes")]
+use crate::encryption::aes::{AesExtraField, AesVendorVersion};
 use crate::entry::ZipEntry;
 use crate::error::{Result, Zip64ErrorCase, ZipError};
 use crate::spec::extra_field::Zip64ExtendedInformationExtraFieldBuilder;
@@ -17,10 +21,13 @@ use crate::StringEncoding;
 #[cfg(any(feature = "deflate", feature = "bzip2", feature = "zstd", feature = "lzma", feature = "xz"))]
 use futures_util::io::Cursor;
 
-use crate::spec::consts::{NON_ZIP64_MAX_NUM_FILES, NON_ZIP64_MAX_SIZE};
+use crate::spec::consts::{
+    AES_COMPRESSION_METHOD, LFH_LENGTH, LZMA_COMPRESSION_METHOD, NON_ZIP64_MAX_NUM_FILES, NON_ZIP64_MAX_SIZE,
+    SIGNATURE_LENGTH,
+};
 #[cfg(any(feature = "deflate", feature = "bzip2", feature = "zstd", feature = "lzma", feature = "xz"))]
 use async_compression::futures::write;
-use futures_util::io::{AsyncWrite, AsyncWriteExt};
+use futures_util::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 pub struct EntryWholeWriter<'b, 'c, W: AsyncWrite + Unpin> {
     writer: &'b mut ZipFileWriter<W>,
@@ -34,9 +41,23 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
     }
 
     pub async fn write(mut self) -> Result<()> {
+        if self.entry.store_if_empty && self.data.is_empty() {
+            self.entry.compression = Compression::Stored;
+        }
+
+        // The encoder doesn't record the uncompressed size within LZMA data, so always terminates it with a marker.
+        self.entry.general_purpose_flag.lzma_eos_marker = u16::from(self.entry.compression) == LZMA_COMPRESSION_METHOD;
+
         let mut _compressed_data: Option<Vec<u8>> = None;
         let compressed_data = match self.entry.compression() {
             Compression::Stored => self.data,
+            Compression::Unsupported(value) => return Err(ZipError::CompressionNotSupported(value)),
+            #[cfg(feature = "deflate-dictionary")]
+            Compression::Deflate if self.entry.deflate_dictionary.is_some() => {
+                let dictionary = self.entry.deflate_dictionary.as_deref().unwrap_or_default();
+                _compressed_data = Some(compress_with_dictionary(self.data, dictionary, self.entry.compression_level));
+                _compressed_data.as_ref().unwrap()
+            }
             #[cfg(any(
                 feature = "deflate",
                 feature = "bzip2",
@@ -52,26 +73,80 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
             }
         };
 
-        let mut zip64_extra_field_builder = None;
+        #[cfg(feature = "aes")]
+        let _encrypted_data;
+        #[cfg(feature = "aes")]
+        let compressed_data = match self.entry.aes_encryption.take() {
+            Some(encryption) => {
+                let field = AesExtraField {
+                    version: AesVendorVersion::Ae2,
+                    strength: encryption.strength,
+                    compression: self.entry.compression,
+                };
 
-        let (lfh_uncompressed_size, lfh_compressed_size) = if self.data.len() as u64 > NON_ZIP64_MAX_SIZE as u64
-            || compressed_data.len() as u64 > NON_ZIP64_MAX_SIZE as u64
-        {
-            if self.writer.force_no_zip64 {
-                return Err(ZipError::Zip64Needed(Zip64ErrorCase::LargeFile));
-            }
-            if !self.writer.is_zip64 {
-                self.writer.is_zip64 = true;
+                self.entry.extra_fields.push(field.as_extra_field());
+                self.entry.compression = Compression::Unsupported(AES_COMPRESSION_METHOD);
+
+                _encrypted_data = crate::encryption::aes::encrypt(compressed_data, &encryption)?;
+                &_encrypted_data
             }
-            zip64_extra_field_builder = Some(
-                Zip64ExtendedInformationExtraFieldBuilder::new()
-                    .sizes(compressed_data.len() as u64, self.data.len() as u64),
-            );
-            (NON_ZIP64_MAX_SIZE, NON_ZIP64_MAX_SIZE)
-        } else {
-            (self.data.len() as u32, compressed_data.len() as u32)
+            None => compressed_data,
         };
 
+        // AE-2 entries record a zero CRC32 value, relying upon the authentication code of their encrypted data instead.
+        let crc = match self.entry.compression() {
+            Compression::Unsupported(AES_COMPRESSION_METHOD) => 0,
+            _ => crc32fast::hash(self.data),
+        };
+        let (header, zip64_extra_field_builder) =
+            self.write_local_header(crc, compressed_data.len() as u64, self.data.len() as u64).await?;
+        self.writer.writer.write_all(compressed_data).await?;
+
+        self.finish(header, zip64_extra_field_builder)
+    }
+
+    /// Writes an entry whose data has already been compressed, copying it verbatim from the provided reader.
+    ///
+    /// The entry's compression method, CRC32 value, and sizes must already describe the data, and exactly the entry's
+    /// compressed size is copied from the reader.
+    pub async fn write_raw<R: AsyncRead + Unpin>(mut self, reader: R) -> Result<()> {
+        let (crc, compressed_size, uncompressed_size) =
+            (self.entry.crc32, self.entry.compressed_size, self.entry.uncompressed_size);
+        let (header, zip64_extra_field_builder) =
+            self.write_local_header(crc, compressed_size, uncompressed_size).await?;
+
+        let copied = futures_util::io::copy(reader.take(compressed_size), &mut self.writer.writer).await?;
+        if copied != compressed_size {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+
+        self.finish(header, zip64_extra_field_builder)
+    }
+
+    /// Writes the entry's local file header, returning its pending central directory record.
+    async fn write_local_header(
+        &mut self,
+        crc: u32,
+        compressed_size: u64,
+        uncompressed_size: u64,
+    ) -> Result<(CentralDirectoryRecord, Option<Zip64ExtendedInformationExtraFieldBuilder>)> {
+        let mut zip64_extra_field_builder = None;
+
+        let (lfh_uncompressed_size, lfh_compressed_size) =
+            if uncompressed_size > NON_ZIP64_MAX_SIZE as u64 || compressed_size > NON_ZIP64_MAX_SIZE as u64 {
+                if self.writer.force_no_zip64 {
+                    return Err(ZipError::Zip64Needed(Zip64ErrorCase::LargeFile));
+                }
+                if !self.writer.is_zip64 {
+                    self.writer.is_zip64 = true;
+                }
+                zip64_extra_field_builder =
+                    Some(Zip64ExtendedInformationExtraFieldBuilder::new().sizes(compressed_size, uncompressed_size));
+                (NON_ZIP64_MAX_SIZE, NON_ZIP64_MAX_SIZE)
+            } else {
+                (uncompressed_size as u32, compressed_size as u32)
+            };
+
         let lh_offset = if self.writer.writer.offset() > NON_ZIP64_MAX_SIZE as usize {
             if self.writer.force_no_zip64 {
                 return Err(ZipError::Zip64Needed(Zip64ErrorCase::LargeFile));
@@ -139,36 +214,44 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
         let filename_basic = self.entry.filename().alternative().unwrap_or_else(|| self.entry.filename().as_bytes());
         let comment_basic = self.entry.comment().alternative().unwrap_or_else(|| self.entry.comment().as_bytes());
 
+        // Any alignment padding is only written to the local file header's extra field.
+        let mut lfh_extra_field = (&*self.entry.local_extra_fields()).as_bytes();
+        let unpadded_data_offset =
+            self.writer.writer.offset() + SIGNATURE_LENGTH + LFH_LENGTH + filename_basic.len() + lfh_extra_field.len();
+        lfh_extra_field.extend(alignment_extra_field(unpadded_data_offset, self.entry.alignment));
+
         let lf_header = LocalFileHeader {
             compressed_size: lfh_compressed_size,
             uncompressed_size: lfh_uncompressed_size,
             compression: self.entry.compression().into(),
-            crc: crc32fast::hash(self.data),
-            extra_field_length: self
-                .entry
-                .extra_fields()
-                .count_bytes()
-                .try_into()
-                .map_err(|_| ZipError::ExtraFieldTooLarge)?,
+            crc,
+            extra_field_length: lfh_extra_field.len().try_into().map_err(|_| ZipError::ExtraFieldTooLarge)?,
             file_name_length: filename_basic.len().try_into().map_err(|_| ZipError::FileNameTooLarge)?,
             mod_time: self.entry.last_modification_date().time,
             mod_date: self.entry.last_modification_date().date,
             version: crate::spec::version::as_needed_to_extract(&self.entry),
             flags: GeneralPurposeFlag {
                 data_descriptor: false,
-                encrypted: false,
+                encrypted: self.entry.compression() == Compression::Unsupported(AES_COMPRESSION_METHOD),
+                lzma_eos_marker: self.entry.general_purpose_flag.lzma_eos_marker,
                 filename_unicode: utf8_without_alternative,
+                other: self.entry.general_purpose_flag.other,
             },
         };
 
-        let mut header = CentralDirectoryRecord {
-            v_made_by: crate::spec::version::as_made_by(),
+        let header = CentralDirectoryRecord {
+            v_made_by: crate::spec::version::as_made_by(self.entry.attribute_compatibility),
             v_needed: lf_header.version,
             compressed_size: lf_header.compressed_size,
             uncompressed_size: lf_header.uncompressed_size,
             compression: lf_header.compression,
             crc: lf_header.crc,
-            extra_field_length: lf_header.extra_field_length,
+            extra_field_length: self
+                .entry
+                .extra_fields()
+                .count_bytes()
+                .try_into()
+                .map_err(|_| ZipError::ExtraFieldTooLarge)?,
             file_name_length: lf_header.file_name_length,
             file_comment_length: comment_basic.len().try_into().map_err(|_| ZipError::CommentTooLarge)?,
             mod_time: lf_header.mod_time,
@@ -180,12 +263,21 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
             lh_offset,
         };
 
+        self.writer.begin_record(SIGNATURE_LENGTH + LFH_LENGTH + filename_basic.len() + lfh_extra_field.len());
         self.writer.writer.write_all(&crate::spec::consts::LFH_SIGNATURE.to_le_bytes()).await?;
         self.writer.writer.write_all(&lf_header.as_slice()).await?;
         self.writer.writer.write_all(filename_basic).await?;
-        self.writer.writer.write_all(&self.entry.extra_fields().as_bytes()).await?;
-        self.writer.writer.write_all(compressed_data).await?;
+        self.writer.writer.write_all(&lfh_extra_field).await?;
 
+        Ok((header, zip64_extra_field_builder))
+    }
+
+    /// Records the entry's central directory record once its data has been written.
+    fn finish(
+        mut self,
+        mut header: CentralDirectoryRecord,
+        zip64_extra_field_builder: Option<Zip64ExtendedInformationExtraFieldBuilder>,
+    ) -> Result<()> {
         if let Some(builder) = zip64_extra_field_builder {
             self.entry.extra_fields.push(ExtraField::Zip64ExtendedInformation(builder.build()?));
             header.extra_field_length =
@@ -194,7 +286,7 @@ impl<'b, 'c, W: AsyncWrite + Unpin> EntryWholeWriter<'b, 'c, W> {
 
         self.writer.cd_entries.push(CentralDirectoryEntry { header, entry: self.entry });
         // Ensure that we can fit this many files in this archive if forcing no zip64
-        if self.writer.cd_entries.len() > NON_ZIP64_MAX_NUM_FILES as usize {
+        if self.writer.num_entries() > NON_ZIP64_MAX_NUM_FILES as usize {
             if self.writer.force_no_zip64 {
                 return Err(ZipError::Zip64Needed(Zip64ErrorCase::TooManyFiles));
             }
@@ -236,10 +328,10 @@ async fn compress(compression: Compression, data: &[u8], level: async_compressio
         }
         #[cfg(feature = "lzma")]
         Compression::Lzma => {
-            let mut writer = write::LzmaEncoder::with_quality(Cursor::new(Vec::new()), level);
+            let mut writer = write::LzmaEncoder::with_quality(LzmaHeaderWriter::new(Cursor::new(Vec::new())), level);
             writer.write_all(data).await.unwrap();
             writer.close().await.unwrap();
-            writer.into_inner().into_inner()
+            writer.into_inner().into_inner().into_inner()
         }
         #[cfg(feature = "xz")]
         Compression::Xz => {
@@ -258,3 +350,29 @@ async fn compress(compression: Compression, data: &[u8], level: async_compressio
         _ => unreachable!(),
     }
 }
+
+#[cfg(feature = "deflate-dictionary")]
+fn compress_with_dictionary(data: &[u8], dictionary: &[u8], level: async_compression::Level) -> Vec<u8> {
+    use flate2::{Compress, FlushCompress, Status};
+
+    let level = match level {
+        async_compression::Level::Fastest => flate2::Compression::fast(),
+        async_compression::Level::Best => flate2::Compression::best(),
+        async_compression::Level::Precise(level) => flate2::Compression::new(level.clamp(0, 9) as u32),
+        _ => flate2::Compression::default(),
+    };
+
+    let mut compress = Compress::new(level, false);
+    compress.set_dictionary(dictionary).unwrap();
+
+    let mut output = Vec::with_capacity(data.len() / 2 + 64);
+    loop {
+        let consumed = compress.total_in() as usize;
+        let status = compress.compress_vec(&data[consumed..], &mut output, FlushCompress::Finish).unwrap();
+
+        match status {
+            Status::StreamEnd => return output,
+            _ => output.reserve(output.capacity().max(64)),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::spec::consts::{LZMA_HEADER_LENGTH, LZMA_PROPERTIES_LENGTH};
+
+use std::io::Error;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::io::AsyncWrite;
+
+/// The length of the header which prefixes a bare `.lzma` stream, holding the properties and the uncompressed size.
+const ALONE_HEADER_LENGTH: usize = LZMA_PROPERTIES_LENGTH + 8;
+/// The LZMA SDK version recorded within the header. As the data is produced by liblzma rather than the SDK, this is
+/// nominal and matches that written by other encoders.
+const LZMA_SDK_VERSION: [u8; 2] = [9, 4];
+
+/// A wrapping writer which converts a bare `.lzma` stream into an entry's LZMA data.
+///
+/// The uncompressed size which follows the properties is replaced by the LZMA SDK version and the length of the
+/// properties which precede them. As the encoder doesn't know the uncompressed size upfront, its data is always
+/// terminated by an end-of-stream marker.
+pub struct LzmaHeaderWriter<W> {
+    inner: W,
+    header: Vec<u8>,
+    pending: Vec<u8>,
+    written: usize,
+}
+
+impl<W> LzmaHeaderWriter<W> {
+    /// Constructs a new wrapping writer from an inner [`AsyncWrite`] writer.
+    pub fn new(inner: W) -> Self {
+        Self { inner, header: Vec::with_capacity(ALONE_HEADER_LENGTH), pending: Vec::new(), written: 0 }
+    }
+
+    /// Consumes this writer and returns the inner value.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W> LzmaHeaderWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    /// Writes out any of the converted header which the inner writer hasn't yet accepted.
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while self.written < self.pending.len() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.pending[self.written..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+            }
+            self.written += written;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W> AsyncWrite for LzmaHeaderWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<std::result::Result<usize, Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+
+        if this.header.len() == ALONE_HEADER_LENGTH {
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        }
+
+        let length = std::cmp::min(buf.len(), ALONE_HEADER_LENGTH - this.header.len());
+        this.header.extend_from_slice(&buf[..length]);
+
+        if this.header.len() == ALONE_HEADER_LENGTH {
+            this.pending.reserve(LZMA_HEADER_LENGTH + LZMA_PROPERTIES_LENGTH);
+            this.pending.extend_from_slice(&LZMA_SDK_VERSION);
+            this.pending.extend_from_slice(&(LZMA_PROPERTIES_LENGTH as u16).to_le_bytes());
+            this.pending.extend_from_slice(&this.header[..LZMA_PROPERTIES_LENGTH]);
+        }
+
+        Poll::Ready(Ok(length))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<std::result::Result<(), Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<std::result::Result<(), Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (c) 2022 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use std::future::Future;
+use std::io::Result;
+
+use futures_util::io::Cursor;
+
+/// A writer whose underlying data can be truncated.
+///
+/// This is required by [`ZipFileWriter::append()`] so that no stale data (such as the previous end of central
+/// directory record) is left behind when the rewritten ZIP file is shorter than the existing one.
+///
+/// [`ZipFileWriter::append()`]: crate::base::write::ZipFileWriter::append
+pub trait AsyncTruncate {
+    /// Truncates the underlying data to the provided length in bytes.
+    fn truncate(&mut self, len: u64) -> impl Future<Output = Result<()>> + Send;
+}
+
+impl AsyncTruncate for Cursor<Vec<u8>> {
+    async fn truncate(&mut self, len: u64) -> Result<()> {
+        self.get_mut().truncate(len.try_into().unwrap_or(usize::MAX));
+        Ok(())
+    }
+}
+
+impl AsyncTruncate for Cursor<&mut Vec<u8>> {
+    async fn truncate(&mut self, len: u64) -> Result<()> {
+        self.get_mut().truncate(len.try_into().unwrap_or(usize::MAX));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio-fs")]
+impl AsyncTruncate for tokio_util::compat::Compat<tokio::fs::File> {
+    async fn truncate(&mut self, len: u64) -> Result<()> {
+        self.get_mut().set_len(len).await
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+/// A writer which splits the ZIP file written to it across multiple volumes.
+///
+/// Offsets are those of the whole ZIP file, as tracked by the [`ZipFileWriter`] writing to it. Volume numbers start at
+/// zero, and a volume starts at the first offset written to it.
+///
+/// [`ZipFileWriter`]: crate::base::write::ZipFileWriter
+pub(crate) trait Volumes {
+    /// Ends the current volume if fewer than the provided number of bytes remain within it, so that a record of that
+    /// length isn't split across volumes, returning the volume number and relative offset at which it'll be written.
+    ///
+    /// The next volume is only started by the next write, so nothing happens if no further writes are made.
+    fn reserve(&mut self, length: u64) -> (u32, u64);
+
+    /// Returns the volume number an offset which has already been written falls within, and the offset relative to
+    /// the start of that volume.
+    fn locate(&self, offset: u64) -> (u32, u64);
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A ZIP writer which acts over an owned vector of bytes.
+//!
+//! ### Usage
+//! [`MemZipFileWriter`] dereferences to a [`ZipFileWriter`] over a [`Vec<u8>`], so entries are written exactly as they
+//! would be to any other writer. Once finished, [`MemZipFileWriter::into_inner()`] writes the central directory and
+//! returns the ZIP file's bytes.
+//!
+//! ### Example
+//! ```no_run
+//! # use async_zip::base::write::mem::MemZipFileWriter;
+//! # use async_zip::{Compression, ZipEntryBuilder};
+//! # use async_zip::error::Result;
+//! #
+//! # async fn run() -> Result<Vec<u8>> {
+//! let mut writer = MemZipFileWriter::new();
+//!
+//! let opts = ZipEntryBuilder::new(String::from("foo.txt").into(), Compression::Stored);
+//! writer.write_entry_whole(opts, b"This is an example file.").await?;
+//!
+//! writer.into_inner().await
+//! # }
+//! ```
+
+use crate::base::write::ZipFileWriter;
+use crate::error::Result;
+
+use std::ops::{Deref, DerefMut};
+
+/// A ZIP file writer which acts over an owned vector of bytes.
+pub struct MemZipFileWriter {
+    inner: ZipFileWriter<Vec<u8>>,
+}
+
+impl MemZipFileWriter {
+    /// Constructs a new ZIP writer over an empty vector of bytes.
+    pub fn new() -> Self {
+        Self { inner: ZipFileWriter::new(Vec::new()) }
+    }
+
+    /// Writes the central directory and returns the ZIP file's bytes.
+    ///
+    /// This is equivalent to calling [`ZipFileWriter::close()`] on the inner writer.
+    pub async fn into_inner(self) -> Result<Vec<u8>> {
+        self.inner.close().await
+    }
+}
+
+impl Default for MemZipFileWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<ZipFileWriter<Vec<u8>>> for MemZipFileWriter {
+    /// Wraps a writer over a vector of bytes (eg. one which has been configured via its builder-style methods).
+    fn from(inner: ZipFileWriter<Vec<u8>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Deref for MemZipFileWriter {
+    type Target = ZipFileWriter<Vec<u8>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for MemZipFileWriter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
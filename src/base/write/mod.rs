@@ -48,39 +48,162 @@
 //! # }
 //! # }
 //! ```
+//! ### Stream data from stdin to stdout
+//! As the writer never seeks, a stream writer can be piped directly between non-seekable sources and destinations.
+//! See the `cli_stdin` example for a complete program.
+//! ```no_run
+//! # #[cfg(feature = "deflate")]
+//! # {
+//! # use async_zip::{Compression, ZipEntryBuilder, base::write::ZipFileWriter};
+//! # use async_zip::error::ZipError;
+//! # use futures_util::io::AsyncWriteExt;
+//! # use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+//! #
+//! # async fn run() -> Result<(), ZipError> {
+//! let mut writer = ZipFileWriter::new(tokio::io::stdout().compat_write());
+//! let opts = ZipEntryBuilder::new(String::from("stdin.txt").into(), Compression::Deflate);
+//!
+//! let mut entry_writer = writer.write_entry_stream(opts).await?;
+//! futures_util::io::copy(&mut tokio::io::stdin().compat(), &mut entry_writer).await?;
+//!
+//! entry_writer.close().await?;
+//! writer.close().await?.flush().await?;
+//! #   Ok(())
+//! # }
+//! # }
+//! ```
 
 pub(crate) mod compressed_writer;
 pub(crate) mod entry_stream;
 pub(crate) mod entry_whole;
 pub(crate) mod io;
+pub mod mem;
+pub(crate) mod rewrite;
 
 pub use entry_stream::EntryStreamWriter;
+pub use io::truncate::AsyncTruncate;
+pub use rewrite::rewrite_names;
 
 #[cfg(feature = "tokio")]
-use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+use tokio_util::compat::{Compat, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
-use crate::entry::ZipEntry;
+use crate::base::read::ReadOptions;
+use crate::entry::{StoredZipEntry, ZipEntry};
 use crate::error::Result;
 use crate::spec::extra_field::ExtraFieldAsBytes;
 use crate::spec::header::{
     CentralDirectoryRecord, EndOfCentralDirectoryHeader, ExtraField, InfoZipUnicodeCommentExtraField,
     InfoZipUnicodePathExtraField, Zip64EndOfCentralDirectoryLocator, Zip64EndOfCentralDirectoryRecord,
 };
+use crate::{AttributeCompatibility, Compression, ZipDateTime, ZipEntryBuilder, ZipString};
 
 #[cfg(feature = "tokio")]
 use crate::tokio::write::ZipFileWriter as TokioZipFileWriter;
 
 use entry_whole::EntryWholeWriter;
 use io::offset::AsyncOffsetWriter;
+use io::volume::Volumes;
+
+use std::collections::HashMap;
+
+use crate::spec::consts::{
+    AES_COMPRESSION_METHOD, ALIGNMENT_EXTRA_FIELD_ID, ALIGNMENT_EXTRA_FIELD_LENGTH, CDH_LENGTH, CDH_SIGNATURE,
+    EOCDR_LENGTH, NON_ZIP64_MAX_NUM_FILES, NON_ZIP64_MAX_SIZE, PRODUCER_MARKER, SIGNATURE_LENGTH, ZIP64_EOCDL_LENGTH,
+    ZIP64_EOCDR_LENGTH,
+};
+use futures_util::future::BoxFuture;
+use futures_util::io::{
+    AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt, BufReader, SeekFrom,
+};
 
-use crate::spec::consts::{NON_ZIP64_MAX_NUM_FILES, NON_ZIP64_MAX_SIZE};
-use futures_util::io::{AsyncWrite, AsyncWriteExt};
+/// The amount of data, equal to 64KiB, up to which [`ZipFileWriter::write_entry_from_reader()`] writes an entry whole.
+const WHOLE_ENTRY_THRESHOLD: usize = 64 * 1024;
 
 pub(crate) struct CentralDirectoryEntry {
     pub header: CentralDirectoryRecord,
     pub entry: ZipEntry,
 }
 
+impl CentralDirectoryEntry {
+    /// Writes this entry's central directory record, including its signature.
+    async fn write<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<()> {
+        let filename_basic = self.entry.filename().alternative().unwrap_or_else(|| self.entry.filename().as_bytes());
+        let comment_basic = self.entry.comment().alternative().unwrap_or_else(|| self.entry.comment().as_bytes());
+
+        writer.write_all(&CDH_SIGNATURE.to_le_bytes()).await?;
+        writer.write_all(&self.header.as_slice()).await?;
+        writer.write_all(filename_basic).await?;
+        writer.write_all(&self.entry.extra_fields().as_bytes()).await?;
+        writer.write_all(comment_basic).await?;
+
+        Ok(())
+    }
+
+    /// Returns the length of this entry's central directory record, including its signature.
+    fn length(&self) -> usize {
+        let filename_basic = self.entry.filename().alternative().unwrap_or_else(|| self.entry.filename().as_bytes());
+        let comment_basic = self.entry.comment().alternative().unwrap_or_else(|| self.entry.comment().as_bytes());
+
+        SIGNATURE_LENGTH
+            + CDH_LENGTH
+            + filename_basic.len()
+            + self.entry.extra_fields().count_bytes()
+            + comment_basic.len()
+    }
+
+    /// Rewrites the offset of this entry's local file header to be relative to the volume it starts within, as
+    /// required when the ZIP file is split across volumes.
+    fn relocate(&mut self, volumes: &dyn Volumes) -> Result<()> {
+        let zip64_offset = self.entry.extra_fields.iter_mut().find_map(|field| match field {
+            ExtraField::Zip64ExtendedInformation(field) => field.relative_header_offset.as_mut(),
+            _ => None,
+        });
+
+        let disk = match zip64_offset {
+            Some(offset) if self.header.lh_offset == NON_ZIP64_MAX_SIZE => {
+                let (disk, relative_offset) = volumes.locate(*offset);
+                *offset = relative_offset;
+                disk
+            }
+            _ => {
+                let (disk, relative_offset) = volumes.locate(self.header.lh_offset.into());
+                self.header.lh_offset = relative_offset as u32;
+                disk
+            }
+        };
+        self.header.disk_start = volume_number(disk)?;
+
+        Ok(())
+    }
+}
+
+/// Converts a volume number into the 16-bit form recorded within most headers.
+fn volume_number(disk: u32) -> Result<u16> {
+    disk.try_into()
+        .ok()
+        .filter(|disk| *disk != NON_ZIP64_MAX_NUM_FILES)
+        .ok_or(crate::error::ZipError::FeatureNotSupported("Splitting a ZIP file into more than 65535 volumes"))
+}
+
+/// A seekable read/write target which central directory records are spilled to.
+trait SpillTarget: AsyncRead + AsyncWrite + AsyncSeek + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + AsyncSeek + Unpin + Send> SpillTarget for T {}
+
+/// The state of a writer's central directory spill.
+struct CentralDirectorySpill {
+    target: Box<dyn SpillTarget>,
+    max_records: usize,
+    spilled_entries: usize,
+    spilled_bytes: u64,
+}
+
+/// Truncates a writer to its current position, once an appended-to ZIP file has been closed.
+type Truncator<W> = for<'a> fn(&'a mut W) -> BoxFuture<'a, std::io::Result<()>>;
+
+/// Accesses the volumes a writer splits the ZIP file across, if it's split.
+pub(crate) type VolumeAccess<W> = fn(&mut W) -> &mut dyn Volumes;
+
 /// A ZIP file writer which acts over AsyncWrite implementers.
 ///
 /// # Note
@@ -92,7 +215,26 @@ pub struct ZipFileWriter<W> {
     force_no_zip64: bool,
     /// Whether to write Zip64 end of directory structs.
     pub(crate) is_zip64: bool,
-    comment_opt: Option<String>,
+    /// Whether to sort the central directory records by filename when closing.
+    sort_central_directory: bool,
+    /// The compression method used by [`ZipFileWriter::write_entry()`].
+    default_compression: Compression,
+    /// The alignment applied to stored entries which don't set their own.
+    stored_alignment: u16,
+    /// The compression methods used by [`ZipFileWriter::write_path()`], keyed by lowercase file extension.
+    extension_compression: HashMap<String, Compression>,
+    /// Where central directory records are spilled to when too many are held in memory, if anywhere.
+    cd_spill: Option<CentralDirectorySpill>,
+    /// The clock used for the modification date of entries which don't set their own.
+    clock: Option<Box<dyn Fn() -> ZipDateTime + Send>>,
+    /// The total size to which the ZIP file is padded when closing, if any.
+    padded_size: Option<u64>,
+    /// How the inner writer is truncated when closing, if it's an existing ZIP file which was appended to.
+    truncator: Option<Truncator<W>>,
+    /// How the volumes are accessed when the inner writer splits the ZIP file across multiple volumes, if it does.
+    pub(crate) volumes: Option<VolumeAccess<W>>,
+    comment_opt: Option<Vec<u8>>,
+    producer_opt: Option<String>,
 }
 
 impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
@@ -102,8 +244,18 @@ impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
             writer: AsyncOffsetWriter::new(writer),
             cd_entries: Vec::new(),
             comment_opt: None,
+            producer_opt: None,
             is_zip64: false,
             force_no_zip64: false,
+            sort_central_directory: false,
+            default_compression: Compression::Stored,
+            stored_alignment: 0,
+            extension_compression: HashMap::new(),
+            cd_spill: None,
+            clock: None,
+            padded_size: None,
+            truncator: None,
+            volumes: None,
         }
     }
 
@@ -116,26 +268,312 @@ impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
 
     /// Force the ZIP writer to emit Zip64 structs at the end of the archive.
     /// Zip64 extended fields will only be written if needed.
+    ///
+    /// The classic end of central directory record is always written after the Zip64 structs. Its fields hold the real
+    /// values where they fit and `0xFFFF`/`0xFFFFFFFF` sentinels otherwise, so readers which are unaware of Zip64 can
+    /// still read archives that don't exceed the classic limits.
     pub fn force_zip64(mut self) -> Self {
         self.is_zip64 = true;
         self
     }
 
+    /// Sort the central directory records by filename when the writer is closed.
+    ///
+    /// This only affects the order in which entries are listed, and not the order in which their data is written.
+    ///
+    /// As sorting requires all records to be held in memory, [`ZipError::FeatureNotSupported`] is returned if
+    /// [`ZipFileWriter::spill_central_directory()`] has already been set.
+    ///
+    /// [`ZipError::FeatureNotSupported`]: crate::error::ZipError::FeatureNotSupported
+    pub fn sort_central_directory(mut self, sort: bool) -> Result<Self> {
+        if sort && self.cd_spill.is_some() {
+            return Err(crate::error::ZipError::FeatureNotSupported("Sorting a spilled central directory"));
+        }
+
+        self.sort_central_directory = sort;
+        Ok(self)
+    }
+
+    /// Set the compression method used for entries written via [`ZipFileWriter::write_entry()`].
+    ///
+    /// This defaults to [`Compression::Stored`]. Entries written via a builder always use the builder's method.
+    pub fn with_default_compression(mut self, compression: Compression) -> Self {
+        self.default_compression = compression;
+        self
+    }
+
+    /// Set the compression methods used for entries written via [`ZipFileWriter::write_path()`], keyed by file
+    /// extension.
+    ///
+    /// Extensions are matched case-insensitively and may be provided with or without a leading `.`. Paths whose
+    /// extension isn't present within the map use the method set via [`ZipFileWriter::with_default_compression()`].
+    ///
+    /// ```
+    /// # use async_zip::{base::write::ZipFileWriter, Compression};
+    /// # use std::collections::HashMap;
+    /// #
+    /// let map = HashMap::from([("png".to_string(), Compression::Stored), ("txt".to_string(), Compression::Stored)]);
+    /// let writer = ZipFileWriter::new(Vec::<u8>::new()).compress_by_extension(map);
+    /// ```
+    pub fn compress_by_extension(mut self, map: HashMap<String, Compression>) -> Self {
+        self.extension_compression = map
+            .into_iter()
+            .map(|(extension, compression)| (extension.trim_start_matches('.').to_ascii_lowercase(), compression))
+            .collect();
+        self
+    }
+
+    /// Spill central directory records to the provided target whenever more than `max_records` are held in memory.
+    ///
+    /// By default, the central directory records of all entries are held in memory until the writer is closed. For
+    /// ZIP files with a very large number of entries, this option bounds the writer's memory usage by writing those
+    /// records out to a separate seekable target (eg. a temporary file), before copying them back into the ZIP file
+    /// when it's closed. The target should be empty, and is left positioned at the end of the spilled records.
+    ///
+    /// This option can't be combined with [`ZipFileWriter::sort_central_directory()`], as sorting requires all records
+    /// to be held in memory. [`ZipError::FeatureNotSupported`] is returned if sorting has already been enabled.
+    ///
+    /// [`ZipError::FeatureNotSupported`]: crate::error::ZipError::FeatureNotSupported
+    pub fn spill_central_directory<S>(mut self, target: S, max_records: usize) -> Result<Self>
+    where
+        S: AsyncRead + AsyncWrite + AsyncSeek + Unpin + Send + 'static,
+    {
+        if self.sort_central_directory {
+            return Err(crate::error::ZipError::FeatureNotSupported("Sorting a spilled central directory"));
+        }
+
+        self.cd_spill =
+            Some(CentralDirectorySpill { target: Box::new(target), max_records, spilled_entries: 0, spilled_bytes: 0 });
+        Ok(self)
+    }
+
+    /// Set a clock which provides the last modification date of entries which don't set their own.
+    ///
+    /// By default, such entries are written with a zeroed date. Injecting a clock allows the current time to be used
+    /// (eg. `OffsetDateTime::now_utc` with the `time` feature, or `Utc::now` with the `chrono` feature), or a fixed
+    /// time to be pinned for deterministic tests and reproducible builds. The clock may return anything convertible
+    /// into a [`ZipDateTime`], and is called once per entry, when the entry begins to be written.
+    pub fn with_clock<T: Into<ZipDateTime>>(mut self, clock: impl Fn() -> T + Send + 'static) -> Self {
+        self.clock = Some(Box::new(move || clock().into()));
+        self
+    }
+
+    /// Align the data of all stored entries to the provided number of bytes, as done by Android's zipalign.
+    ///
+    /// This applies to any entry using [`Compression::Stored`] which doesn't already set its own alignment via
+    /// [`ZipEntryBuilder::align()`], allowing eg. an APK's stored entries to be aligned to 4 bytes whilst its shared
+    /// libraries are individually aligned to 16KiB.
+    pub fn align_stored_entries(mut self, alignment: u16) -> Self {
+        self.stored_alignment = alignment;
+        self
+    }
+
+    /// Write a new ZIP entry of known size and data, using the writer's default compression method.
+    ///
+    /// This is equivalent to calling [`ZipFileWriter::write_entry_whole()`] with a builder constructed from the
+    /// filename and the method set via [`ZipFileWriter::with_default_compression()`].
+    pub async fn write_entry<S: Into<ZipString>>(&mut self, filename: S, data: &[u8]) -> Result<()> {
+        let builder = ZipEntryBuilder::new(filename.into(), self.default_compression);
+        self.write_entry_whole(builder, data).await
+    }
+
+    /// Write a new ZIP entry of known size and data, using a compression method chosen by the path's file extension.
+    ///
+    /// The method is looked up within the map set via [`ZipFileWriter::compress_by_extension()`], falling back to the
+    /// writer's default compression method.
+    pub async fn write_path(&mut self, path: &str, data: &[u8]) -> Result<()> {
+        let compression = path
+            .rsplit_once('.')
+            .filter(|(_, extension)| !extension.contains(['/', '\\']))
+            .and_then(|(_, extension)| self.extension_compression.get(&extension.to_ascii_lowercase()))
+            .copied()
+            .unwrap_or(self.default_compression);
+
+        let builder = ZipEntryBuilder::new(path.to_string().into(), compression);
+        self.write_entry_whole(builder, data).await
+    }
+
+    /// Pads the ZIP file to a fixed total size when it's closed (eg. to fill a firmware partition).
+    ///
+    /// The padding consists of null bytes appended to the ZIP file comment, so the result remains a valid ZIP file
+    /// which any reader accepts. As a comment is limited to 65,535 bytes, [`ZipError::InvalidPaddedSize`] is returned
+    /// when closing if the padding wouldn't fit, or if the ZIP file is already larger than the provided size.
+    ///
+    /// [`ZipError::InvalidPaddedSize`]: crate::error::ZipError::InvalidPaddedSize
+    pub fn pad_to(mut self, size: u64) -> Self {
+        self.padded_size = Some(size);
+        self
+    }
+
     /// Write a new ZIP entry of known size and data.
     pub async fn write_entry_whole<E: Into<ZipEntry>>(&mut self, entry: E, data: &[u8]) -> Result<()> {
-        EntryWholeWriter::from_raw(self, entry.into(), data).write().await
+        self.spill_if_needed().await?;
+        let entry = self.apply_entry_defaults(entry.into());
+        EntryWholeWriter::from_raw(self, entry, data).write().await
+    }
+
+    /// Write a batch of new ZIP entries of known size and data, in order.
+    ///
+    /// Writing stops at the first entry which fails, returning a [`ZipError::EntryError`] which holds its position
+    /// within the batch and its filename. Any entries prior to it will have been written.
+    ///
+    /// [`ZipError::EntryError`]: crate::error::ZipError::EntryError
+    pub async fn write_entries<I>(&mut self, entries: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (ZipEntryBuilder, Vec<u8>)>,
+    {
+        for (index, (builder, data)) in entries.into_iter().enumerate() {
+            let mut entry: ZipEntry = builder.into();
+            entry.index = index;
+
+            let context = entry.clone();
+            self.write_entry_whole(entry, &data).await.map_err(|err| err.in_entry(&context))?;
+        }
+
+        Ok(())
+    }
+
+    /// Copies an entry read from another ZIP file into this one, without decompressing or recompressing its data.
+    ///
+    /// The reader should be one returned by the other ZIP reader's `raw_reader_without_entry()` method for the same
+    /// entry. Its compressed data is copied verbatim alongside its compression method, CRC32 value, sizes, and other
+    /// metadata. Any ZIP64 extended information extra field is dropped as it'd otherwise be stale.
+    ///
+    /// Entries encrypted with ZipCrypto can't be copied, as their encryption header may depend upon their data
+    /// descriptor flag, which isn't carried over. Those encrypted with AES can.
+    pub async fn copy_entry_raw<R: AsyncRead + Unpin>(&mut self, entry: &StoredZipEntry, reader: R) -> Result<()> {
+        let mut entry = entry.entry.clone();
+        entry.extra_fields.retain(|field| !matches!(field, ExtraField::Zip64ExtendedInformation(_)));
+
+        self.write_entry_raw(entry, reader).await
+    }
+
+    /// Write a new ZIP entry whose data has already been compressed, copying it verbatim from the provided reader.
+    ///
+    /// The entry's compression method, CRC32 value, and sizes are written as-is and must describe the data (eg. as set
+    /// via [`ZipEntryBuilder::with_crc_and_sizes()`](crate::ZipEntryBuilder::with_crc_and_sizes)), as they're written
+    /// within the local file header rather than a data descriptor. Exactly as many bytes as the compressed size are
+    /// copied, erroring if the reader ends first.
+    ///
+    /// Entries encrypted with ZipCrypto can't be written, as per [`ZipFileWriter::copy_entry_raw()`].
+    pub async fn write_entry_raw<E, R>(&mut self, entry: E, reader: R) -> Result<()>
+    where
+        E: Into<ZipEntry>,
+        R: AsyncRead + Unpin,
+    {
+        let entry = entry.into();
+        if entry.general_purpose_flag.encrypted
+            && entry.compression() != Compression::Unsupported(AES_COMPRESSION_METHOD)
+        {
+            return Err(crate::error::ZipError::FeatureNotSupported("Copying entries encrypted with ZipCrypto"));
+        }
+
+        self.spill_if_needed().await?;
+        let entry = self.apply_entry_defaults(entry);
+        EntryWholeWriter::from_raw(self, entry, &[]).write_raw(reader).await
     }
 
     /// Write an entry of unknown size and data via streaming (ie. using a data descriptor).
     /// The generated Local File Header will be invalid, with no compressed size, uncompressed size,
     /// and a null CRC. This might cause problems with the destination reader.
     pub async fn write_entry_stream<E: Into<ZipEntry>>(&mut self, entry: E) -> Result<EntryStreamWriter<'_, W>> {
-        EntryStreamWriter::from_raw(self, entry.into()).await
+        self.spill_if_needed().await?;
+        let entry = self.apply_entry_defaults(entry.into());
+        EntryStreamWriter::from_raw(self, entry).await
+    }
+
+    /// Write a new ZIP entry whose data is copied from the provided reader, returning the number of bytes copied.
+    ///
+    /// Up to the first 64KiB of data is buffered. If the reader reaches EOF within that, the entry is written as per
+    /// [`ZipFileWriter::write_entry_whole()`], with its CRC32 value and sizes held within its local file header.
+    /// Otherwise, the entry is streamed as per [`ZipFileWriter::write_entry_stream()`], with its CRC32 value and sizes
+    /// held within a data descriptor, so that the whole of its data is never held in memory. As such, larger entries
+    /// are subject to the restrictions of the stream writer (eg. they can't be encrypted).
+    pub async fn write_entry_from_reader<E, R>(&mut self, entry: E, mut reader: R) -> Result<u64>
+    where
+        E: Into<ZipEntry>,
+        R: AsyncRead + Unpin,
+    {
+        let mut buffer = Vec::new();
+        (&mut reader).take(WHOLE_ENTRY_THRESHOLD as u64 + 1).read_to_end(&mut buffer).await?;
+
+        if buffer.len() <= WHOLE_ENTRY_THRESHOLD {
+            self.write_entry_whole(entry, &buffer).await?;
+            return Ok(buffer.len() as u64);
+        }
+
+        let mut entry_writer = self.write_entry_stream(entry).await?;
+        entry_writer.write_all(&buffer).await?;
+        let copied = futures_util::io::copy(reader, &mut entry_writer).await?;
+        entry_writer.close().await?;
+
+        Ok(buffer.len() as u64 + copied)
+    }
+
+    /// Writes the in-memory central directory records to the spill target if there are too many of them.
+    async fn spill_if_needed(&mut self) -> Result<()> {
+        let Some(spill) = self.cd_spill.as_mut() else {
+            return Ok(());
+        };
+
+        if self.cd_entries.len() < spill.max_records {
+            return Ok(());
+        }
+
+        let mut target = AsyncOffsetWriter::new(&mut spill.target);
+        for entry in &self.cd_entries {
+            entry.write(&mut target).await?;
+        }
+
+        spill.spilled_bytes += target.offset() as u64;
+        spill.spilled_entries += self.cd_entries.len();
+        self.cd_entries.clear();
+        Ok(())
+    }
+
+    /// Returns the number of entries written so far, including any which have been spilled.
+    pub(crate) fn num_entries(&self) -> usize {
+        self.cd_entries.len() + self.cd_spill.as_ref().map(|spill| spill.spilled_entries).unwrap_or_default()
+    }
+
+    /// Applies the writer-level defaults (stored alignment and clock) to an entry which doesn't set its own.
+    fn apply_entry_defaults(&self, mut entry: ZipEntry) -> ZipEntry {
+        if entry.compression == Compression::Stored && entry.alignment == 0 {
+            entry.alignment = self.stored_alignment;
+        }
+        if let Some(clock) = self.clock.as_ref().filter(|_| !entry.date_set) {
+            entry.last_modification_date = clock();
+        }
+        entry
     }
 
     /// Set the ZIP file comment.
+    ///
+    /// The comment is stored within the end of central directory record, so is limited to 65,535 bytes (including any
+    /// producer tag). [`ZipError::CommentTooLarge`] is returned when closing if it's any longer.
+    ///
+    /// [`ZipError::CommentTooLarge`]: crate::error::ZipError::CommentTooLarge
     pub fn comment(&mut self, comment: String) {
-        self.comment_opt = Some(comment);
+        self.comment_opt = Some(comment.into_bytes());
+    }
+
+    /// Set a tag identifying the software which produced this ZIP file.
+    ///
+    /// As the ZIP format has no dedicated field for this, the tag is appended to the ZIP file comment as a final line
+    /// of the form `Producer: <tag>`. It can be read back via [`ZipFile::producer()`](crate::ZipFile::producer).
+    /// The tag should therefore not contain any line breaks.
+    pub fn producer(&mut self, producer: String) {
+        self.producer_opt = Some(producer);
+    }
+
+    /// Flushes all data written for completed entries through to the underlying writer, without finalising the ZIP file.
+    ///
+    /// This allows downstream consumers of a long-running writer (eg. a stream reader on the other end of a pipe) to
+    /// make progress before the writer is closed. As an entry stream writer holds a mutable borrow of this writer, this
+    /// can only be called between entries. The central directory is still only written by [`ZipFileWriter::close()`].
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        Ok(())
     }
 
     /// Returns a mutable reference to the inner writer.
@@ -145,6 +583,22 @@ impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
         self.writer.inner_mut()
     }
 
+    /// Ensures a record of the provided length isn't split across volumes, if the ZIP file is split across them.
+    pub(crate) fn begin_record(&mut self, length: usize) {
+        if let Some(volumes) = self.volumes {
+            volumes(self.writer.inner_mut()).reserve(length as u64);
+        }
+    }
+
+    /// Consumes this ZIP writer without completing any closing tasks, returning the inner writer.
+    ///
+    /// No central directory or end of central directory record is written, so the data written so far won't form a
+    /// valid ZIP file. The returned writer can then be used to clean up that data (eg. by removing the underlying file),
+    /// or [`ZipFileWriter::abort_and_truncate()`] can be used instead to truncate it.
+    pub fn abort(self) -> W {
+        self.writer.into_inner()
+    }
+
     /// Consumes this ZIP writer and completes all closing tasks.
     ///
     /// This includes:
@@ -156,34 +610,90 @@ impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
     pub async fn close(mut self) -> Result<W> {
         let cd_offset = self.writer.offset();
 
-        for entry in &self.cd_entries {
-            let filename_basic =
-                entry.entry.filename().alternative().unwrap_or_else(|| entry.entry.filename().as_bytes());
-            let comment_basic = entry.entry.comment().alternative().unwrap_or_else(|| entry.entry.comment().as_bytes());
-
-            self.writer.write_all(&crate::spec::consts::CDH_SIGNATURE.to_le_bytes()).await?;
-            self.writer.write_all(&entry.header.as_slice()).await?;
-            self.writer.write_all(filename_basic).await?;
-            self.writer.write_all(&entry.entry.extra_fields().as_bytes()).await?;
-            self.writer.write_all(comment_basic).await?;
+        let num_entries_in_directory = self.num_entries() as u64;
+
+        if let Some(spill) = self.cd_spill.as_mut() {
+            let end = spill.target.seek(SeekFrom::Current(0)).await?;
+            let start = end.checked_sub(spill.spilled_bytes).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "spill target is shorter than the spilled records")
+            })?;
+            spill.target.seek(SeekFrom::Start(start)).await?;
+            futures_util::io::copy(&mut (&mut spill.target).take(spill.spilled_bytes), &mut self.writer).await?;
+        }
+
+        if self.sort_central_directory {
+            self.cd_entries.sort_by(|a, b| a.entry.filename().as_bytes().cmp(b.entry.filename().as_bytes()));
+        }
+
+        // When split across volumes, no record is split across two of them, and the volume each starts on is noted.
+        let mut record_volumes = Vec::new();
+        for entry in &mut self.cd_entries {
+            if let Some(volumes) = self.volumes {
+                let volumes = volumes(self.writer.inner_mut());
+                record_volumes.push(volumes.reserve(entry.length() as u64));
+                entry.relocate(volumes)?;
+            }
+            entry.write(&mut self.writer).await?;
         }
 
         let central_directory_size = (self.writer.offset() - cd_offset) as u64;
         let central_directory_size_u32 = if central_directory_size > NON_ZIP64_MAX_SIZE as u64 {
+            if self.force_no_zip64 {
+                let case = crate::error::Zip64ErrorCase::TooLargeCentralDirectory;
+                return Err(crate::error::ZipError::Zip64Needed(case));
+            } else {
+                self.is_zip64 = true;
+            }
             NON_ZIP64_MAX_SIZE
         } else {
             central_directory_size as u32
         };
-        let num_entries_in_directory = self.cd_entries.len() as u64;
         let num_entries_in_directory_u16 = if num_entries_in_directory > NON_ZIP64_MAX_NUM_FILES as u64 {
             NON_ZIP64_MAX_NUM_FILES
         } else {
             num_entries_in_directory as u16
         };
-        let cd_offset = cd_offset as u64;
+
+        let mut comment = self.comment_opt.unwrap_or_default();
+        if let Some(producer) = self.producer_opt {
+            if !comment.is_empty() {
+                comment.push(b'\n');
+            }
+            comment.extend_from_slice(format!("{PRODUCER_MARKER}{producer}").as_bytes());
+        }
+
+        if let Some(padded_size) = self.padded_size {
+            let zip64_length = if self.is_zip64 { ZIP64_EOCDR_LENGTH + ZIP64_EOCDL_LENGTH } else { 0 };
+            let size = (self.writer.offset() + SIGNATURE_LENGTH + EOCDR_LENGTH + comment.len()) as u64 + zip64_length;
+            let padding =
+                padded_size.checked_sub(size).filter(|padding| comment.len() as u64 + padding <= u16::MAX as u64);
+            let padding = padding.ok_or(crate::error::ZipError::InvalidPaddedSize(size, padded_size))?;
+            comment.resize(comment.len() + padding as usize, 0);
+        }
+
+        // The records which end the ZIP file are kept together on its final volume, as readers expect.
+        let end_offset = self.writer.offset() as u64;
+        let (last_disk, end_offset) = match self.volumes {
+            Some(volumes) => {
+                let zip64_length = if self.is_zip64 { ZIP64_EOCDR_LENGTH + ZIP64_EOCDL_LENGTH } else { 0 };
+                let length = (SIGNATURE_LENGTH + EOCDR_LENGTH + comment.len()) as u64 + zip64_length;
+                volumes(self.writer.inner_mut()).reserve(length)
+            }
+            None => (0, end_offset),
+        };
+        let (cd_disk, cd_offset) = record_volumes.first().copied().unwrap_or(match self.volumes {
+            Some(_) => (last_disk, end_offset),
+            None => (0, cd_offset as u64),
+        });
+        let num_entries_on_disk = match self.volumes {
+            Some(_) => record_volumes.iter().filter(|(disk, _)| *disk == last_disk).count() as u64,
+            None => num_entries_in_directory,
+        };
+
         let cd_offset_u32 = if cd_offset > NON_ZIP64_MAX_SIZE as u64 {
             if self.force_no_zip64 {
-                return Err(crate::error::ZipError::Zip64Needed(crate::error::Zip64ErrorCase::LargeFile));
+                let case = crate::error::Zip64ErrorCase::TooLargeCentralDirectory;
+                return Err(crate::error::ZipError::Zip64Needed(case));
             } else {
                 self.is_zip64 = true;
             }
@@ -194,15 +704,13 @@ impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
 
         // Add the zip64 EOCDR and EOCDL if we are in zip64 mode.
         if self.is_zip64 {
-            let eocdr_offset = self.writer.offset();
-
             let eocdr = Zip64EndOfCentralDirectoryRecord {
                 size_of_zip64_end_of_cd_record: 44,
-                version_made_by: crate::spec::version::as_made_by(),
+                version_made_by: crate::spec::version::as_made_by(AttributeCompatibility::Unix),
                 version_needed_to_extract: 46,
-                disk_number: 0,
-                disk_number_start_of_cd: 0,
-                num_entries_in_directory_on_disk: num_entries_in_directory,
+                disk_number: last_disk,
+                disk_number_start_of_cd: cd_disk,
+                num_entries_in_directory_on_disk: num_entries_on_disk,
                 num_entries_in_directory,
                 directory_size: central_directory_size,
                 offset_of_start_of_directory: cd_offset,
@@ -211,34 +719,163 @@ impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
             self.writer.write_all(&eocdr.as_bytes()).await?;
 
             let eocdl = Zip64EndOfCentralDirectoryLocator {
-                number_of_disk_with_start_of_zip64_end_of_central_directory: 0,
-                relative_offset: eocdr_offset as u64,
-                total_number_of_disks: 1,
+                number_of_disk_with_start_of_zip64_end_of_central_directory: last_disk,
+                relative_offset: end_offset,
+                total_number_of_disks: last_disk + 1,
             };
             self.writer.write_all(&crate::spec::consts::ZIP64_EOCDL_SIGNATURE.to_le_bytes()).await?;
             self.writer.write_all(&eocdl.as_bytes()).await?;
         }
 
         let header = EndOfCentralDirectoryHeader {
-            disk_num: 0,
-            start_cent_dir_disk: 0,
-            num_of_entries_disk: num_entries_in_directory_u16,
+            disk_num: volume_number(last_disk)?,
+            start_cent_dir_disk: volume_number(cd_disk)?,
+            num_of_entries_disk: num_entries_on_disk.try_into().unwrap_or(NON_ZIP64_MAX_NUM_FILES),
             num_of_entries: num_entries_in_directory_u16,
             size_cent_dir: central_directory_size_u32,
             cent_dir_offset: cd_offset_u32,
-            file_comm_length: self.comment_opt.as_ref().map(|v| v.len() as u16).unwrap_or_default(),
+            file_comm_length: comment.len().try_into().map_err(|_| crate::error::ZipError::CommentTooLarge)?,
         };
 
         self.writer.write_all(&crate::spec::consts::EOCDR_SIGNATURE.to_le_bytes()).await?;
         self.writer.write_all(&header.as_slice()).await?;
-        if let Some(comment) = self.comment_opt {
-            self.writer.write_all(comment.as_bytes()).await?;
+        self.writer.write_all(&comment).await?;
+
+        if let Some(truncator) = self.truncator {
+            self.writer.flush().await?;
+            truncator(self.writer.inner_mut()).await?;
         }
 
         Ok(self.writer.into_inner())
     }
 }
 
+impl<W: AsyncWrite + AsyncTruncate + Unpin> ZipFileWriter<W> {
+    /// Consumes this ZIP writer without completing any closing tasks, truncating the inner writer to zero bytes.
+    ///
+    /// This discards the partially written ZIP file (eg. an incomplete file on disk) as per [`ZipFileWriter::abort()`],
+    /// whereas the position of the returned writer is left unchanged. As the whole of the underlying data is truncated,
+    /// this shouldn't be used with a writer constructed via [`ZipFileWriter::append()`], whose existing entries would
+    /// otherwise be lost.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use async_zip::{Compression, ZipEntryBuilder, base::write::ZipFileWriter};
+    /// # use async_zip::error::Result;
+    /// # use futures_util::io::Cursor;
+    /// #
+    /// # async fn run(data: &[u8]) -> Result<()> {
+    /// let mut writer = ZipFileWriter::new(Cursor::new(Vec::new()));
+    ///
+    /// let opts = ZipEntryBuilder::new(String::from("foo.txt").into(), Compression::Stored);
+    /// if writer.write_entry_whole(opts, data).await.is_err() {
+    ///     writer.abort_and_truncate().await?;
+    /// }
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn abort_and_truncate(mut self) -> Result<W> {
+        self.writer.flush().await?;
+        let mut writer = self.writer.into_inner();
+        writer.truncate(0).await?;
+
+        Ok(writer)
+    }
+}
+
+impl<W: AsyncWrite + AsyncRead + AsyncSeek + Unpin> ZipFileWriter<W> {
+    /// Construct a ZIP file writer which appends new entries to an existing ZIP file.
+    ///
+    /// The existing central directory is parsed and then overwritten by the data of any new entries, before being
+    /// rewritten (alongside records for the new entries) when the writer is closed. The existing file comment is
+    /// carried over and the ZIP64 mode of the existing file is retained.
+    ///
+    /// # Note
+    /// The inner writer is truncated to the end of the rewritten ZIP file when closing, so no data from the existing
+    /// one is left behind if the new one is shorter (eg. as the file comment was shortened).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use async_zip::{Compression, ZipEntryBuilder, base::write::ZipFileWriter};
+    /// # use async_zip::error::ZipError;
+    /// # use futures_util::io::Cursor;
+    /// #
+    /// # async fn run(existing: Vec<u8>) -> Result<Vec<u8>, ZipError> {
+    /// let mut writer = ZipFileWriter::append(Cursor::new(existing)).await?;
+    ///
+    /// let opts = ZipEntryBuilder::new(String::from("new.txt").into(), Compression::Stored);
+    /// writer.write_entry_whole(opts, b"This is a new file.").await?;
+    ///
+    /// let data = writer.close().await?.into_inner();
+    /// #   Ok(data)
+    /// # }
+    /// ```
+    pub async fn append(mut writer: W) -> Result<Self>
+    where
+        W: AsyncTruncate + Send,
+    {
+        let file = crate::base::read::file(&mut writer, &ReadOptions::default()).await?;
+        // Offsets are recorded relative to any prepended bytes, so new entries are too.
+        let cd_offset = file.central_directory_offset();
+        let cd_start = cd_offset + file.prepended_bytes();
+        writer.seek(SeekFrom::Start(cd_start)).await?;
+
+        let mut reader = BufReader::new(&mut writer);
+        let mut cd_entries = Vec::with_capacity(file.entries().len());
+
+        for stored_entry in file.entries() {
+            crate::utils::assert_signature(&mut reader, CDH_SIGNATURE).await?;
+            let mut header = CentralDirectoryRecord::from_reader(&mut reader).await?;
+
+            let trailing_length =
+                header.file_name_length as u64 + header.extra_field_length as u64 + header.file_comment_length as u64;
+            futures_util::io::copy(&mut (&mut reader).take(trailing_length), &mut futures_util::io::sink()).await?;
+
+            // The extra fields are re-serialised on close, so ensure the recorded length matches.
+            let entry = stored_entry.entry.clone();
+            header.extra_field_length = entry
+                .extra_fields()
+                .as_bytes()
+                .len()
+                .try_into()
+                .map_err(|_| crate::error::ZipError::ExtraFieldTooLarge)?;
+
+            cd_entries.push(CentralDirectoryEntry { header, entry });
+        }
+
+        writer.seek(SeekFrom::Start(cd_start)).await?;
+
+        let offset = cd_offset.try_into().map_err(|_| crate::error::ZipError::TargetZip64NotSupported)?;
+        let comment_opt = match file.comment().as_bytes() {
+            [] => None,
+            comment => Some(comment.to_vec()),
+        };
+
+        Ok(Self {
+            writer: AsyncOffsetWriter::with_offset(writer, offset),
+            cd_entries,
+            comment_opt,
+            producer_opt: None,
+            is_zip64: file.zip64(),
+            force_no_zip64: false,
+            sort_central_directory: false,
+            default_compression: Compression::Stored,
+            stored_alignment: 0,
+            extension_compression: HashMap::new(),
+            cd_spill: None,
+            clock: None,
+            padded_size: None,
+            truncator: Some(|writer| {
+                Box::pin(async move {
+                    let end = writer.seek(SeekFrom::Current(0)).await?;
+                    writer.truncate(end).await
+                })
+            }),
+            volumes: None,
+        })
+    }
+}
+
 #[cfg(feature = "tokio")]
 impl<W> ZipFileWriter<Compat<W>>
 where
@@ -246,16 +883,62 @@ where
 {
     /// Construct a new ZIP file writer from a mutable reference to a writer.
     pub fn with_tokio(writer: W) -> TokioZipFileWriter<W> {
-        Self {
-            writer: AsyncOffsetWriter::new(writer.compat_write()),
-            cd_entries: Vec::new(),
-            comment_opt: None,
-            is_zip64: false,
-            force_no_zip64: false,
-        }
+        Self::new(writer.compat_write())
     }
 }
 
+#[cfg(feature = "tokio")]
+impl<W> ZipFileWriter<Compat<W>>
+where
+    W: tokio::io::AsyncWrite + tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin + Send,
+    Compat<W>: AsyncTruncate,
+{
+    /// Construct a ZIP file writer which appends new entries to an existing ZIP file (a [`tokio::fs::File`] opened
+    /// for both reading and writing), as per [`ZipFileWriter::append()`].
+    ///
+    /// Any ZIP64 structures of the existing file are carried over, alongside the metadata of its entries.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use async_zip::{Compression, ZipEntryBuilder, tokio::write::ZipFileWriter};
+    /// # use async_zip::error::Result;
+    /// #
+    /// # async fn run() -> Result<()> {
+    /// let file = tokio::fs::OpenOptions::new().read(true).write(true).open("./foo.zip").await?;
+    /// let mut writer = ZipFileWriter::append_to(file).await?;
+    ///
+    /// let opts = ZipEntryBuilder::new(String::from("new.txt").into(), Compression::Stored);
+    /// writer.write_entry_whole(opts, b"This is a new file.").await?;
+    /// writer.close().await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn append_to(writer: W) -> Result<TokioZipFileWriter<W>> {
+        Self::append(writer.compat()).await
+    }
+}
+
+/// Returns an alignment extra field which pads an entry's data to the provided alignment, given the offset at which
+/// its data would start without the field.
+///
+/// This follows the layout used by Android's zipalign: the alignment as a u16, followed by zeroed padding bytes.
+pub(crate) fn alignment_extra_field(unpadded_data_offset: usize, alignment: u16) -> Vec<u8> {
+    if alignment <= 1 {
+        return Vec::new();
+    }
+
+    let alignment_usize = alignment as usize;
+    let padding =
+        (alignment_usize - (unpadded_data_offset + ALIGNMENT_EXTRA_FIELD_LENGTH) % alignment_usize) % alignment_usize;
+
+    let mut field = Vec::with_capacity(ALIGNMENT_EXTRA_FIELD_LENGTH + padding);
+    field.extend_from_slice(&ALIGNMENT_EXTRA_FIELD_ID.to_le_bytes());
+    field.extend_from_slice(&((ALIGNMENT_EXTRA_FIELD_LENGTH - 4 + padding) as u16).to_le_bytes());
+    field.extend_from_slice(&alignment.to_le_bytes());
+    field.resize(ALIGNMENT_EXTRA_FIELD_LENGTH + padding, 0);
+    field
+}
+
 pub(crate) fn get_or_put_info_zip_unicode_path_extra_field_mut(
     extra_fields: &mut Vec<ExtraField>,
 ) -> &mut InfoZipUnicodePathExtraField {
@@ -0,0 +1,82 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::base::read::seek::ZipFileReader;
+use crate::base::write::ZipFileWriter;
+use crate::entry::safety::UnsafeReason;
+use crate::entry::ZipEntry;
+use crate::error::{Result, ZipError};
+use crate::spec::header::ExtraField;
+
+use futures_util::io::{AsyncRead, AsyncSeek, AsyncWrite};
+
+/// Copies every entry of a ZIP file into a writer under a new name, without decompressing or recompressing its data.
+///
+/// Each entry's compressed data is copied verbatim alongside its compression method, CRC32 value, sizes, and other
+/// metadata, whilst its name is replaced by the result of the provided function. Any ZIP64 extended information or
+/// Info-ZIP Unicode Path extra fields are dropped as they'd otherwise be stale, and the central directory is rebuilt
+/// when the writer is closed.
+///
+/// A new name which is [unsafe to extract](ZipEntry::unsafe_reason) (eg. an absolute path or one containing a `..`
+/// component) is rejected with [`ZipError::UnsafeEntryPath`], as is a name which is too long to be stored. Symbolic
+/// links are carried over as-is, whereas entries encrypted with ZipCrypto are rejected as per
+/// [`ZipFileWriter::copy_entry_raw()`].
+///
+/// # Example
+/// ```no_run
+/// # use async_zip::base::{read::seek::ZipFileReader, write::{rewrite_names, ZipFileWriter}};
+/// # use async_zip::error::Result;
+/// # use futures_util::io::Cursor;
+/// #
+/// # async fn run(existing: Vec<u8>) -> Result<Vec<u8>> {
+/// let mut reader = ZipFileReader::new(Cursor::new(existing)).await?;
+/// let mut writer = ZipFileWriter::new(Vec::new());
+///
+/// rewrite_names(&mut reader, &mut writer, |name| format!("payload/{name}")).await?;
+/// writer.close().await
+/// # }
+/// ```
+pub async fn rewrite_names<R, W, F>(src: &mut ZipFileReader<R>, dst: &mut ZipFileWriter<W>, mut rename: F) -> Result<()>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+    W: AsyncWrite + Unpin,
+    F: FnMut(&str) -> String,
+{
+    for index in 0..src.file().entries().len() {
+        let entry = src.file().entries()[index].entry.clone();
+        rewrite_entry(src, dst, index, entry.clone(), &mut rename).await.map_err(|err| err.in_entry(&entry))?;
+    }
+
+    Ok(())
+}
+
+async fn rewrite_entry<R, W, F>(
+    src: &mut ZipFileReader<R>,
+    dst: &mut ZipFileWriter<W>,
+    index: usize,
+    mut entry: ZipEntry,
+    rename: &mut F,
+) -> Result<()>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+    W: AsyncWrite + Unpin,
+    F: FnMut(&str) -> String,
+{
+    let name = rename(entry.filename().as_str()?);
+    if name.len() > u16::MAX as usize {
+        return Err(ZipError::FileNameTooLarge);
+    }
+
+    entry.filename = name.into();
+    entry
+        .extra_fields
+        .retain(|field| !matches!(field, ExtraField::Zip64ExtendedInformation(_) | ExtraField::InfoZipUnicodePath(_)));
+
+    match entry.unsafe_reason() {
+        None | Some(UnsafeReason::Symlink) => (),
+        Some(reason) => return Err(ZipError::UnsafeEntryPath(reason)),
+    }
+
+    let reader = src.raw_reader_without_entry(index).await?;
+    dst.write_entry_raw(entry, reader).await
+}
@@ -4,6 +4,19 @@
 #[cfg(feature = "chrono")]
 use chrono::{DateTime, Datelike, LocalResult, TimeZone, Timelike, Utc};
 
+#[cfg(feature = "time")]
+use time::{Date, Month, OffsetDateTime, Time, UtcOffset};
+
+#[cfg(feature = "tokio-fs")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The number of seconds between the FILETIME epoch (1601-01-01) and the Unix epoch (1970-01-01).
+#[cfg(feature = "tokio-fs")]
+const FILETIME_UNIX_EPOCH_OFFSET: i64 = 11_644_473_600;
+/// The number of 100-nanosecond FILETIME intervals within a second.
+#[cfg(feature = "tokio-fs")]
+const FILETIME_INTERVALS_PER_SECOND: u64 = 10_000_000;
+
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#446
 // https://learn.microsoft.com/en-us/windows/win32/api/oleauto/nf-oleauto-dosdatetimetovarianttime
 
@@ -70,4 +83,186 @@ impl ZipDateTime {
 
         ZipDateTime { date: year | month | day, time: hour | min | second }
     }
+
+    /// Constructs time's [`OffsetDateTime`] representation of this date & time, in UTC.
+    ///
+    /// Returns None if any of its components are out of range (eg. the zeroed date of an entry which doesn't set one).
+    /// Note that this requires the `time` feature.
+    #[cfg(feature = "time")]
+    pub fn as_time(&self) -> Option<OffsetDateTime> {
+        let month = Month::try_from(u8::try_from(self.month()).ok()?).ok()?;
+        let date = Date::from_calendar_date(self.year(), month, self.day() as u8).ok()?;
+        let time = Time::from_hms(self.hour() as u8, self.minute() as u8, self.second() as u8).ok()?;
+
+        Some(date.with_time(time).assume_utc())
+    }
+
+    /// Constructs this date & time from time's [`OffsetDateTime`] representation, converted to UTC.
+    ///
+    /// Note that this requires the `time` feature.
+    #[cfg(feature = "time")]
+    pub fn from_time(dt: &OffsetDateTime) -> Self {
+        let dt = dt.to_offset(UtcOffset::UTC);
+
+        let year: u16 = (((dt.year() - 1980) << 9) & 0xFE00).try_into().unwrap();
+        let month: u16 = (u16::from(u8::from(dt.month())) << 5) & 0x1E0;
+        let day: u16 = u16::from(dt.day()) & 0x1F;
+
+        let hour: u16 = (u16::from(dt.hour()) << 11) & 0xF800;
+        let min: u16 = (u16::from(dt.minute()) << 5) & 0x7E0;
+        let second: u16 = (u16::from(dt.second()) >> 1) & 0x1F;
+
+        ZipDateTime { date: year | month | day, time: hour | min | second }
+    }
+
+    /// Returns this date & time as a [`SystemTime`], interpreting it as UTC as per [`ZipDateTime::as_chrono()`].
+    ///
+    /// Returns None if any of its components are out of range (eg. the zeroed date of an entry which doesn't set one).
+    #[cfg(feature = "tokio-fs")]
+    pub(crate) fn as_system_time(&self) -> Option<SystemTime> {
+        let (month, day) = (self.month() as i64, self.day() as i64);
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        if self.hour() > 23 || self.minute() > 59 || self.second() > 59 {
+            return None;
+        }
+
+        // Days since the Unix epoch for a date within the proleptic Gregorian calendar, as per Howard Hinnant's
+        // `days_from_civil` algorithm.
+        let year = self.year() as i64 - i64::from(month <= 2);
+        let year_of_era = year.rem_euclid(400);
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = year.div_euclid(400) * 146_097 + day_of_era - 719_468;
+
+        let seconds = days * 86_400 + (self.hour() * 3600 + self.minute() * 60 + self.second()) as i64;
+        unix_time(seconds)
+    }
+
+    /// Constructs this date & time from a [`SystemTime`], as UTC and truncated to two seconds.
+    ///
+    /// Returns None if the time falls outside of the years representable by MS-DOS (1980 to 2107).
+    #[cfg(feature = "tokio-fs")]
+    pub(crate) fn from_system_time(time: SystemTime) -> Option<Self> {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => i64::try_from(duration.as_secs()).ok()?,
+            Err(err) => -i64::try_from(err.duration().as_secs()).ok()?,
+        };
+        let (days, seconds_of_day) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+        // The inverse of the above, as per Howard Hinnant's `civil_from_days` algorithm.
+        let days = days + 719_468;
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = days.div_euclid(146_097) * 400 + year_of_era + i64::from(month <= 2);
+
+        if !(1980..=2107).contains(&year) {
+            return None;
+        }
+
+        let (hour, minute, second) = (seconds_of_day / 3600, seconds_of_day / 60 % 60, seconds_of_day % 60);
+        let date = ((year - 1980) << 9 | month << 5 | day) as u16;
+        let time = (hour << 11 | minute << 5 | second >> 1) as u16;
+
+        Some(ZipDateTime { date, time })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime<Utc>> for ZipDateTime {
+    fn from(dt: DateTime<Utc>) -> Self {
+        ZipDateTime::from_chrono(&dt)
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<OffsetDateTime> for ZipDateTime {
+    fn from(dt: OffsetDateTime) -> Self {
+        ZipDateTime::from_time(&dt)
+    }
+}
+
+/// Returns the [`SystemTime`] a number of seconds from the Unix epoch, if representable.
+#[cfg(feature = "tokio-fs")]
+pub(crate) fn unix_time(seconds: i64) -> Option<SystemTime> {
+    match seconds {
+        0.. => UNIX_EPOCH.checked_add(Duration::from_secs(seconds as u64)),
+        _ => UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs())),
+    }
+}
+
+// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#455
+
+/// The file times held within the NTFS extra field (0x000a).
+///
+/// Each time is a Windows FILETIME value: the number of 100-nanosecond intervals since 1601-01-01 00:00:00 UTC.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct NtfsTimes {
+    pub(crate) modified: u64,
+    pub(crate) accessed: u64,
+    pub(crate) created: u64,
+}
+
+impl NtfsTimes {
+    /// Constructs a new set of file times from their FILETIME values.
+    pub fn new(modified: u64, accessed: u64, created: u64) -> Self {
+        Self { modified, accessed, created }
+    }
+
+    /// Returns the last modification time.
+    pub fn modified(&self) -> u64 {
+        self.modified
+    }
+
+    /// Returns the last access time.
+    pub fn accessed(&self) -> u64 {
+        self.accessed
+    }
+
+    /// Returns the creation time.
+    pub fn created(&self) -> u64 {
+        self.created
+    }
+
+    /// Returns the last modification time as a [`SystemTime`], if representable.
+    #[cfg(feature = "tokio-fs")]
+    pub(crate) fn modified_system_time(&self) -> Option<SystemTime> {
+        let seconds = (self.modified / FILETIME_INTERVALS_PER_SECOND) as i64 - FILETIME_UNIX_EPOCH_OFFSET;
+        let nanos = (self.modified % FILETIME_INTERVALS_PER_SECOND) as u32 * 100;
+
+        unix_time(seconds)?.checked_add(Duration::from_nanos(nanos.into()))
+    }
+}
+
+/// The times held within an Info-ZIP extended timestamp extra field (0x5455), as Unix epoch seconds.
+///
+/// Only the times present within the field are returned. The copy within the central directory typically only holds
+/// the modification time, whereas the copy within the local file header may also hold the access and creation times.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ExtendedTimestamp {
+    pub(crate) modified: Option<i32>,
+    pub(crate) accessed: Option<i32>,
+    pub(crate) created: Option<i32>,
+}
+
+impl ExtendedTimestamp {
+    /// Returns the last modification time, if present.
+    pub fn modified(&self) -> Option<i32> {
+        self.modified
+    }
+
+    /// Returns the last access time, if present.
+    pub fn accessed(&self) -> Option<i32> {
+        self.accessed
+    }
+
+    /// Returns the creation time, if present.
+    pub fn created(&self) -> Option<i32> {
+        self.created
+    }
 }
@@ -0,0 +1,206 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! WinZip AES encryption.
+//!
+//! Encrypted data is laid out as a salt, a 2-byte password verifier, the ciphertext, and a 10-byte authentication code.
+//! Keys are derived from the password via PBKDF2-HMAC-SHA1, the data is encrypted with AES in CTR mode (using a
+//! little-endian counter starting at one), and the ciphertext is authenticated with HMAC-SHA1.
+//!
+//! <https://www.winzip.com/en/support/aes-encryption/>
+
+pub(crate) use crate::encryption::extra_field::{AesExtraField, AesVendorVersion};
+
+use crate::encryption::extra_field::{AesStrength, EXTRA_FIELD_DATA_LENGTH, VENDOR_ID};
+use crate::error::{Result, ZipError};
+use crate::spec::header::{ExtraField, HeaderId, UnknownExtraField};
+
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+/// The number of PBKDF2 iterations used to derive the keys.
+const KEY_DERIVATION_ROUNDS: u32 = 1000;
+/// The length of the password verifier which follows the salt.
+pub(crate) const PASSWORD_VERIFIER_LENGTH: usize = 2;
+/// The length of the truncated HMAC-SHA1 authentication code which follows the ciphertext.
+pub(crate) const AUTHENTICATION_CODE_LENGTH: usize = 10;
+
+impl AesStrength {
+    fn as_raw(self) -> u8 {
+        match self {
+            AesStrength::Aes128 => 1,
+            AesStrength::Aes192 => 2,
+            AesStrength::Aes256 => 3,
+        }
+    }
+
+    fn key_length(self) -> usize {
+        match self {
+            AesStrength::Aes128 => 16,
+            AesStrength::Aes192 => 24,
+            AesStrength::Aes256 => 32,
+        }
+    }
+
+    /// Returns the length of the salt which prefixes the encrypted data.
+    pub(crate) fn salt_length(self) -> usize {
+        self.key_length() / 2
+    }
+
+    /// Returns the number of bytes which encryption adds to an entry's compressed data.
+    pub(crate) fn overhead(self) -> u64 {
+        (self.salt_length() + PASSWORD_VERIFIER_LENGTH + AUTHENTICATION_CODE_LENGTH) as u64
+    }
+}
+
+impl AesExtraField {
+    pub(crate) fn as_extra_field(&self) -> ExtraField {
+        let version: u16 = match self.version {
+            AesVendorVersion::Ae1 => 1,
+            AesVendorVersion::Ae2 => 2,
+        };
+
+        let mut content = Vec::with_capacity(EXTRA_FIELD_DATA_LENGTH as usize);
+        content.extend_from_slice(&version.to_le_bytes());
+        content.extend_from_slice(&VENDOR_ID);
+        content.push(self.strength.as_raw());
+        content.extend_from_slice(&u16::from(self.compression).to_le_bytes());
+
+        ExtraField::Unknown(UnknownExtraField {
+            header_id: HeaderId::AES_EXTRA_FIELD,
+            data_size: EXTRA_FIELD_DATA_LENGTH,
+            content,
+        })
+    }
+}
+
+/// The password and key strength with which an entry is to be encrypted when written.
+#[derive(Clone)]
+pub(crate) struct AesEncryption {
+    pub(crate) password: Vec<u8>,
+    pub(crate) strength: AesStrength,
+}
+
+impl std::fmt::Debug for AesEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AesEncryption").field("password", &"<redacted>").field("strength", &self.strength).finish()
+    }
+}
+
+enum BlockCipher {
+    Aes128(aes::Aes128),
+    Aes192(aes::Aes192),
+    Aes256(aes::Aes256),
+}
+
+impl BlockCipher {
+    fn new(key: &[u8]) -> Self {
+        // The key's length always matches its strength as it's derived from it, so construction can't fail.
+        match key.len() {
+            16 => BlockCipher::Aes128(aes::Aes128::new(GenericArray::from_slice(key))),
+            24 => BlockCipher::Aes192(aes::Aes192::new(GenericArray::from_slice(key))),
+            _ => BlockCipher::Aes256(aes::Aes256::new(GenericArray::from_slice(key))),
+        }
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; 16]) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            BlockCipher::Aes128(cipher) => cipher.encrypt_block(block),
+            BlockCipher::Aes192(cipher) => cipher.encrypt_block(block),
+            BlockCipher::Aes256(cipher) => cipher.encrypt_block(block),
+        }
+    }
+}
+
+/// The AES-CTR keystream and HMAC-SHA1 state for a single entry.
+pub(crate) struct AesCipher {
+    cipher: BlockCipher,
+    mac: Hmac<Sha1>,
+    counter: u128,
+    keystream: [u8; 16],
+    used: usize,
+}
+
+impl AesCipher {
+    /// Derives the keys for an entry from its password and salt, returning the cipher and the expected password
+    /// verifier.
+    pub(crate) fn new(password: &[u8], salt: &[u8], strength: AesStrength) -> (Self, [u8; PASSWORD_VERIFIER_LENGTH]) {
+        let key_length = strength.key_length();
+        let mut derived = vec![0; 2 * key_length + PASSWORD_VERIFIER_LENGTH];
+        pbkdf2::pbkdf2::<Hmac<Sha1>>(password, salt, KEY_DERIVATION_ROUNDS, &mut derived);
+
+        let (encryption_key, rest) = derived.split_at(key_length);
+        let (authentication_key, verifier) = rest.split_at(key_length);
+
+        let cipher = Self {
+            cipher: BlockCipher::new(encryption_key),
+            mac: <Hmac<Sha1> as Mac>::new_from_slice(authentication_key).expect("HMAC accepts keys of any length"),
+            counter: 0,
+            keystream: [0; 16],
+            used: 16,
+        };
+
+        (cipher, [verifier[0], verifier[1]])
+    }
+
+    /// Decrypts data in place, having first authenticated the ciphertext.
+    pub(crate) fn decrypt(&mut self, data: &mut [u8]) {
+        self.mac.update(data);
+        self.apply_keystream(data);
+    }
+
+    /// Encrypts data in place, then authenticates the ciphertext.
+    pub(crate) fn encrypt(&mut self, data: &mut [u8]) {
+        self.apply_keystream(data);
+        self.mac.update(data);
+    }
+
+    /// Verifies the authentication code which follows the ciphertext, in constant time.
+    pub(crate) fn verify(self, code: &[u8]) -> Result<()> {
+        self.mac.verify_truncated_left(code).map_err(|_| ZipError::WrongPassword)
+    }
+
+    /// Returns the authentication code to be written after the ciphertext.
+    pub(crate) fn authentication_code(self) -> [u8; AUTHENTICATION_CODE_LENGTH] {
+        let code = self.mac.finalize().into_bytes();
+        let mut truncated = [0; AUTHENTICATION_CODE_LENGTH];
+        truncated.copy_from_slice(&code[..AUTHENTICATION_CODE_LENGTH]);
+        truncated
+    }
+
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data {
+            if self.used == self.keystream.len() {
+                self.counter = self.counter.wrapping_add(1);
+                self.keystream = self.counter.to_le_bytes();
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.used = 0;
+            }
+
+            *byte ^= self.keystream[self.used];
+            self.used += 1;
+        }
+    }
+}
+
+/// Encrypts an entry's compressed data, returning it prefixed by a random salt and the password verifier, and suffixed
+/// by the authentication code.
+pub(crate) fn encrypt(data: &[u8], encryption: &AesEncryption) -> Result<Vec<u8>> {
+    let salt_length = encryption.strength.salt_length();
+    let mut output = Vec::with_capacity(data.len() + encryption.strength.overhead() as usize);
+
+    output.resize(salt_length, 0);
+    getrandom::getrandom(&mut output).map_err(std::io::Error::from)?;
+
+    let (mut cipher, verifier) = AesCipher::new(&encryption.password, &output, encryption.strength);
+    output.extend_from_slice(&verifier);
+
+    let start = output.len();
+    output.extend_from_slice(data);
+    cipher.encrypt(&mut output[start..]);
+    output.extend_from_slice(&cipher.authentication_code());
+
+    Ok(output)
+}
@@ -0,0 +1,114 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! The AES extra field (0x9901), which describes how an entry was encrypted with WinZip's AES scheme.
+//!
+//! This is parsed regardless of whether the `aes` feature is enabled, so that encrypted entries can be inspected.
+//!
+//! <https://www.winzip.com/en/support/aes-encryption/>
+
+use crate::spec::header::{ExtraField, HeaderId};
+use crate::spec::Compression;
+
+/// The vendor ID held within the AES extra field.
+pub(crate) const VENDOR_ID: [u8; 2] = *b"AE";
+/// The length of the AES extra field's data.
+pub(crate) const EXTRA_FIELD_DATA_LENGTH: u16 = 7;
+
+/// The strength of the AES key used to encrypt an entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AesStrength {
+    Aes128,
+    Aes192,
+    Aes256,
+}
+
+impl AesStrength {
+    fn from_raw(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(AesStrength::Aes128),
+            2 => Some(AesStrength::Aes192),
+            3 => Some(AesStrength::Aes256),
+            _ => None,
+        }
+    }
+}
+
+/// The version of WinZip's AES scheme used to encrypt an entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AesVendorVersion {
+    /// The entry's CRC32 value is stored as normal.
+    Ae1,
+    /// The entry's CRC32 value is zero, leaving the authentication code as the sole integrity check.
+    Ae2,
+}
+
+/// The contents of the AES extra field (0x9901).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AesExtraField {
+    pub(crate) version: AesVendorVersion,
+    pub(crate) strength: AesStrength,
+    pub(crate) compression: Compression,
+}
+
+impl AesExtraField {
+    /// Locates and parses the AES extra field, if one is present.
+    pub(crate) fn from_extra_fields(fields: &[ExtraField]) -> Option<Self> {
+        fields.iter().find_map(|field| match field {
+            ExtraField::Unknown(field) if field.header_id == HeaderId::AES_EXTRA_FIELD => {
+                Self::from_bytes(&field.content)
+            }
+            _ => None,
+        })
+    }
+
+    fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < EXTRA_FIELD_DATA_LENGTH as usize || data[2..4] != VENDOR_ID {
+            return None;
+        }
+
+        let version = match u16::from_le_bytes([data[0], data[1]]) {
+            1 => AesVendorVersion::Ae1,
+            2 => AesVendorVersion::Ae2,
+            _ => return None,
+        };
+        let strength = AesStrength::from_raw(data[4])?;
+        let compression = Compression::from_raw(u16::from_le_bytes([data[5], data[6]]));
+
+        Some(Self { version, strength, compression })
+    }
+}
+
+/// How an entry was encrypted with WinZip's AES scheme, as described by its AES extra field (0x9901).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AesInfo {
+    version: AesVendorVersion,
+    strength: AesStrength,
+    compression: Compression,
+}
+
+impl AesInfo {
+    /// Returns the version of the scheme used to encrypt the entry.
+    pub fn version(&self) -> AesVendorVersion {
+        self.version
+    }
+
+    /// Returns the strength of the key used to encrypt the entry.
+    pub fn strength(&self) -> AesStrength {
+        self.strength
+    }
+
+    /// Returns the compression method of the entry's data prior to encryption.
+    ///
+    /// The entry's own compression method is always 99 when encrypted with AES, so this is the method its decrypted
+    /// data must be decompressed with.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+}
+
+impl From<AesExtraField> for AesInfo {
+    fn from(field: AesExtraField) -> Self {
+        Self { version: field.version, strength: field.strength, compression: field.compression }
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A module which supports the encryption and decryption of entry data.
+//!
+//! Entries encrypted with traditional PKWARE encryption (ZipCrypto) can be read, as can those encrypted with WinZip's AES
+//! scheme (AE-1 and AE-2) when the `aes` feature is enabled. Only the latter can be written, as ZipCrypto is trivially
+//! broken. Entries are written as AE-2, which omits the CRC32 value in favour of the HMAC-SHA1 authentication code
+//! appended to the encrypted data.
+//!
+//! How an entry was encrypted with AES can be inspected via [`ZipEntry::aes_info()`] even without the `aes` feature.
+//!
+//! [`ZipEntry::aes_info()`]: crate::ZipEntry::aes_info
+
+#[cfg(feature = "aes")]
+pub(crate) mod aes;
+pub(crate) mod extra_field;
+pub(crate) mod zip_crypto;
+
+pub use extra_field::{AesInfo, AesStrength, AesVendorVersion};
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! Traditional PKWARE encryption (ZipCrypto).
+//!
+//! Encrypted data is prefixed by a 12-byte header, the last byte of which is compared against the high byte of the
+//! entry's CRC32 value (or of its last modification time if it uses a data descriptor) to reject most wrong passwords
+//! before any data is decrypted.
+//!
+//! <https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#61-traditional-pkware-decryption>
+
+use crate::entry::ZipEntry;
+
+/// The length of the encryption header which prefixes the encrypted data.
+pub(crate) const HEADER_LENGTH: usize = 12;
+
+/// The CRC32 lookup table used to update the keys, as per the reflected 0xEDB88320 polynomial.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut index = 0;
+
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            value = if value & 1 == 1 { (value >> 1) ^ 0xEDB88320 } else { value >> 1 };
+            bit += 1;
+        }
+
+        table[index] = value;
+        index += 1;
+    }
+
+    table
+};
+
+/// The three keys which make up the cipher's state.
+pub(crate) struct ZipCryptoKeys([u32; 3]);
+
+impl ZipCryptoKeys {
+    /// Initialises the keys from the provided password.
+    pub(crate) fn new(password: &[u8]) -> Self {
+        let mut keys = Self([0x12345678, 0x23456789, 0x34567890]);
+
+        for byte in password {
+            keys.update(*byte);
+        }
+
+        keys
+    }
+
+    /// Decrypts data in place.
+    pub(crate) fn decrypt(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte ^= self.stream_byte();
+            self.update(*byte);
+        }
+    }
+
+    fn stream_byte(&self) -> u8 {
+        let temp = (self.0[2] | 2) & 0xFFFF;
+        (temp.wrapping_mul(temp ^ 1) >> 8) as u8
+    }
+
+    fn update(&mut self, byte: u8) {
+        self.0[0] = crc32_update(self.0[0], byte);
+        self.0[1] = self.0[1].wrapping_add(self.0[0] & 0xFF).wrapping_mul(134775813).wrapping_add(1);
+        self.0[2] = crc32_update(self.0[2], (self.0[1] >> 24) as u8);
+    }
+}
+
+fn crc32_update(crc: u32, byte: u8) -> u32 {
+    (crc >> 8) ^ CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize]
+}
+
+/// Returns the value which the last byte of an entry's decrypted encryption header is expected to hold.
+pub(crate) fn check_byte(entry: &ZipEntry) -> u8 {
+    if entry.general_purpose_flag.data_descriptor {
+        (entry.last_modification_date().time >> 8) as u8
+    } else {
+        (entry.crc32() >> 24) as u8
+    }
+}
@@ -2,8 +2,14 @@
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
 use crate::entry::ZipEntry;
-use crate::spec::{attribute::AttributeCompatibility, header::ExtraField, Compression};
-use crate::{date::ZipDateTime, string::ZipString};
+#[cfg(feature = "encoding")]
+use crate::error::{Result, ZipError};
+use crate::spec::header::{ExtendedTimestampExtraField, ExtraField, NtfsAttribute, NtfsExtraField};
+use crate::spec::{attribute::AttributeCompatibility, Compression};
+use crate::{
+    date::{ExtendedTimestamp, NtfsTimes, ZipDateTime},
+    string::ZipString,
+};
 
 /// A builder for [`ZipEntry`].
 pub struct ZipEntryBuilder(pub(crate) ZipEntry);
@@ -28,6 +34,28 @@ impl ZipEntryBuilder {
         self
     }
 
+    /// Encodes the entry's filename into the provided legacy codepage (eg. Shift-JIS).
+    ///
+    /// The encoded name is written as the entry's filename without the UTF-8 flag (bit 11) being set, so that legacy
+    /// consumers expecting that codepage can decode it. The original name is retained within an Info-ZIP Unicode Path
+    /// extra field for readers which support it.
+    ///
+    /// Errors if the filename isn't valid UTF-8, or if it contains any characters which can't be represented in the
+    /// provided encoding.
+    #[cfg(feature = "encoding")]
+    pub fn filename_encoding(mut self, encoding: &'static encoding_rs::Encoding) -> Result<Self> {
+        let filename = self.0.filename.as_str()?.to_string();
+        let (encoded, _, had_errors) = encoding.encode(&filename);
+
+        if had_errors {
+            return Err(ZipError::StringNotRepresentable(encoding.name()));
+        }
+
+        let encoded = encoded.into_owned();
+        self.0.filename = ZipString::new_with_alternative(filename, encoded);
+        Ok(self)
+    }
+
     /// Sets the entry's compression method.
     pub fn compression(mut self, compression: Compression) -> Self {
         self.0.compression = compression;
@@ -43,6 +71,19 @@ impl ZipEntryBuilder {
         self
     }
 
+    /// Sets the entry's CRC32 value and both of its sizes, as describe data which has already been compressed.
+    ///
+    /// This is intended for use with [`ZipFileWriter::write_entry_raw()`], which writes these values as-is so that the
+    /// entry's local file header is complete and no data descriptor is needed.
+    ///
+    /// [`ZipFileWriter::write_entry_raw()`]: crate::base::write::ZipFileWriter::write_entry_raw
+    pub fn with_crc_and_sizes(mut self, crc: u32, compressed_size: u64, uncompressed_size: u64) -> Self {
+        self.0.crc32 = crc;
+        self.0.compressed_size = compressed_size;
+        self.0.uncompressed_size = uncompressed_size;
+        self
+    }
+
     /// Set the deflate compression option.
     ///
     /// If the compression type isn't deflate, this option has no effect.
@@ -52,6 +93,56 @@ impl ZipEntryBuilder {
         self
     }
 
+    /// Set the level at which the entry's data is compressed.
+    ///
+    /// [`Level::Fastest`](crate::Level::Fastest) and [`Level::Best`](crate::Level::Best) trade compression ratio for
+    /// speed and vice versa, whereas [`Level::Precise`](crate::Level::Precise) selects a specific level (eg. `0` to `9`
+    /// for Deflate), clamped to the range supported by the compression method. If unset,
+    /// [`Level::Default`](crate::Level::Default) is used. This option has no effect on stored entries.
+    #[cfg(any(
+        feature = "deflate",
+        feature = "bzip2",
+        feature = "zstd",
+        feature = "lzma",
+        feature = "xz",
+        feature = "deflate64"
+    ))]
+    pub fn compression_level(mut self, level: crate::Level) -> Self {
+        self.0.compression_level = level;
+        self
+    }
+
+    /// Set a preset dictionary used when compressing the entry with the Deflate compression method.
+    ///
+    /// A dictionary containing content common to many small entries can considerably improve their compression ratio.
+    /// As the ZIP format has no standard way to signal that a dictionary was used, the same dictionary must be agreed
+    /// upon out-of-band and provided when reading via
+    /// [`ReadOptions::deflate_dictionary()`](crate::base::read::ReadOptions::deflate_dictionary). Other readers won't
+    /// be able to decompress such entries.
+    ///
+    /// This is only supported for entries written via
+    /// [`ZipFileWriter::write_entry_whole()`](crate::base::write::ZipFileWriter::write_entry_whole). If the
+    /// compression type isn't deflate, this option has no effect.
+    #[cfg(feature = "deflate-dictionary")]
+    pub fn deflate_dictionary(mut self, dictionary: Vec<u8>) -> Self {
+        self.0.deflate_dictionary = Some(dictionary);
+        self
+    }
+
+    /// Encrypt the entry's data with the provided password using WinZip's AES scheme.
+    ///
+    /// The entry is written as AE-2, so its CRC32 value is recorded as zero and the authentication code appended to
+    /// the encrypted data is relied upon to detect corruption instead. Its compression method is moved into an AES extra
+    /// field (0x9901), with the method recorded in its headers being set to 99.
+    ///
+    /// This is only supported for entries written via
+    /// [`ZipFileWriter::write_entry_whole()`](crate::base::write::ZipFileWriter::write_entry_whole).
+    #[cfg(feature = "aes")]
+    pub fn encrypt_aes(mut self, password: &[u8], strength: crate::encryption::AesStrength) -> Self {
+        self.0.aes_encryption = Some(crate::encryption::aes::AesEncryption { password: password.to_vec(), strength });
+        self
+    }
+
     /// Sets the entry's attribute host compatibility.
     pub fn attribute_compatibility(mut self, compatibility: AttributeCompatibility) -> Self {
         self.0.attribute_compatibility = compatibility;
@@ -61,6 +152,7 @@ impl ZipEntryBuilder {
     /// Sets the entry's last modification date.
     pub fn last_modification_date(mut self, date: ZipDateTime) -> Self {
         self.0.last_modification_date = date;
+        self.0.date_set = true;
         self
     }
 
@@ -76,9 +168,34 @@ impl ZipEntryBuilder {
         self
     }
 
-    /// Sets the entry's extra field data.
+    /// Sets the entry's extra field data, written within both its local file header and central directory record.
+    ///
+    /// Fields are written in ascending order of their header IDs rather than the order provided, so that archives are
+    /// reproducible byte-for-byte.
     pub fn extra_fields(mut self, field: Vec<ExtraField>) -> Self {
         self.0.extra_fields = field;
+        self.0.local_extra_fields = None;
+        self
+    }
+
+    /// Sets the extra field data written only within the entry's local file header.
+    ///
+    /// By default, the local file header holds the same fields as the central directory record, as set via
+    /// [`ZipEntryBuilder::extra_fields()`] (which resets this). Some fields legitimately differ between the two (eg. an
+    /// extended timestamp field may only carry its access and creation times locally), so this allows reproducing
+    /// such ZIP files. Fields managed by the writer (eg. ZIP64 extended information) are still written to both.
+    pub fn local_extra_field(mut self, fields: Vec<ExtraField>) -> Self {
+        self.0.local_extra_fields = Some(fields);
+        self
+    }
+
+    /// Sets the extra field data written only within the entry's central directory record.
+    ///
+    /// Unless they were already set separately via [`ZipEntryBuilder::local_extra_field()`], the local file header
+    /// keeps the fields previously shared by both.
+    pub fn central_extra_field(mut self, fields: Vec<ExtraField>) -> Self {
+        let previous = std::mem::replace(&mut self.0.extra_fields, fields);
+        self.0.local_extra_fields.get_or_insert(previous);
         self
     }
 
@@ -88,13 +205,74 @@ impl ZipEntryBuilder {
         self
     }
 
-    /// Sets the entry's Unix permissions mode.
+    /// Sets the entry's Unix mode (file type and permission bits, eg. `0o100755`).
     ///
-    /// If the attribute host compatibility isn't set to Unix, this will have no effect.
-    pub fn unix_permissions(mut self, mode: u16) -> Self {
-        if matches!(self.0.attribute_compatibility, AttributeCompatibility::Unix) {
-            self.0.external_file_attribute = (self.0.external_file_attribute & 0xFFFF) | (mode as u32) << 16;
-        }
+    /// The mode is packed into the upper 16 bits of the external file attribute, and the attribute host compatibility
+    /// is set to Unix so that it's recorded within the "version made by" field. Otherwise, most tools would ignore the
+    /// mode when extracting.
+    pub fn unix_permissions(mut self, mode: u32) -> Self {
+        self.0.attribute_compatibility = AttributeCompatibility::Unix;
+        self.0.external_file_attribute = (self.0.external_file_attribute & 0xFFFF) | (mode & 0xFFFF) << 16;
+        self
+    }
+
+    /// Sets the entry's high-resolution file times, to be written within an NTFS extra field (0x000a).
+    ///
+    /// Any existing NTFS extra field is replaced, so this should be called after [`ZipEntryBuilder::extra_fields()`].
+    /// The last modification date held within the entry's headers is left as-is.
+    pub fn ntfs_times(mut self, times: NtfsTimes) -> Self {
+        self.0.extra_fields.retain(|field| !matches!(field, ExtraField::Ntfs(_)));
+        self.0
+            .extra_fields
+            .push(ExtraField::Ntfs(NtfsExtraField { reserved: 0, attributes: vec![NtfsAttribute::Times(times)] }));
+        self
+    }
+
+    /// Sets the entry's last modification time as Unix epoch seconds, to be written within an Info-ZIP extended
+    /// timestamp extra field (0x5455).
+    ///
+    /// As only the modification time is written, the field is identical within the local file header and the central
+    /// directory. Any existing extended timestamp field is replaced, so this should be called after
+    /// [`ZipEntryBuilder::extra_fields()`]. The last modification date held within the entry's headers is left as-is.
+    pub fn last_modification_unix_time(mut self, seconds: i32) -> Self {
+        let times = ExtendedTimestamp { modified: Some(seconds), accessed: None, created: None };
+        self.0.extra_fields.retain(|field| !matches!(field, ExtraField::ExtendedTimestamp(_)));
+        self.0.extra_fields.push(ExtraField::ExtendedTimestamp(ExtendedTimestampExtraField { flags: 0x01, times }));
+        self
+    }
+
+    /// Sets the alignment, in bytes, of the entry's data within the ZIP file.
+    ///
+    /// When writing the entry, its local file header's extra field will be padded so that its data starts at an offset
+    /// which is a multiple of the alignment (as done by Android's zipalign). The padding isn't written to the central
+    /// directory. An alignment of zero or one disables padding, which is the default.
+    pub fn align(mut self, alignment: u16) -> Self {
+        self.0.alignment = alignment;
+        self
+    }
+
+    /// Sets the entry's general purpose bit flags.
+    ///
+    /// The bits which describe how the entry is written are always determined by the writer, so bits 0 (encrypted), 3
+    /// (data descriptor), and 11 (UTF-8 filename and comment) are ignored here. Bit 1 is set by the writer for streamed
+    /// LZMA entries, whose end is marked within the data itself, but may otherwise be set here (eg. to record a deflate
+    /// compression option). All remaining bits are written as provided and can be read back via
+    /// [`ZipEntry::general_purpose_flags()`].
+    pub fn general_purpose_flags(mut self, flags: u16) -> Self {
+        let flags = crate::spec::header::GeneralPurposeFlag::from(flags);
+        self.0.general_purpose_flag.lzma_eos_marker = flags.lzma_eos_marker;
+        self.0.general_purpose_flag.other = flags.other;
+        self
+    }
+
+    /// Sets whether the entry is stored rather than compressed if its data is empty.
+    ///
+    /// Compressing zero bytes still produces a small amount of output (eg. an empty deflate block), so by default,
+    /// entries written with [`ZipFileWriter::write_entry_whole()`](crate::base::write::ZipFileWriter::write_entry_whole)
+    /// whose data is empty are written using [`Compression::Stored`] with a compressed size of zero. This has no effect
+    /// on streamed entries, as their size isn't known until after their compression method has been written.
+    pub fn no_compression_for_empty(mut self, enabled: bool) -> Self {
+        self.0.store_if_empty = enabled;
         self
     }
 
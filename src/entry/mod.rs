@@ -2,23 +2,30 @@
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
 pub mod builder;
+pub(crate) mod safety;
 
+use std::borrow::Cow;
 use std::ops::Deref;
 
 use futures_util::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, SeekFrom};
 
+use crate::encryption::extra_field::AesExtraField;
+use crate::encryption::{zip_crypto, AesInfo};
 use crate::entry::builder::ZipEntryBuilder;
+use crate::entry::safety::{UnsafeReason, UNIX_FILE_TYPE_MASK};
 use crate::error::{Result, ZipError};
 use crate::spec::{
     attribute::AttributeCompatibility,
-    consts::LFH_SIGNATURE,
-    header::{ExtraField, LocalFileHeader},
+    consts::{LFH_LENGTH, LFH_SIGNATURE, SIGNATURE_LENGTH},
+    header::{ExtraField, GeneralPurposeFlag, InfoZipUnicodeCommentExtraField, LocalFileHeader, NtfsAttribute},
     Compression,
 };
-use crate::{
-    string::{StringEncoding, ZipString},
-    ZipDateTime,
-};
+use crate::{string::ZipString, ExtendedTimestamp, NtfsTimes, ZipDateTime};
+
+/// The MS-DOS attribute bit which marks an entry as a directory.
+const MSDOS_DIRECTORY_ATTRIBUTE: u32 = 0x10;
+/// The file type bits of a Unix mode which identify a directory.
+const UNIX_DIRECTORY_MODE: u32 = 0o040000;
 
 /// An immutable store of data about a ZIP entry.
 ///
@@ -43,10 +50,21 @@ pub struct ZipEntry {
     pub(crate) compressed_size: u64,
     pub(crate) attribute_compatibility: AttributeCompatibility,
     pub(crate) last_modification_date: ZipDateTime,
+    /// Whether the last modification date was set explicitly (or read from an existing entry), rather than defaulted.
+    pub(crate) date_set: bool,
     pub(crate) internal_file_attribute: u16,
     pub(crate) external_file_attribute: u32,
     pub(crate) extra_fields: Vec<ExtraField>,
+    pub(crate) local_extra_fields: Option<Vec<ExtraField>>,
     pub(crate) comment: ZipString,
+    pub(crate) general_purpose_flag: GeneralPurposeFlag,
+    pub(crate) alignment: u16,
+    pub(crate) store_if_empty: bool,
+    pub(crate) index: usize,
+    #[cfg(feature = "deflate-dictionary")]
+    pub(crate) deflate_dictionary: Option<Vec<u8>>,
+    #[cfg(feature = "aes")]
+    pub(crate) aes_encryption: Option<crate::encryption::aes::AesEncryption>,
 }
 
 impl From<ZipEntryBuilder> for ZipEntry {
@@ -74,10 +92,20 @@ impl ZipEntry {
             compressed_size: 0,
             attribute_compatibility: AttributeCompatibility::Unix,
             last_modification_date: ZipDateTime::default(),
+            date_set: false,
             internal_file_attribute: 0,
             external_file_attribute: 0,
             extra_fields: Vec::new(),
+            local_extra_fields: None,
             comment: String::new().into(),
+            general_purpose_flag: GeneralPurposeFlag::default(),
+            alignment: 0,
+            store_if_empty: true,
+            index: 0,
+            #[cfg(feature = "deflate-dictionary")]
+            deflate_dictionary: None,
+            #[cfg(feature = "aes")]
+            aes_encryption: None,
         }
     }
 
@@ -96,6 +124,14 @@ impl ZipEntry {
         self.compression
     }
 
+    /// Returns whether or not the entry's data can be read with the features enabled in the current build.
+    ///
+    /// Entries which use a compression method that isn't supported by this build will error when opened for reading,
+    /// so this can be used to skip over them ahead of time.
+    pub fn is_supported(&self) -> bool {
+        self.compression.is_supported()
+    }
+
     /// Returns the entry's CRC32 value.
     pub fn crc32(&self) -> u32 {
         self.crc32
@@ -131,31 +167,255 @@ impl ZipEntry {
         self.external_file_attribute
     }
 
+    /// Returns the entry's general purpose bit flags, as recorded within its central directory record.
+    ///
+    /// Entries read via the streaming reader only have their local file header available, so its flags are returned
+    /// instead.
+    pub fn general_purpose_flags(&self) -> u16 {
+        u16::from_le_bytes(self.general_purpose_flag.as_slice())
+    }
+
+    /// Returns whether or not the entry's data is encrypted (general purpose bit 0).
+    pub fn is_encrypted(&self) -> bool {
+        self.general_purpose_flag.encrypted
+    }
+
+    /// Returns whether or not the entry's CRC32 value and sizes were written within a data descriptor following its
+    /// data (general purpose bit 3).
+    pub fn has_data_descriptor(&self) -> bool {
+        self.general_purpose_flag.data_descriptor
+    }
+
+    /// Returns whether or not the entry's filename and comment are flagged as being encoded as UTF-8 (general purpose
+    /// bit 11).
+    pub fn is_utf8(&self) -> bool {
+        self.general_purpose_flag.filename_unicode
+    }
+
     /// Returns the entry's extra field data.
     pub fn extra_fields(&self) -> &[ExtraField] {
         &self.extra_fields
     }
 
+    /// Returns the extra fields to be written within the entry's local file header.
+    ///
+    /// Unless they were set separately, these are the same as those written within its central directory record. If
+    /// they were, any fields managed by the writer (ie. ZIP64 extended information and the Info-ZIP Unicode fields) are
+    /// still carried over, as they must describe the local file header too.
+    pub(crate) fn local_extra_fields(&self) -> Cow<'_, [ExtraField]> {
+        let Some(local) = self.local_extra_fields.as_ref() else {
+            return Cow::Borrowed(&self.extra_fields);
+        };
+
+        let managed = self.extra_fields.iter().filter(|field| {
+            matches!(
+                field,
+                ExtraField::Zip64ExtendedInformation(_)
+                    | ExtraField::InfoZipUnicodePath(_)
+                    | ExtraField::InfoZipUnicodeComment(_)
+            )
+        });
+        Cow::Owned(local.iter().chain(managed).cloned().collect())
+    }
+
     /// Returns the entry's file comment.
     pub fn comment(&self) -> &ZipString {
         &self.comment
     }
 
-    /// Returns the entry's integer-based UNIX permissions.
+    /// Returns the entry's file comment exactly as it was stored, before any decoding.
+    ///
+    /// Whereas [`ZipEntry::comment()`] may prefer a comment held within an Info-ZIP Unicode Comment extra field, this
+    /// always returns the bytes of the comment field itself, which needn't be valid UTF-8.
+    pub fn comment_bytes(&self) -> &[u8] {
+        self.comment.alternative().unwrap_or_else(|| self.comment.as_bytes())
+    }
+
+    /// Returns the entry's file comment as a string, replacing any invalid UTF-8 sequences with
+    /// [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER).
+    pub fn comment_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.comment.as_bytes())
+    }
+
+    /// Returns the entry's index within the central directory of the ZIP file it was read from.
+    ///
+    /// This is the index accepted by a reader's `reader_with_entry()` and `reader_without_entry()` methods, allowing an
+    /// entry's data to be read when only a reference to the entry is at hand (eg. within a callback). Entries which
+    /// weren't read from a central directory (eg. those constructed via a builder or read via the stream reader) have
+    /// an index of zero.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the entry's filename exactly as it was stored, before any decoding.
+    ///
+    /// Whereas [`ZipEntry::filename()`] may prefer a name held within an Info-ZIP Unicode Path extra field, this always
+    /// returns the bytes of the name field itself (from the central directory, or the local file header when stream
+    /// reading). No separator normalisation is applied to either.
+    pub fn raw_name_bytes(&self) -> &[u8] {
+        self.filename.alternative().unwrap_or_else(|| self.filename.as_bytes())
+    }
+
+    /// Returns the entry's comment as carried by an Info-ZIP Unicode Comment extra field (0x6375), if present.
+    ///
+    /// The value is only returned if the field's CRC32 value matches that of the entry's non-Unicode comment and it's
+    /// valid UTF-8. When this is the case, [`ZipEntry::comment()`] will also prefer this value.
+    pub fn unicode_comment(&self) -> Option<String> {
+        let basic = self.comment_bytes();
+
+        self.extra_fields.iter().find_map(|field| match field {
+            ExtraField::InfoZipUnicodeComment(InfoZipUnicodeCommentExtraField::V1 { crc32, unicode })
+                if *crc32 == crc32fast::hash(basic) =>
+            {
+                String::from_utf8(unicode.clone()).ok()
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the entry's high-resolution file times as carried by an NTFS extra field (0x000a), if present.
+    pub fn ntfs_times(&self) -> Option<NtfsTimes> {
+        self.extra_fields.iter().find_map(|field| match field {
+            ExtraField::Ntfs(field) => field.attributes.iter().find_map(|attribute| match attribute {
+                NtfsAttribute::Times(times) => Some(*times),
+                NtfsAttribute::Unknown { .. } => None,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Returns how the entry was encrypted with WinZip's AES scheme, as described by its AES extra field (0x9901), if
+    /// present.
+    ///
+    /// This is available regardless of whether the `aes` feature is enabled, so encrypted entries can be inspected
+    /// without being decrypted.
+    pub fn aes_info(&self) -> Option<AesInfo> {
+        AesExtraField::from_extra_fields(&self.extra_fields).map(AesInfo::from)
+    }
+
+    /// Returns the entry's times as carried by an Info-ZIP extended timestamp extra field (0x5455), if present.
     ///
     /// # Note
-    /// This will return None if the attribute host compatibility is not listed as Unix.
-    pub fn unix_permissions(&self) -> Option<u16> {
+    /// Entries read from the central directory typically only carry the modification time, whereas those read via
+    /// the stream reader carry whichever times the local file header holds.
+    pub fn extended_timestamp(&self) -> Option<ExtendedTimestamp> {
+        self.extra_fields.iter().find_map(|field| match field {
+            ExtraField::ExtendedTimestamp(field) => Some(field.times),
+            _ => None,
+        })
+    }
+
+    /// Returns the entry's Unix mode (file type and permission bits), as held within the upper 16 bits of its external
+    /// file attribute.
+    ///
+    /// # Note
+    /// This will return None if the attribute host compatibility (taken from the "version made by" field of the
+    /// central directory record) is not Unix.
+    pub fn unix_permissions(&self) -> Option<u32> {
         if !matches!(self.attribute_compatibility, AttributeCompatibility::Unix) {
             return None;
         }
 
-        Some(((self.external_file_attribute) >> 16) as u16)
+        Some(self.external_file_attribute >> 16)
+    }
+
+    /// Returns a new builder pre-populated with this entry's writable metadata.
+    ///
+    /// This includes the filename, compression method, comment, last modification date, attributes, and extra fields,
+    /// and is useful for faithfully recreating an entry read from one ZIP file within another. The CRC32 value and sizes
+    /// are reset as they'll be recomputed upon writing, as is any ZIP64 extended information extra field.
+    pub fn to_builder(&self) -> ZipEntryBuilder {
+        let mut entry = self.clone();
+        entry.crc32 = 0;
+        entry.uncompressed_size = 0;
+        entry.compressed_size = 0;
+        entry.extra_fields.retain(|field| !matches!(field, ExtraField::Zip64ExtendedInformation(_)));
+
+        ZipEntryBuilder(entry)
     }
 
     /// Returns whether or not the entry represents a directory.
+    ///
+    /// An entry is considered a directory if its filename ends with a `/`, or if its external file attribute marks it
+    /// as one (see [`ZipEntry::dir_strict()`]), as producers differ in which of the two they use.
     pub fn dir(&self) -> Result<bool> {
-        Ok(self.filename.as_str()?.ends_with('/'))
+        Ok(self.filename.as_str()?.ends_with('/') || self.dir_strict())
+    }
+
+    /// Returns whether or not the entry's external file attribute marks it as a directory.
+    ///
+    /// This checks the MS-DOS directory attribute bit and, if the attribute host compatibility is Unix, the file type
+    /// bits of the Unix mode. Unlike [`ZipEntry::dir()`], the filename is ignored so that a file whose name ends with a
+    /// `/` isn't misclassified, at the cost of missing directories from producers which only use the trailing `/`.
+    pub fn dir_strict(&self) -> bool {
+        let msdos = self.external_file_attribute & MSDOS_DIRECTORY_ATTRIBUTE != 0;
+        let unix = self.unix_permissions().is_some_and(|mode| mode & UNIX_FILE_TYPE_MASK == UNIX_DIRECTORY_MODE);
+
+        msdos || unix
+    }
+
+    /// Returns an error if the entry is stored but its compressed and uncompressed sizes differ.
+    ///
+    /// As stored data is copied verbatim, such an entry is corrupt, and reading it using either size would produce
+    /// incorrect data. The encryption header which prefixes ZipCrypto-encrypted data is accounted for.
+    pub(crate) fn check_stored_sizes(&self) -> Result<()> {
+        let overhead = if self.general_purpose_flag.encrypted { zip_crypto::HEADER_LENGTH as u64 } else { 0 };
+
+        if self.compression == Compression::Stored
+            && self.compressed_size != self.uncompressed_size.saturating_add(overhead)
+        {
+            return Err(ZipError::InconsistentStoredEntry(self.compressed_size, self.uncompressed_size));
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether or not the entry's sizes are unknown, as it was written with a data descriptor whose sizes weren't
+    /// carried over into the central directory.
+    ///
+    /// Only entries whose compressed data marks its own end (ie. those which are neither stored nor encrypted) are
+    /// considered, as only they can be read without a known size.
+    pub(crate) fn has_unknown_size(&self) -> bool {
+        self.general_purpose_flag.data_descriptor
+            && self.compressed_size == 0
+            && self.uncompressed_size == 0
+            && self.compression != Compression::Stored
+            && !self.general_purpose_flag.encrypted
+    }
+
+    /// Returns an error if the entry's data can't be read out of order, as it's compressed or encrypted (or its sizes
+    /// are inconsistent).
+    pub(crate) fn check_random_access(&self) -> Result<()> {
+        if self.compression != Compression::Stored {
+            return Err(ZipError::FeatureNotSupported("random access to compressed entries"));
+        }
+        if self.general_purpose_flag.encrypted {
+            return Err(ZipError::FeatureNotSupported("random access to encrypted entries"));
+        }
+
+        self.check_stored_sizes()
+    }
+
+    /// Returns the size to which the entry's LZMA-compressed data decodes, unless its end is instead marked within the
+    /// data itself.
+    pub(crate) fn lzma_uncompressed_size(&self) -> Option<u64> {
+        (!self.general_purpose_flag.lzma_eos_marker).then_some(self.uncompressed_size)
+    }
+
+    /// Returns whether or not the entry is likely to be dangerous to extract.
+    ///
+    /// See [`ZipEntry::unsafe_reason()`] for the cases which are checked.
+    pub fn is_potentially_unsafe(&self) -> bool {
+        self.unsafe_reason().is_some()
+    }
+
+    /// Returns the first reason for which the entry is likely to be dangerous to extract, if any.
+    ///
+    /// This flags absolute filenames, `..` components, NUL bytes, reserved Windows device names, and symbolic links.
+    /// As a symbolic link's target is stored within the entry's data, all symbolic links are flagged rather than only
+    /// those which point outside of the extraction directory.
+    pub fn unsafe_reason(&self) -> Option<UnsafeReason> {
+        safety::unsafe_reason(self)
     }
 }
 
@@ -176,8 +436,11 @@ impl StoredZipEntry {
         self.file_offset
     }
 
-    /// Seek to the offset in bytes where the data of the entry starts.
-    pub(crate) async fn seek_to_data_offset<R: AsyncRead + AsyncSeek + Unpin>(&self, mut reader: &mut R) -> Result<()> {
+    /// Returns the offset in bytes to where the data of the entry starts.
+    ///
+    /// As the local file header may differ in length from the central directory record (eg. due to alignment padding
+    /// within its extra field), this is read from the provided source.
+    pub async fn data_offset<R: AsyncRead + AsyncSeek + Unpin>(&self, reader: &mut R) -> Result<u64> {
         // Seek to the header
         reader.seek(SeekFrom::Start(self.file_offset)).await?;
 
@@ -194,13 +457,28 @@ impl StoredZipEntry {
         };
 
         // Skip the local file header and trailing data
-        let header = LocalFileHeader::from_reader(&mut reader).await?;
-        let _filename =
-            crate::base::read::io::read_string(&mut reader, header.file_name_length.into(), StringEncoding::Utf8)
-                .await?;
-        let _extra_field = crate::base::read::io::read_bytes(&mut reader, header.extra_field_length.into()).await?;
+        let header = LocalFileHeader::from_reader(reader).await?;
+        let trailing_length = header.file_name_length as u64 + header.extra_field_length as u64;
 
-        Ok(())
+        Ok(self.file_offset + (SIGNATURE_LENGTH + LFH_LENGTH) as u64 + trailing_length)
+    }
+
+    /// Seek to the offset in bytes where the data of the entry starts.
+    ///
+    /// Any error is wrapped with this entry's context, as per [`ZipError::EntryError`].
+    ///
+    /// [`ZipError::EntryError`]: crate::error::ZipError::EntryError
+    pub(crate) async fn seek_to_data_offset<R: AsyncRead + AsyncSeek + Unpin>(&self, reader: &mut R) -> Result<()> {
+        let result = async {
+            self.entry.check_stored_sizes()?;
+
+            let data_offset = self.data_offset(reader).await?;
+            reader.seek(SeekFrom::Start(data_offset)).await?;
+
+            Ok(())
+        };
+
+        result.await.map_err(|err: ZipError| err.in_entry(&self.entry))
     }
 }
 
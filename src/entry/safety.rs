@@ -0,0 +1,92 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use std::fmt::{Display, Formatter};
+
+use crate::entry::ZipEntry;
+
+/// Device names which are reserved on Windows regardless of any extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2",
+    "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// The file type bits of a Unix mode which identify a symbolic link.
+pub(crate) const UNIX_SYMLINK_MODE: u32 = 0o120000;
+pub(crate) const UNIX_FILE_TYPE_MASK: u32 = 0o170000;
+
+/// A reason for which an entry may be dangerous to extract.
+///
+/// Returned by [`ZipEntry::unsafe_reason()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsafeReason {
+    /// The filename is an absolute path (eg. `/etc/passwd` or `C:\Windows`).
+    AbsolutePath,
+    /// The filename contains a `..` component which may escape the extraction directory.
+    ParentComponent,
+    /// The filename contains a NUL byte, which may truncate the path when passed to the OS.
+    NulByte,
+    /// The filename contains a component which is a reserved device name on Windows (eg. `CON` or `LPT1`).
+    ReservedName,
+    /// The entry is a symbolic link, whose target may point outside of the extraction directory.
+    Symlink,
+}
+
+impl Display for UnsafeReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AbsolutePath => write!(f, "filename is an absolute path"),
+            Self::ParentComponent => write!(f, "filename contains a parent directory component"),
+            Self::NulByte => write!(f, "filename contains a NUL byte"),
+            Self::ReservedName => write!(f, "filename contains a reserved Windows device name"),
+            Self::Symlink => write!(f, "entry is a symbolic link"),
+        }
+    }
+}
+
+pub(crate) fn unsafe_reason(entry: &ZipEntry) -> Option<UnsafeReason> {
+    let filename = entry.filename().as_bytes();
+
+    if filename.contains(&0) {
+        return Some(UnsafeReason::NulByte);
+    }
+
+    let is_separator = |byte: &u8| *byte == b'/' || *byte == b'\\';
+    let has_drive_prefix = |component: &[u8]| matches!(component, [letter, b':', ..] if letter.is_ascii_alphabetic());
+
+    if filename.first().is_some_and(is_separator) {
+        return Some(UnsafeReason::AbsolutePath);
+    }
+
+    for component in filename.split(is_separator) {
+        // Windows treats a drive prefix as the start of a new absolute path, even when it follows other components.
+        if has_drive_prefix(component) {
+            return Some(UnsafeReason::AbsolutePath);
+        }
+        if component == b".." {
+            return Some(UnsafeReason::ParentComponent);
+        }
+        if is_reserved_name(component) {
+            return Some(UnsafeReason::ReservedName);
+        }
+    }
+
+    if is_symlink(entry) {
+        return Some(UnsafeReason::Symlink);
+    }
+
+    None
+}
+
+/// Returns whether the entry's Unix mode (if any) identifies it as a symbolic link.
+pub(crate) fn is_symlink(entry: &ZipEntry) -> bool {
+    entry.unix_permissions().is_some_and(|mode| mode & UNIX_FILE_TYPE_MASK == UNIX_SYMLINK_MODE)
+}
+
+fn is_reserved_name(component: &[u8]) -> bool {
+    // Windows ignores any extension, and all trailing spaces and dots, when matching device names.
+    let stem = component.split(|byte| *byte == b'.').next().unwrap_or_default();
+    let stem = &stem[..stem.iter().rposition(|byte| *byte != b' ').map_or(0, |index| index + 1)];
+
+    WINDOWS_RESERVED_NAMES.iter().any(|name| name.as_bytes().eq_ignore_ascii_case(stem))
+}
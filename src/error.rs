@@ -3,6 +3,9 @@
 
 //! A module which holds relevant error reporting structures/types.
 
+use crate::entry::safety::UnsafeReason;
+use crate::entry::ZipEntry;
+
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
@@ -13,6 +16,7 @@ pub type Result<V> = std::result::Result<V, ZipError>;
 pub enum Zip64ErrorCase {
     TooManyFiles,
     LargeFile,
+    TooLargeCentralDirectory,
 }
 
 impl Display for Zip64ErrorCase {
@@ -20,6 +24,7 @@ impl Display for Zip64ErrorCase {
         match self {
             Self::TooManyFiles => write!(f, "More than 65536 files in archive"),
             Self::LargeFile => write!(f, "File is larger than 4 GiB"),
+            Self::TooLargeCentralDirectory => write!(f, "Central directory is larger than or starts beyond 4 GiB"),
         }
     }
 }
@@ -36,18 +41,42 @@ pub enum ZipError {
     AttributeCompatibilityNotSupported(u16),
     #[error("attempted to read a ZIP64 file whilst on a 32-bit target")]
     TargetZip64NotSupported,
+    #[error("attempted to read a ZIP64 file whilst ZIP64 support was disabled")]
+    Zip64Required,
     #[error("attempted to write a ZIP file with force_no_zip64 when ZIP64 is needed: {0}")]
     Zip64Needed(Zip64ErrorCase),
+    #[error("attempted to stream read a stored entry whose size is only known from its data descriptor")]
+    StreamingStoredWithoutSize,
+    #[error("unable to locate the data descriptor of an entry whose sizes weren't recorded in its local file header")]
+    DataDescriptorNotFound,
+    #[error("entry is unsafe to extract: {0}")]
+    UnsafeEntryPath(UnsafeReason),
+    #[error("stored entry has differing compressed ({0}) and uncompressed ({1}) sizes")]
+    InconsistentStoredEntry(u64, u64),
     #[error("end of file has not been reached")]
     EOFNotReached,
+    #[error("entry {0} read from the local file headers doesn't match the central directory")]
+    CentralDirectoryMismatch(usize),
+    #[error("volume {0} of a split ZIP file wasn't provided")]
+    MissingVolume(u32),
+    #[error("a volume size of {0} bytes is outside of the 64KiB to 4GiB range")]
+    InvalidVolumeSize(u64),
     #[error("extra fields exceeded maximum size")]
     ExtraFieldTooLarge,
     #[error("comment exceeded maximum size")]
     CommentTooLarge,
+    #[error("a ZIP file of {0} bytes can't be padded to {1} bytes")]
+    InvalidPaddedSize(u64, u64),
     #[error("filename exceeded maximum size")]
     FileNameTooLarge,
     #[error("attempted to convert non-UTF8 bytes to a string/str")]
     StringNotUtf8,
+    #[error("string contained characters which can't be represented in the {0} encoding")]
+    StringNotRepresentable(&'static str),
+    #[error("entry data was not valid UTF-8")]
+    InvalidUtf8,
+    #[error("entry data exceeded the maximum size of {0} bytes")]
+    EntryTooLarge(u64),
 
     #[error("unable to locate the end of central directory record")]
     UnableToLocateEOCDR,
@@ -55,13 +84,23 @@ pub enum ZipError {
     InvalidExtraFieldHeader(u16, usize),
     #[error("zip64 extended information field was incomplete")]
     Zip64ExtendedFieldIncomplete,
+    #[error("a size or offset was set to the ZIP64 sentinel value without a ZIP64 extended information field")]
+    MissingZip64ExtraField,
 
     #[error("an upstream reader returned an error: {0}")]
-    UpstreamReadError(#[from] std::io::Error),
-    #[error("a computed CRC32 value did not match the expected value")]
-    CRC32CheckError,
+    UpstreamReadError(#[source] std::io::Error),
+    #[error("the compressed data requires a larger window than the configured maximum")]
+    WindowTooLarge,
+    #[error("a computed CRC32 value ({actual:#010x}) did not match the expected value ({expected:#010x})")]
+    CRC32CheckError { expected: u32, actual: u32 },
+    #[error("an entry's data decompressed to {actual} bytes rather than its recorded size of {expected} bytes")]
+    UncompressedSizeMismatch { expected: u64, actual: u64 },
+    #[error("the provided password was incorrect, or the encrypted data has been tampered with")]
+    WrongPassword,
     #[error("entry index was out of bounds")]
     EntryIndexOutOfBounds,
+    #[error("no entry was found with the name {0:?}")]
+    EntryNotFound(String),
     #[error("Encountered an unexpected header (actual: {0:#x}, expected: {1:#x}).")]
     UnexpectedHeaderError(u32, u32),
 
@@ -69,4 +108,43 @@ pub enum ZipError {
     InfoZipUnicodeCommentFieldIncomplete,
     #[error("Info-ZIP Unicode Path Extra Field was incomplete")]
     InfoZipUnicodePathFieldIncomplete,
+
+    #[error("entry {index} ({name:?}): {source}")]
+    EntryError { index: usize, name: String, source: Box<ZipError> },
+}
+
+impl From<std::io::Error> for ZipError {
+    /// Converts an IO error into a ZIP error, unwrapping any ZIP error raised from within a reader (eg. a failed CRC32
+    /// check at EOF) rather than nesting it.
+    fn from(err: std::io::Error) -> Self {
+        match err.get_ref().map(|inner| inner.is::<ZipError>()) {
+            Some(true) => *err.into_inner().and_then(|inner| inner.downcast().ok()).expect("inner error is a ZipError"),
+            _ => ZipError::UpstreamReadError(err),
+        }
+    }
+}
+
+impl ZipError {
+    /// Wraps this error with the index and name of the entry which was being processed when it occurred.
+    ///
+    /// This is used by operations over multiple entries (eg. extraction), so that the offending entry is included
+    /// within the error's [`Display`] output. Errors which are already associated with an entry are left as-is.
+    pub(crate) fn in_entry(self, entry: &ZipEntry) -> Self {
+        match self {
+            ZipError::EntryError { .. } => self,
+            source => ZipError::EntryError {
+                index: entry.index(),
+                name: String::from_utf8_lossy(entry.filename().as_bytes()).into_owned(),
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Returns the underlying error, unwrapping any entry context added via [`ZipError::EntryError`].
+    pub fn without_entry(&self) -> &ZipError {
+        match self {
+            ZipError::EntryError { source, .. } => source.without_entry(),
+            other => other,
+        }
+    }
 }
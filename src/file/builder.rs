@@ -14,7 +14,19 @@ impl From<ZipFile> for ZipFileBuilder {
 
 impl Default for ZipFileBuilder {
     fn default() -> Self {
-        ZipFileBuilder(ZipFile { entries: Vec::new(), zip64: false, comment: String::new().into() })
+        ZipFileBuilder(ZipFile {
+            entries: Vec::new(),
+            zip64: false,
+            comment: String::new().into(),
+            central_directory_offset: 0,
+            central_directory_size: 0,
+            prepended_bytes: 0,
+            trailing_bytes: 0,
+            declared_entry_count: None,
+            partial: false,
+            name_index: Default::default(),
+            folded_name_index: Default::default(),
+        })
     }
 }
 
@@ -0,0 +1,139 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A minimal glob matcher for entry names.
+//!
+//! Supports `?` (any single character other than `/`), `*` (any run of characters other than `/`), `**` (any run of
+//! characters, including `/`), and bracketed character classes such as `[abc]`, `[a-z]`, and `[!abc]`. A `**/` at the
+//! start of a path segment matches zero or more whole directories.
+//!
+//! Matching is iterative, only ever backtracking to the most recent `*` and `**`, so it runs in polynomial time
+//! regardless of how many wildcards the pattern holds.
+
+/// Returns whether the provided name matches the glob pattern.
+pub(crate) fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // The pattern and name positions to resume from when backtracking to the most recent `*`.
+    let mut star: Option<(usize, usize)> = None;
+    // As above for the most recent `**`, alongside whether it may only resume at the start of a directory.
+    let mut globstar: Option<(usize, usize, bool)> = None;
+
+    loop {
+        if p < pattern.len() {
+            let segment_start = p == 0 || pattern[p - 1] == '/';
+
+            match &pattern[p..] {
+                ['*', '*', '/', ..] if segment_start => {
+                    globstar = Some((p + 3, n, true));
+                    star = None;
+                    p += 3;
+                    continue;
+                }
+                ['*', '*', ..] => {
+                    globstar = Some((p + 2, n, false));
+                    star = None;
+                    p += 2;
+                    continue;
+                }
+                ['*', ..] => {
+                    star = Some((p + 1, n));
+                    p += 1;
+                    continue;
+                }
+                ['?', ..] if name.get(n).is_some_and(|c| *c != '/') => {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+                ['?', ..] => {}
+                ['[', rest @ ..] => match parse_class(rest) {
+                    Some((class, rest)) if name.get(n).is_some_and(|c| *c != '/' && class.matches(*c)) => {
+                        p = pattern.len() - rest.len();
+                        n += 1;
+                        continue;
+                    }
+                    Some(_) => {}
+                    // An unterminated class is treated as a literal `[`.
+                    None if name.get(n) == Some(&'[') => {
+                        p += 1;
+                        n += 1;
+                        continue;
+                    }
+                    None => {}
+                },
+                [literal, ..] if name.get(n) == Some(literal) => {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        } else if n == name.len() {
+            return true;
+        }
+
+        // A `*` may only extend over the current segment, after which the most recent `**` is backtracked to instead.
+        if let Some((star_p, star_n)) = star {
+            if name.get(star_n).is_some_and(|c| *c != '/') {
+                star = Some((star_p, star_n + 1));
+                (p, n) = (star_p, star_n + 1);
+                continue;
+            }
+            star = None;
+        }
+
+        if let Some((globstar_p, globstar_n, directories)) = globstar {
+            let next = match directories {
+                true => name[globstar_n..].iter().position(|c| *c == '/').map(|index| globstar_n + index + 1),
+                false => (globstar_n < name.len()).then_some(globstar_n + 1),
+            };
+
+            if let Some(next) = next {
+                globstar = Some((globstar_p, next, directories));
+                (p, n) = (globstar_p, next);
+                continue;
+            }
+        }
+
+        return false;
+    }
+}
+
+struct Class<'a> {
+    negated: bool,
+    body: &'a [char],
+}
+
+impl Class<'_> {
+    fn matches(&self, c: char) -> bool {
+        let mut found = false;
+        let mut index = 0;
+
+        while index < self.body.len() {
+            if index + 2 < self.body.len() && self.body[index + 1] == '-' {
+                found |= (self.body[index]..=self.body[index + 2]).contains(&c);
+                index += 3;
+            } else {
+                found |= self.body[index] == c;
+                index += 1;
+            }
+        }
+
+        found != self.negated
+    }
+}
+
+/// Parses a character class following its opening `[`, returning it and the remaining pattern.
+fn parse_class(pattern: &[char]) -> Option<(Class<'_>, &[char])> {
+    let (negated, pattern) = match pattern {
+        ['!', rest @ ..] => (true, rest),
+        _ => (false, pattern),
+    };
+
+    // A `]` directly after the opening bracket is treated as part of the class.
+    let end = pattern.iter().skip(1).position(|c| *c == ']')? + 1;
+    Some((Class { negated, body: &pattern[..end] }, &pattern[end + 1..]))
+}
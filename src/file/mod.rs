@@ -2,16 +2,33 @@
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
 pub(crate) mod builder;
+pub(crate) mod glob;
 
+use crate::error::Result;
+use crate::spec::consts::PRODUCER_MARKER;
 use crate::{entry::StoredZipEntry, string::ZipString};
 use builder::ZipFileBuilder;
 
+#[cfg(feature = "fingerprint")]
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
 /// An immutable store of data about a ZIP file.
 #[derive(Clone)]
 pub struct ZipFile {
     pub(crate) entries: Vec<StoredZipEntry>,
     pub(crate) zip64: bool,
     pub(crate) comment: ZipString,
+    pub(crate) central_directory_offset: u64,
+    pub(crate) central_directory_size: u64,
+    pub(crate) prepended_bytes: u64,
+    pub(crate) trailing_bytes: u64,
+    pub(crate) declared_entry_count: Option<u64>,
+    pub(crate) partial: bool,
+    pub(crate) name_index: OnceLock<HashMap<Vec<u8>, usize>>,
+    pub(crate) folded_name_index: OnceLock<HashMap<Vec<u8>, usize>>,
 }
 
 impl From<ZipFileBuilder> for ZipFile {
@@ -26,13 +43,193 @@ impl ZipFile {
         &self.entries
     }
 
+    /// Returns an iterator over the names of this ZIP file's entries, in central directory order.
+    ///
+    /// Names are decoded lossily, replacing any invalid UTF-8 sequences with `U+FFFD`, and are only allocated when
+    /// such a replacement is needed.
+    pub fn entry_names(&self) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+        self.entries.iter().map(|entry| String::from_utf8_lossy(entry.filename().as_bytes()))
+    }
+
+    /// Returns the index of the entry with the provided name, if present.
+    ///
+    /// The name is matched against each entry's [raw filename bytes](crate::ZipEntry::raw_name_bytes) exactly as
+    /// stored. If multiple entries share a name, the first is returned. A lookup table is built on the first call, so
+    /// repeated lookups within large ZIP files are cheap.
+    pub fn entry_index(&self, name: &str) -> Option<usize> {
+        let index = self.name_index.get_or_init(|| build_name_index(&self.entries, |name| name.to_vec()));
+        index.get(name.as_bytes()).copied()
+    }
+
+    /// Returns the index of the entry with the provided name as per [`ZipFile::entry_index()`], ignoring case.
+    ///
+    /// Names which are valid UTF-8 are compared using Unicode lowercase mapping, whereas any others only have their
+    /// ASCII characters folded.
+    pub fn entry_index_ignore_case(&self, name: &str) -> Option<usize> {
+        let index = self.folded_name_index.get_or_init(|| build_name_index(&self.entries, fold_case));
+        index.get(&fold_case(name.as_bytes())).copied()
+    }
+
+    /// Returns the indices of all entries whose names match the provided glob pattern.
+    ///
+    /// Backslashes within entry names are normalised to forward slashes before matching, and entries whose names
+    /// aren't valid UTF-8 are never matched. The pattern supports `?`, `*` (which doesn't cross a `/`), `**` (which
+    /// does), and bracketed character classes such as `[a-z]` or `[!0-9]`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use async_zip::ZipFile;
+    /// #
+    /// # fn run(file: &ZipFile) {
+    /// for index in file.find_entries("**/*.json") {
+    ///     // Read the entry at `index`.
+    /// }
+    /// # }
+    /// ```
+    pub fn find_entries(&self, pattern: &str) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| match entry.filename().as_str() {
+                Ok(name) => glob::matches(pattern, &name.replace('\\', "/")),
+                Err(_) => false,
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Returns the total number of bytes which extracting this ZIP file would write, alongside the number of file and
+    /// directory entries, in that order.
+    ///
+    /// The byte count is the sum of each entry's uncompressed size as recorded in the central directory, and so should
+    /// only be trusted as far as the archive itself is. Directories are identified as per [`ZipEntry::dir()`], so an
+    /// error is returned if any entry's filename isn't valid UTF-8.
+    ///
+    /// [`ZipEntry::dir()`]: crate::ZipEntry::dir
+    pub fn extraction_footprint(&self) -> Result<(u64, usize, usize)> {
+        let mut bytes = 0u64;
+        let mut files = 0;
+        let mut dirs = 0;
+
+        for entry in &self.entries {
+            if entry.dir()? {
+                dirs += 1;
+            } else {
+                files += 1;
+                bytes = bytes.saturating_add(entry.uncompressed_size());
+            }
+        }
+
+        Ok((bytes, files, dirs))
+    }
+
+    /// Returns a SHA-256 fingerprint of this ZIP file's contents, computed without decompressing any entries.
+    ///
+    /// The fingerprint covers each entry's name, CRC32 value, and uncompressed size (in name-sorted order), and so is
+    /// independent of entry order, timestamps, and compression methods. Two ZIP files with equal fingerprints hold the
+    /// same files, as far as their central directories can be trusted.
+    #[cfg(feature = "fingerprint")]
+    pub fn content_fingerprint(&self) -> [u8; 32] {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|a, b| a.filename().as_bytes().cmp(b.filename().as_bytes()));
+
+        let mut hasher = Sha256::new();
+        for entry in entries {
+            let name = entry.filename().as_bytes();
+            hasher.update((name.len() as u64).to_le_bytes());
+            hasher.update(name);
+            hasher.update(entry.crc32().to_le_bytes());
+            hasher.update(entry.uncompressed_size().to_le_bytes());
+        }
+
+        hasher.finalize().into()
+    }
+
     /// Returns this ZIP file's trailing comment.
     pub fn comment(&self) -> &ZipString {
         &self.comment
     }
 
+    /// Returns the tag identifying the software which produced this ZIP file, if present.
+    ///
+    /// This is read from the last line of the ZIP file comment if it has the form `Producer: <tag>`, as written by
+    /// [`ZipFileWriter::producer()`](crate::base::write::ZipFileWriter::producer). Any null bytes which follow it (as
+    /// written by [`ZipFileWriter::pad_to()`](crate::base::write::ZipFileWriter::pad_to)) are ignored.
+    pub fn producer(&self) -> Option<&str> {
+        self.comment.as_str().ok()?.trim_end_matches('\0').lines().last()?.strip_prefix(PRODUCER_MARKER)
+    }
+
+    /// Returns the offset of the central directory, as recorded by the end of central directory record.
+    pub fn central_directory_offset(&self) -> u64 {
+        self.central_directory_offset
+    }
+
+    /// Returns the size of the central directory, as recorded by the end of central directory record.
+    pub fn central_directory_size(&self) -> u64 {
+        self.central_directory_size
+    }
+
+    /// Returns the number of bytes which precede the ZIP file's data (eg. the executable stub of a self-extracting
+    /// archive).
+    ///
+    /// The offsets recorded within the ZIP file are relative to the end of these bytes, whereas the offsets returned
+    /// by [`StoredZipEntry::header_offset()`] already account for them.
+    ///
+    /// [`StoredZipEntry::header_offset()`]: crate::StoredZipEntry::header_offset
+    pub fn prepended_bytes(&self) -> u64 {
+        self.prepended_bytes
+    }
+
+    /// Returns the number of bytes which follow the end of central directory record and its comment.
+    ///
+    /// A well-formed ZIP file ends with its comment, so any such bytes were likely appended to it after it was written
+    /// (eg. a tampered or polyglot file).
+    pub fn trailing_bytes(&self) -> u64 {
+        self.trailing_bytes
+    }
+
+    /// Returns whether or not any bytes follow the end of central directory record and its comment, as per
+    /// [`ZipFile::trailing_bytes()`].
+    pub fn has_trailing_data(&self) -> bool {
+        self.trailing_bytes > 0
+    }
+
+    /// Returns the number of entries declared by the end of central directory record, if it differed from the number
+    /// of records actually found within the central directory.
+    ///
+    /// In such a case, [`ZipFile::entries()`] holds all records found within the bounds of the central directory
+    /// rather than being truncated to (or erroring because of) the declared count.
+    pub fn entry_count_mismatch(&self) -> Option<u64> {
+        self.declared_entry_count
+    }
+
+    /// Returns whether or not parsing of the central directory stopped early, leaving some entries unread.
+    ///
+    /// This is only the case when a maximum was set via
+    /// [`ReadOptions::max_entries_to_parse()`](crate::base::read::ReadOptions::max_entries_to_parse) and the central
+    /// directory held more records than that.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
     /// Returns whether or not this ZIP file is zip64
     pub fn zip64(&self) -> bool {
         self.zip64
     }
 }
+
+/// Maps each entry's (transformed) raw filename to its index, keeping the first index for duplicate names.
+fn build_name_index(entries: &[StoredZipEntry], key: impl Fn(&[u8]) -> Vec<u8>) -> HashMap<Vec<u8>, usize> {
+    let mut index = HashMap::with_capacity(entries.len());
+    for (position, entry) in entries.iter().enumerate() {
+        index.entry(key(entry.raw_name_bytes())).or_insert(position);
+    }
+    index
+}
+
+fn fold_case(name: &[u8]) -> Vec<u8> {
+    match std::str::from_utf8(name) {
+        Ok(name) => name.to_lowercase().into_bytes(),
+        Err(_) => name.to_ascii_lowercase(),
+    }
+}
@@ -26,9 +26,17 @@
 //! - `full` - Enables all below features.
 //! - `full-wasm` - Enables all below features that are compatible with WASM.
 //! - `chrono` - Enables support for parsing dates via `chrono`.
+//! - `time` - Enables support for parsing dates via `time`.
+//! - `encoding` - Enables support for encoding and decoding filenames in legacy codepages via `encoding_rs`.
 //! - `tokio` - Enables support for the `tokio` implementation module.
 //! - `tokio-fs` - Enables support for the `tokio::fs` reading module.
+//! - `cap-std` - Enables extracting into a `cap_std::fs::Dir` capability via `cap-std`.
+//! - `smol` - Enables support for the `smol::fs` reading module.
 //! - `deflate` - Enables support for the Deflate compression method.
+//! - `deflate-dictionary` - Enables support for preset Deflate dictionaries via `flate2`. This switches `flate2` to its
+//!   `zlib-rs` backend, which applies to every crate in the build using `flate2` as Cargo unifies features.
+//! - `fingerprint` - Enables computing a SHA-256 fingerprint of a ZIP file's contents via `sha2`.
+//! - `aes` - Enables support for reading and writing entries encrypted with WinZip's AES scheme.
 //! - `bzip2` - Enables support for the bzip2 compression method.
 //! - `lzma` - Enables support for the LZMA compression method.
 //! - `zstd` - Enables support for the zstd compression method.
@@ -37,8 +45,11 @@
 //! [Read more.](https://github.com/Majored/rs-async-zip)
 
 pub mod base;
+pub mod encryption;
 pub mod error;
 
+#[cfg(feature = "smol")]
+pub mod smol;
 #[cfg(feature = "tokio")]
 pub mod tokio;
 
@@ -55,8 +66,21 @@ pub(crate) mod tests;
 pub use crate::spec::attribute::AttributeCompatibility;
 pub use crate::spec::compression::{Compression, DeflateOption};
 
-pub use crate::entry::{builder::ZipEntryBuilder, StoredZipEntry, ZipEntry};
+#[cfg(any(
+    feature = "deflate",
+    feature = "bzip2",
+    feature = "zstd",
+    feature = "lzma",
+    feature = "xz",
+    feature = "deflate64"
+))]
+pub use async_compression::Level;
+
+pub use crate::entry::{builder::ZipEntryBuilder, safety::UnsafeReason, StoredZipEntry, ZipEntry};
 pub use crate::file::{builder::ZipFileBuilder, ZipFile};
 
-pub use crate::date::ZipDateTime;
+pub use crate::date::{ExtendedTimestamp, NtfsTimes, ZipDateTime};
 pub use crate::string::{StringEncoding, ZipString};
+
+#[cfg(feature = "encoding")]
+pub use encoding_rs;
@@ -0,0 +1,16 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A set of [`smol`]-specific features.
+//!
+//! # Usage
+//! As [`smol`]'s IO types implement the same async IO traits as the [`base`] implementation, they can be used with it
+//! directly and no compatibility types or aliases are needed. This module instead provides a reader which acts over a
+//! file system path, opening the file via [`smol::fs::File`].
+
+#[cfg(doc)]
+use crate::base;
+#[cfg(doc)]
+use smol;
+
+pub mod read;
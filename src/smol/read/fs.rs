@@ -0,0 +1,249 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A concurrent ZIP reader which acts over a file system path.
+//!
+//! This is the [`smol`] counterpart of the `tokio::read::fs` module, constructing a new [`File`] from the path when
+//! reading so that concurrent [`ZipEntryReader`]s can be constructed from a single [`ZipFileReader`]. The reader is
+//! cheaply cloneable, so it can be moved into spawned tasks when needed.
+//!
+//! ### Example
+//! ```no_run
+//! # use async_zip::smol::read::fs::ZipFileReader;
+//! # use async_zip::error::Result;
+//! # use futures_util::io::AsyncReadExt;
+//! #
+//! async fn run() -> Result<()> {
+//!     let reader = ZipFileReader::new("./foo.zip").await?;
+//!
+//!     let mut entry = reader.reader_with_entry(0).await?;
+//!     let mut data = Vec::new();
+//!     entry.read_to_end_checked(&mut data).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use crate::base::read::io::entry::{WithEntry, WithoutEntry, ZipEntryReader};
+use crate::base::read::io::owned::OwnedReader;
+use crate::base::read::{mem, ReadOptions, VerifyReport};
+use crate::entry::ZipEntry;
+use crate::error::{Result, ZipError};
+use crate::file::ZipFile;
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use futures_util::io::{AsyncReadExt, BufReader, Take};
+use smol::fs::File;
+
+struct Inner {
+    path: PathBuf,
+    file: ZipFile,
+    options: ReadOptions,
+}
+
+/// A concurrent ZIP reader which acts over a file system path.
+#[derive(Clone)]
+pub struct ZipFileReader {
+    inner: Arc<Inner>,
+}
+
+impl ZipFileReader {
+    /// Constructs a new ZIP reader from a file system path.
+    pub async fn new<P>(path: P) -> Result<ZipFileReader>
+    where
+        P: AsRef<Path>,
+    {
+        Self::with_options(path, ReadOptions::default()).await
+    }
+
+    /// Constructs a new ZIP reader from a file system path with the provided options.
+    pub async fn with_options<P>(path: P, options: ReadOptions) -> Result<ZipFileReader>
+    where
+        P: AsRef<Path>,
+    {
+        let file = crate::base::read::file(File::open(&path).await?, &options).await?;
+        Ok(ZipFileReader { inner: Arc::new(Inner { path: path.as_ref().to_owned(), file, options }) })
+    }
+
+    /// Constructs a new ZIP reader from a file system path whose central directory is missing or corrupt.
+    ///
+    /// Rather than parsing the central directory, the entries are reconstructed by walking the local file headers from
+    /// the start of the file, stopping at the first record which isn't one. As such, any information only held within
+    /// the central directory (eg. entry comments and file attributes) is unavailable, and the ZIP file's comment is
+    /// empty. The sizes and CRC32 value of an entry written with a data descriptor are taken from that descriptor.
+    pub async fn repair<P>(path: P) -> Result<ZipFileReader>
+    where
+        P: AsRef<Path>,
+    {
+        let file = crate::base::read::repair::repair(File::open(&path).await?).await?;
+        Ok(ZipFileReader::from_raw_parts(path, file))
+    }
+
+    /// Constructs a ZIP reader from a file system path and ZIP file information derived from that path.
+    ///
+    /// Providing a [`ZipFile`] that wasn't derived from that path may lead to inaccurate parsing.
+    pub fn from_raw_parts<P>(path: P, file: ZipFile) -> ZipFileReader
+    where
+        P: AsRef<Path>,
+    {
+        ZipFileReader {
+            inner: Arc::new(Inner { path: path.as_ref().to_owned(), file, options: ReadOptions::default() }),
+        }
+    }
+
+    /// Returns this ZIP file's information.
+    pub fn file(&self) -> &ZipFile {
+        &self.inner.file
+    }
+
+    /// Returns an iterator over the decoded names of this ZIP file's entries, as per [`ZipFile::entry_names()`].
+    pub fn entry_names(&self) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+        self.file().entry_names()
+    }
+
+    /// Returns whether or not any bytes follow the end of this ZIP file's comment, as per
+    /// [`ZipFile::has_trailing_data()`].
+    pub fn has_trailing_data(&self) -> bool {
+        self.file().has_trailing_data()
+    }
+
+    /// Returns the file system path provided to the reader during construction.
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Returns the raw bytes of this ZIP file's central directory.
+    pub async fn central_directory_bytes(&self) -> Result<Vec<u8>> {
+        let fs_file = File::open(&self.inner.path).await?;
+        crate::base::read::central_directory_bytes(fs_file, &self.inner.file).await
+    }
+
+    /// Returns each entry alongside the absolute range of its compressed data within the ZIP file.
+    ///
+    /// Every local file header is read to account for any differences in length from the central directory.
+    pub async fn entries_with_ranges(&self) -> Result<Vec<(ZipEntry, Range<u64>)>> {
+        let fs_file = File::open(&self.inner.path).await?;
+        crate::base::read::entries_with_ranges(fs_file, &self.inner.file).await
+    }
+
+    /// Reads the whole file into memory, returning a reader over those bytes.
+    ///
+    /// The returned reader shares this reader's already-parsed ZIP file information and options, so the central
+    /// directory isn't parsed again. As such, the file shouldn't have been modified since this reader was constructed.
+    pub async fn load_into_memory(&self) -> Result<mem::ZipFileReader> {
+        let data = smol::fs::read(&self.inner.path).await?;
+        Ok(mem::ZipFileReader::from_raw_parts_with_options(data, self.inner.file.clone(), self.inner.options.clone()))
+    }
+
+    /// Returns a new entry reader if the provided index is valid.
+    pub async fn reader_without_entry(&self, index: usize) -> Result<ZipEntryReader<'static, File, WithoutEntry>> {
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut fs_file = BufReader::new(File::open(&self.inner.path).await?);
+
+        stored_entry.seek_to_data_offset(&mut fs_file).await?;
+
+        let reader = ZipEntryReader::new_with_owned(fs_file, stored_entry)?;
+
+        Ok(reader.with_options(&self.inner.options).expect_crc(stored_entry.entry.crc32()))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, without verifying the entry's CRC32 value at EOF.
+    ///
+    /// See [`ZipEntryReader::unchecked()`].
+    pub async fn reader_without_entry_unchecked(
+        &self,
+        index: usize,
+    ) -> Result<ZipEntryReader<'static, File, WithoutEntry>> {
+        Ok(self.reader_without_entry(index).await?.unchecked())
+    }
+
+    /// Returns a new entry reader if the provided index is valid.
+    pub async fn reader_with_entry(&self, index: usize) -> Result<ZipEntryReader<'_, File, WithEntry<'_>>> {
+        if let Some(password) = self.inner.options.password.as_deref() {
+            return self.reader_with_entry_and_password(index, password).await;
+        }
+
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut fs_file = BufReader::new(File::open(&self.inner.path).await?);
+
+        stored_entry.seek_to_data_offset(&mut fs_file).await?;
+
+        let reader = ZipEntryReader::new_with_owned(fs_file, stored_entry)?;
+
+        Ok(reader.with_options(&self.inner.options).into_with_entry(stored_entry))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, decrypting the entry's data with the provided password.
+    ///
+    /// See the `tokio::read::fs` module's equivalent for details on the supported encryption schemes.
+    pub async fn reader_with_entry_and_password(
+        &self,
+        index: usize,
+        password: &[u8],
+    ) -> Result<ZipEntryReader<'_, File, WithEntry<'_>>> {
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut fs_file = BufReader::new(File::open(&self.inner.path).await?);
+
+        stored_entry.seek_to_data_offset(&mut fs_file).await?;
+
+        let reader = ZipEntryReader::new_with_password(OwnedReader::Owned(fs_file), stored_entry, password).await?;
+
+        Ok(reader.with_options(&self.inner.options).into_with_entry(stored_entry))
+    }
+
+    /// Returns a reader over the compressed (and possibly encrypted) data of the entry at the provided index.
+    ///
+    /// The data is returned exactly as stored, so it can be copied into another ZIP file without being decompressed
+    /// and recompressed via [`ZipFileWriter::copy_entry_raw()`](crate::base::write::ZipFileWriter::copy_entry_raw).
+    pub async fn raw_reader_without_entry(&self, index: usize) -> Result<Take<BufReader<File>>> {
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut fs_file = BufReader::new(File::open(&self.inner.path).await?);
+
+        stored_entry.seek_to_data_offset(&mut fs_file).await?;
+
+        Ok(fs_file.take(stored_entry.entry.compressed_size()))
+    }
+
+    /// Reads the entry at the provided index into a string, as per [`ZipEntryReader::read_to_string_checked()`].
+    ///
+    /// At most `max_bytes` bytes are decompressed, with [`ZipError::EntryTooLarge`] being returned if the entry holds
+    /// any more. Data which isn't valid UTF-8 is rejected with [`ZipError::InvalidUtf8`].
+    pub async fn read_entry_to_string(&self, index: usize, max_bytes: u64) -> Result<String> {
+        self.reader_with_entry(index).await?.read_to_string_capped(max_bytes).await
+    }
+
+    /// Verifies that every entry decompresses cleanly and matches its recorded CRC32 value and uncompressed size.
+    ///
+    /// Rather than returning the first error encountered, each entry's outcome is recorded within the returned
+    /// [`VerifyReport`], akin to `unzip -t`. The decompressed data is discarded. Encrypted entries are skipped unless a
+    /// password was provided via [`ReadOptions::password()`].
+    pub async fn verify(&self) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+
+        for (index, stored_entry) in self.inner.file.entries.iter().enumerate() {
+            if report.skip(index, &stored_entry.entry, &self.inner.options) {
+                continue;
+            }
+
+            let result = match self.reader_with_entry(index).await {
+                Ok(mut reader) => reader.verify_checked().await,
+                Err(err) => Err(err),
+            };
+            report.record(index, result);
+        }
+
+        Ok(report)
+    }
+
+    /// Returns a new entry reader for the entry with the provided name, as located by [`ZipFile::entry_index()`].
+    ///
+    /// [`ZipError::EntryNotFound`] is returned if no such entry exists.
+    pub async fn reader_with_entry_by_name(&self, name: &str) -> Result<ZipEntryReader<'_, File, WithEntry<'_>>> {
+        let index = self.inner.file.entry_index(name).ok_or_else(|| ZipError::EntryNotFound(name.to_owned()))?;
+        self.reader_with_entry(index).await
+    }
+}
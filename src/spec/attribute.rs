@@ -3,11 +3,16 @@
 
 use crate::error::{Result, ZipError};
 
-/// An attribute host compatibility supported by this crate.
+/// An attribute host compatibility, as recorded within the upper byte of the "version made by" field.
+///
+/// This determines how an entry's external file attribute is interpreted.
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AttributeCompatibility {
+    MsDos,
     Unix,
+    /// A host whose attributes this crate doesn't interpret, holding its raw value.
+    Other(u16),
 }
 
 impl TryFrom<u16> for AttributeCompatibility {
@@ -17,7 +22,9 @@ impl TryFrom<u16> for AttributeCompatibility {
     // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4422
     fn try_from(value: u16) -> Result<Self> {
         match value {
+            0 => Ok(AttributeCompatibility::MsDos),
             3 => Ok(AttributeCompatibility::Unix),
+            1..=0xFF => Ok(AttributeCompatibility::Other(value)),
             _ => Err(ZipError::AttributeCompatibilityNotSupported(value)),
         }
     }
@@ -28,7 +35,9 @@ impl From<&AttributeCompatibility> for u16 {
     // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4422
     fn from(compatibility: &AttributeCompatibility) -> Self {
         match compatibility {
+            AttributeCompatibility::MsDos => 0,
             AttributeCompatibility::Unix => 3,
+            AttributeCompatibility::Other(value) => *value,
         }
     }
 }
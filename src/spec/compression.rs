@@ -23,6 +23,10 @@ pub enum Compression {
     Zstd,
     #[cfg(feature = "xz")]
     Xz,
+    /// A compression method which is either unknown or hasn't been enabled via its respective feature.
+    ///
+    /// Entries using this method can be listed but their data can't be read or written.
+    Unsupported(u16),
 }
 
 impl TryFrom<u16> for Compression {
@@ -68,10 +72,23 @@ impl From<&Compression> for u16 {
             Compression::Zstd => 93,
             #[cfg(feature = "xz")]
             Compression::Xz => 95,
+            Compression::Unsupported(value) => *value,
         }
     }
 }
 
+impl Compression {
+    // Convert a u16 stored with little endianness into a compression method, retaining any unsupported methods.
+    pub(crate) fn from_raw(value: u16) -> Self {
+        Compression::try_from(value).unwrap_or(Compression::Unsupported(value))
+    }
+
+    /// Returns whether or not this compression method can be read and written by the current build of the crate.
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, Compression::Unsupported(_))
+    }
+}
+
 impl From<Compression> for u16 {
     fn from(compression: Compression) -> u16 {
         (&compression).into()
@@ -7,14 +7,12 @@ pub const SIGNATURE_LENGTH: usize = 4;
 //
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#437
 pub const LFH_SIGNATURE: u32 = 0x4034b50;
-#[allow(dead_code)]
 pub const LFH_LENGTH: usize = 26;
 
 // Central directory header constants
 //
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4312
 pub const CDH_SIGNATURE: u32 = 0x2014b50;
-#[allow(dead_code)]
 pub const CDH_LENGTH: usize = 42;
 
 // End of central directory record constants
@@ -27,6 +25,8 @@ pub const EOCDR_LENGTH: usize = 18;
 /// The signature for the zip64 end of central directory record.
 /// Ref: https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4314
 pub const ZIP64_EOCDR_SIGNATURE: u32 = 0x06064b50;
+/// The length of the zip64 EOCDR without any extensible data, including the signature.
+pub const ZIP64_EOCDR_LENGTH: u64 = 56;
 /// The signature for the zip64 end of central directory locator.
 /// Ref: https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#4315
 pub const ZIP64_EOCDL_SIGNATURE: u32 = 0x07064b50;
@@ -41,3 +41,39 @@ pub const NON_ZIP64_MAX_NUM_FILES: u16 = 0xFFFF;
 
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#439
 pub const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x8074b50;
+
+/// The signature which begins the first volume of a split ZIP file, shared with the data descriptor.
+///
+/// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#853
+#[cfg(feature = "tokio-fs")]
+pub const SPLIT_SIGNATURE: u32 = DATA_DESCRIPTOR_SIGNATURE;
+/// The minimum size of a volume of a split ZIP file, equal to 64KiB.
+///
+/// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#851
+#[cfg(feature = "tokio-fs")]
+pub const MIN_VOLUME_SIZE: u64 = 64 * 1024;
+
+/// The header ID of the extra field used by Android's zipalign to pad an entry's data to an alignment boundary.
+pub const ALIGNMENT_EXTRA_FIELD_ID: u16 = 0xD935;
+/// The length of the alignment extra field without any padding, including its header.
+pub const ALIGNMENT_EXTRA_FIELD_LENGTH: usize = 6;
+
+/// The prefix of the line appended to the ZIP file comment which identifies the software that produced the archive.
+///
+/// The ZIP format has no dedicated field for this, so we follow the convention of placing it on the comment's last line.
+pub const PRODUCER_MARKER: &str = "Producer: ";
+
+/// The compression method recorded for entries encrypted with WinZip's AES scheme, whose actual compression method is
+/// instead held within the AES extra field.
+pub const AES_COMPRESSION_METHOD: u16 = 99;
+
+/// The compression method recorded for entries compressed with LZMA.
+pub const LZMA_COMPRESSION_METHOD: u16 = 14;
+/// The length of the LZMA properties (the lc/lp/pb byte and the dictionary size) which prefix LZMA-compressed data.
+#[cfg(feature = "lzma")]
+pub const LZMA_PROPERTIES_LENGTH: usize = 5;
+/// The length of the header which precedes the LZMA properties within an entry's data, holding the LZMA SDK version
+/// and the length of the properties.
+/// Ref: https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#588
+#[cfg(feature = "lzma")]
+pub const LZMA_HEADER_LENGTH: usize = 4;
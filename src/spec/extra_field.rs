@@ -1,23 +1,40 @@
 // Copyright Cognite AS, 2023
 
+use crate::date::{ExtendedTimestamp, NtfsTimes};
 use crate::error::{Result as ZipResult, ZipError};
 use crate::spec::header::{
-    ExtraField, HeaderId, InfoZipUnicodeCommentExtraField, InfoZipUnicodePathExtraField, UnknownExtraField,
-    Zip64ExtendedInformationExtraField,
+    ExtendedTimestampExtraField, ExtraField, HeaderId, InfoZipUnicodeCommentExtraField, InfoZipUnicodePathExtraField,
+    NtfsAttribute, NtfsExtraField, UnknownExtraField, Zip64ExtendedInformationExtraField,
 };
 
 use super::consts::NON_ZIP64_MAX_SIZE;
 
+/// The tag of the NTFS attribute holding the file times.
+const NTFS_TIMES_TAG: u16 = 0x0001;
+/// The size of the NTFS attribute holding the file times.
+const NTFS_TIMES_SIZE: u16 = 24;
+/// The flags of the extended timestamp extra field which indicate that the modification, access, and creation times
+/// are present, in the order in which they're stored.
+const EXTENDED_TIMESTAMP_FLAGS: [u8; 3] = [0x01, 0x02, 0x04];
+
 pub(crate) trait ExtraFieldAsBytes {
     fn as_bytes(&self) -> Vec<u8>;
 
     fn count_bytes(&self) -> usize;
 }
 
+/// Serialises extra fields in their canonical order, ascending by header ID.
+///
+/// This places the ZIP64 extended information field (0x0001) first, as some readers expect, and ensures that the same
+/// set of fields always produces identical bytes regardless of the order in which they were added. Fields sharing a
+/// header ID keep their relative order.
 impl ExtraFieldAsBytes for &[ExtraField] {
     fn as_bytes(&self) -> Vec<u8> {
+        let mut fields: Vec<&ExtraField> = self.iter().collect();
+        fields.sort_by_key(|field| field.header_id().0);
+
         let mut buffer = Vec::new();
-        for field in self.iter() {
+        for field in fields {
             buffer.append(&mut field.as_bytes());
         }
         buffer
@@ -28,12 +45,28 @@ impl ExtraFieldAsBytes for &[ExtraField] {
     }
 }
 
+impl ExtraField {
+    /// Returns the header ID which prefixes this field.
+    pub(crate) fn header_id(&self) -> HeaderId {
+        match self {
+            ExtraField::Zip64ExtendedInformation(field) => field.header_id,
+            ExtraField::InfoZipUnicodeComment(_) => HeaderId::INFO_ZIP_UNICODE_COMMENT_EXTRA_FIELD,
+            ExtraField::InfoZipUnicodePath(_) => HeaderId::INFO_ZIP_UNICODE_PATH_EXTRA_FIELD,
+            ExtraField::Ntfs(_) => HeaderId::NTFS_EXTRA_FIELD,
+            ExtraField::ExtendedTimestamp(_) => HeaderId::EXTENDED_TIMESTAMP_EXTRA_FIELD,
+            ExtraField::Unknown(field) => field.header_id,
+        }
+    }
+}
+
 impl ExtraFieldAsBytes for ExtraField {
     fn as_bytes(&self) -> Vec<u8> {
         match self {
             ExtraField::Zip64ExtendedInformation(field) => field.as_bytes(),
             ExtraField::InfoZipUnicodeComment(field) => field.as_bytes(),
             ExtraField::InfoZipUnicodePath(field) => field.as_bytes(),
+            ExtraField::Ntfs(field) => field.as_bytes(),
+            ExtraField::ExtendedTimestamp(field) => field.as_bytes(),
             ExtraField::Unknown(field) => field.as_bytes(),
         }
     }
@@ -43,6 +76,8 @@ impl ExtraFieldAsBytes for ExtraField {
             ExtraField::Zip64ExtendedInformation(field) => field.count_bytes(),
             ExtraField::InfoZipUnicodeComment(field) => field.count_bytes(),
             ExtraField::InfoZipUnicodePath(field) => field.count_bytes(),
+            ExtraField::Ntfs(field) => field.count_bytes(),
+            ExtraField::ExtendedTimestamp(field) => field.count_bytes(),
             ExtraField::Unknown(field) => field.count_bytes(),
         }
     }
@@ -156,6 +191,68 @@ impl ExtraFieldAsBytes for InfoZipUnicodePathExtraField {
     }
 }
 
+impl ExtraFieldAsBytes for NtfsExtraField {
+    fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let header_id: u16 = HeaderId::NTFS_EXTRA_FIELD.into();
+        let data_size: u16 = (self.count_bytes() - 4).try_into().unwrap();
+        bytes.append(&mut header_id.to_le_bytes().to_vec());
+        bytes.append(&mut data_size.to_le_bytes().to_vec());
+        bytes.append(&mut self.reserved.to_le_bytes().to_vec());
+        for attribute in &self.attributes {
+            match attribute {
+                NtfsAttribute::Times(times) => {
+                    bytes.append(&mut NTFS_TIMES_TAG.to_le_bytes().to_vec());
+                    bytes.append(&mut NTFS_TIMES_SIZE.to_le_bytes().to_vec());
+                    bytes.append(&mut times.modified.to_le_bytes().to_vec());
+                    bytes.append(&mut times.accessed.to_le_bytes().to_vec());
+                    bytes.append(&mut times.created.to_le_bytes().to_vec());
+                }
+                NtfsAttribute::Unknown { tag, data } => {
+                    let size: u16 = data.len().try_into().unwrap();
+                    bytes.append(&mut tag.to_le_bytes().to_vec());
+                    bytes.append(&mut size.to_le_bytes().to_vec());
+                    bytes.append(&mut data.clone());
+                }
+            }
+        }
+        bytes
+    }
+
+    fn count_bytes(&self) -> usize {
+        let attributes: usize = self
+            .attributes
+            .iter()
+            .map(|attribute| match attribute {
+                NtfsAttribute::Times(_) => 4 + NTFS_TIMES_SIZE as usize,
+                NtfsAttribute::Unknown { data, .. } => 4 + data.len(),
+            })
+            .sum();
+
+        8 + attributes
+    }
+}
+
+impl ExtraFieldAsBytes for ExtendedTimestampExtraField {
+    fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let header_id: u16 = HeaderId::EXTENDED_TIMESTAMP_EXTRA_FIELD.into();
+        let data_size: u16 = (self.count_bytes() - 4).try_into().unwrap();
+        bytes.append(&mut header_id.to_le_bytes().to_vec());
+        bytes.append(&mut data_size.to_le_bytes().to_vec());
+        bytes.push(self.flags);
+        for time in [self.times.modified, self.times.accessed, self.times.created].into_iter().flatten() {
+            bytes.append(&mut time.to_le_bytes().to_vec());
+        }
+        bytes
+    }
+
+    fn count_bytes(&self) -> usize {
+        let times = [self.times.modified, self.times.accessed, self.times.created];
+        5 + 4 * times.iter().filter(|time| time.is_some()).count()
+    }
+}
+
 /// Parse a zip64 extra field from bytes.
 /// The content of "data" should exclude the header.
 fn zip64_extended_information_field_from_bytes(
@@ -254,6 +351,54 @@ fn info_zip_unicode_path_extra_field_from_bytes(
     }
 }
 
+/// Parses the NTFS extra field, returning None if it's malformed so that it may instead be kept as an unknown field.
+///
+/// Attributes other than the file times (or file times of an unexpected size) are kept as unknown attributes.
+fn ntfs_extra_field_from_bytes(data: &[u8]) -> Option<NtfsExtraField> {
+    let reserved = u32::from_le_bytes(data.get(0..4)?.try_into().unwrap());
+    let mut attributes = Vec::new();
+    let mut remaining = &data[4..];
+
+    while !remaining.is_empty() {
+        let tag = u16::from_le_bytes(remaining.get(0..2)?.try_into().unwrap());
+        let size = u16::from_le_bytes(remaining.get(2..4)?.try_into().unwrap());
+        let attribute = remaining.get(4..4 + size as usize)?;
+        remaining = &remaining[4 + size as usize..];
+
+        attributes.push(if tag == NTFS_TIMES_TAG && size == NTFS_TIMES_SIZE {
+            NtfsAttribute::Times(NtfsTimes {
+                modified: u64::from_le_bytes(attribute[0..8].try_into().unwrap()),
+                accessed: u64::from_le_bytes(attribute[8..16].try_into().unwrap()),
+                created: u64::from_le_bytes(attribute[16..24].try_into().unwrap()),
+            })
+        } else {
+            NtfsAttribute::Unknown { tag, data: attribute.to_vec() }
+        });
+    }
+
+    Some(NtfsExtraField { reserved, attributes })
+}
+
+/// Parses the extended timestamp extra field, returning None if it's empty so that it may instead be kept as an unknown
+/// field.
+///
+/// A time is only read if its flag is set and the field is long enough to hold it, as the central directory's copy of
+/// the field typically retains the flags of the local file header's copy whilst only holding the modification time.
+fn extended_timestamp_extra_field_from_bytes(data: &[u8]) -> Option<ExtendedTimestampExtraField> {
+    let (&flags, mut remaining) = data.split_first()?;
+    let mut times = [None; 3];
+
+    for (time, flag) in times.iter_mut().zip(EXTENDED_TIMESTAMP_FLAGS) {
+        if flags & flag != 0 && remaining.len() >= 4 {
+            *time = Some(i32::from_le_bytes(remaining[0..4].try_into().unwrap()));
+            remaining = &remaining[4..];
+        }
+    }
+
+    let [modified, accessed, created] = times;
+    Some(ExtendedTimestampExtraField { flags, times: ExtendedTimestamp { modified, accessed, created } })
+}
+
 pub(crate) fn extra_field_from_bytes(
     header_id: HeaderId,
     data_size: u16,
@@ -277,6 +422,14 @@ pub(crate) fn extra_field_from_bytes(
         HeaderId::INFO_ZIP_UNICODE_PATH_EXTRA_FIELD => Ok(ExtraField::InfoZipUnicodePath(
             info_zip_unicode_path_extra_field_from_bytes(header_id, data_size, data)?,
         )),
+        HeaderId::NTFS_EXTRA_FIELD => match ntfs_extra_field_from_bytes(data) {
+            Some(field) => Ok(ExtraField::Ntfs(field)),
+            None => Ok(ExtraField::Unknown(UnknownExtraField { header_id, data_size, content: data.to_vec() })),
+        },
+        HeaderId::EXTENDED_TIMESTAMP_EXTRA_FIELD => match extended_timestamp_extra_field_from_bytes(data) {
+            Some(field) => Ok(ExtraField::ExtendedTimestamp(field)),
+            None => Ok(ExtraField::Unknown(UnknownExtraField { header_id, data_size, content: data.to_vec() })),
+        },
         _ => Ok(ExtraField::Unknown(UnknownExtraField { header_id, data_size, content: data.to_vec() })),
     }
 }
@@ -1,6 +1,8 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
+use crate::date::{ExtendedTimestamp, NtfsTimes};
+
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#437
 pub struct LocalFileHeader {
     pub version: u16,
@@ -16,11 +18,14 @@ pub struct LocalFileHeader {
 }
 
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#444
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct GeneralPurposeFlag {
     pub encrypted: bool,
+    pub lzma_eos_marker: bool,
     pub data_descriptor: bool,
     pub filename_unicode: bool,
+    /// Any remaining bits which aren't interpreted by this crate.
+    pub other: u16,
 }
 
 /// 2 byte header ids
@@ -30,8 +35,11 @@ pub struct HeaderId(pub u16);
 
 impl HeaderId {
     pub const ZIP64_EXTENDED_INFORMATION_EXTRA_FIELD: HeaderId = HeaderId(0x0001);
+    pub const NTFS_EXTRA_FIELD: HeaderId = HeaderId(0x000a);
+    pub const EXTENDED_TIMESTAMP_EXTRA_FIELD: HeaderId = HeaderId(0x5455);
     pub const INFO_ZIP_UNICODE_COMMENT_EXTRA_FIELD: HeaderId = HeaderId(0x6375);
     pub const INFO_ZIP_UNICODE_PATH_EXTRA_FIELD: HeaderId = HeaderId(0x7075);
+    pub const AES_EXTRA_FIELD: HeaderId = HeaderId(0x9901);
 }
 
 impl From<u16> for HeaderId {
@@ -54,6 +62,8 @@ pub enum ExtraField {
     Zip64ExtendedInformation(Zip64ExtendedInformationExtraField),
     InfoZipUnicodeComment(InfoZipUnicodeCommentExtraField),
     InfoZipUnicodePath(InfoZipUnicodePathExtraField),
+    Ntfs(NtfsExtraField),
+    ExtendedTimestamp(ExtendedTimestampExtraField),
     Unknown(UnknownExtraField),
 }
 
@@ -87,6 +97,32 @@ pub enum InfoZipUnicodePathExtraField {
     Unknown { version: u8, data: Vec<u8> },
 }
 
+/// Stores a sequence of NTFS attributes, of which only the file times (tag 0x0001) are defined.
+/// https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#455
+#[derive(Clone, Debug)]
+pub struct NtfsExtraField {
+    pub reserved: u32,
+    pub attributes: Vec<NtfsAttribute>,
+}
+
+/// Represents a single attribute held within the NTFS extra field.
+#[derive(Clone, Debug)]
+pub enum NtfsAttribute {
+    Times(NtfsTimes),
+    Unknown { tag: u16, data: Vec<u8> },
+}
+
+/// Stores the Info-ZIP extended timestamp ("UT") of a file as Unix epoch seconds.
+///
+/// The flags indicate which times the local file header's copy holds. The central directory's copy typically only
+/// holds the modification time whilst retaining the same flags, so only the times actually present are stored.
+/// https://libzip.org/specifications/extrafld.txt
+#[derive(Clone, Debug)]
+pub struct ExtendedTimestampExtraField {
+    pub flags: u8,
+    pub times: ExtendedTimestamp,
+}
+
 /// Represents any unparsed extra field.
 #[derive(Clone, Debug)]
 pub struct UnknownExtraField {
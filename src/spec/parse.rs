@@ -35,6 +35,10 @@ impl GeneralPurposeFlag {
             false => 0x0,
             true => 0b1,
         };
+        let lzma_eos_marker: u16 = match self.lzma_eos_marker {
+            false => 0x0,
+            true => 0x2,
+        };
         let data_descriptor: u16 = match self.data_descriptor {
             false => 0x0,
             true => 0x8,
@@ -44,7 +48,7 @@ impl GeneralPurposeFlag {
             true => 0x800,
         };
 
-        (encrypted | data_descriptor | filename_unicode).to_le_bytes()
+        (encrypted | lzma_eos_marker | data_descriptor | filename_unicode | self.other).to_le_bytes()
     }
 }
 
@@ -111,10 +115,12 @@ impl From<[u8; 26]> for LocalFileHeader {
 impl From<u16> for GeneralPurposeFlag {
     fn from(value: u16) -> GeneralPurposeFlag {
         let encrypted = !matches!(value & 0x1, 0);
+        let lzma_eos_marker = !matches!((value & 0x2) >> 1, 0);
         let data_descriptor = !matches!((value & 0x8) >> 3, 0);
         let filename_unicode = !matches!((value & 0x800) >> 11, 0);
+        let other = value & !(0x1 | 0x2 | 0x8 | 0x800);
 
-        GeneralPurposeFlag { encrypted, data_descriptor, filename_unicode }
+        GeneralPurposeFlag { encrypted, lzma_eos_marker, data_descriptor, filename_unicode, other }
     }
 }
 
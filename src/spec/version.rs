@@ -2,15 +2,8 @@
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
 use crate::entry::ZipEntry;
-#[cfg(any(
-    feature = "deflate",
-    feature = "bzip2",
-    feature = "zstd",
-    feature = "lzma",
-    feature = "xz",
-    feature = "deflate64"
-))]
-use crate::spec::Compression;
+use crate::spec::attribute::AttributeCompatibility;
+use crate::spec::{consts::AES_COMPRESSION_METHOD, Compression};
 
 pub(crate) const SPEC_VERSION_MADE_BY: u16 = 63;
 
@@ -25,6 +18,7 @@ pub fn as_needed_to_extract(entry: &ZipEntry) -> u16 {
         Compression::Bz => 46,
         #[cfg(feature = "lzma")]
         Compression::Lzma => 63,
+        Compression::Unsupported(AES_COMPRESSION_METHOD) => 51,
         _ => 10,
     };
 
@@ -36,7 +30,6 @@ pub fn as_needed_to_extract(entry: &ZipEntry) -> u16 {
 }
 
 // https://github.com/Majored/rs-async-zip/blob/main/SPECIFICATION.md#442
-pub fn as_made_by() -> u16 {
-    // Default to UNIX mapping for the moment.
-    3 << 8 | SPEC_VERSION_MADE_BY
+pub fn as_made_by(compatibility: AttributeCompatibility) -> u16 {
+    u16::from(compatibility) << 8 | SPEC_VERSION_MADE_BY
 }
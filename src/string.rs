@@ -3,6 +3,23 @@
 
 use crate::error::{Result, ZipError};
 
+use std::borrow::Cow;
+
+/// The characters which the upper half of IBM Code Page 437 (bytes 0x80 to 0xFF) map to.
+///
+/// The lower half is treated as ASCII, as is common practice for ZIP filenames.
+#[rustfmt::skip]
+const CP437_UPPER: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
 /// A string encoding supported by this crate.
 #[derive(Debug, Clone, Copy)]
 pub enum StringEncoding {
@@ -16,6 +33,8 @@ pub struct ZipString {
     encoding: StringEncoding,
     raw: Vec<u8>,
     alternative: Option<Vec<u8>>,
+    /// Whether the string was declared as UTF-8 (eg. via general purpose bit 11) despite its bytes not being valid.
+    declared_utf8: bool,
 }
 
 impl ZipString {
@@ -25,18 +44,25 @@ impl ZipString {
     /// If the provided encoding is [`StringEncoding::Utf8`] but the raw bytes are not valid UTF-8 (ie. a call to
     /// `std::str::from_utf8()` fails), the encoding is defaulted back to [`StringEncoding::Raw`].
     pub fn new(raw: Vec<u8>, mut encoding: StringEncoding) -> Self {
+        let mut declared_utf8 = false;
         if let StringEncoding::Utf8 = encoding {
             if std::str::from_utf8(&raw).is_err() {
                 encoding = StringEncoding::Raw;
+                declared_utf8 = true;
             }
         }
 
-        Self { encoding, raw, alternative: None }
+        Self { encoding, raw, alternative: None, declared_utf8 }
     }
 
     /// Constructs a new encoded string from utf-8 data, with an alternative in native MBCS encoding.
     pub fn new_with_alternative(utf8: String, alternative: Vec<u8>) -> Self {
-        Self { encoding: StringEncoding::Utf8, raw: utf8.into_bytes(), alternative: Some(alternative) }
+        Self {
+            encoding: StringEncoding::Utf8,
+            raw: utf8.into_bytes(),
+            alternative: Some(alternative),
+            declared_utf8: false,
+        }
     }
 
     /// Returns the raw bytes for this string.
@@ -75,6 +101,47 @@ impl ZipString {
         Ok(unsafe { std::str::from_utf8_unchecked(&self.raw) })
     }
 
+    /// Returns the raw bytes converted into a string slice, or decoded from IBM Code Page 437 if they're not UTF-8.
+    ///
+    /// ZIP filenames and comments without the UTF-8 flag (bit 11) set are defined to be CP437-encoded, which is what
+    /// legacy tools (eg. Windows' built-in archiver) produce. Strings which do have the flag set are never treated as
+    /// CP437, so any invalid UTF-8 sequences within them are instead replaced with U+FFFD. Unlike
+    /// [`ZipString::as_str()`], this never fails, whilst the raw bytes remain accessible via [`ZipString::as_bytes()`].
+    pub fn as_str_cp437(&self) -> Cow<'_, str> {
+        match self.as_str() {
+            Ok(string) => Cow::Borrowed(string),
+            Err(_) if self.declared_utf8 => String::from_utf8_lossy(&self.raw),
+            Err(_) => Cow::Owned(self.raw.iter().map(|&byte| decode_cp437(byte)).collect()),
+        }
+    }
+
+    /// Returns the raw bytes converted into a string slice, or decoded from the provided legacy codepage if they're not
+    /// UTF-8.
+    ///
+    /// This is useful where a ZIP file is known to have been produced with a local OEM codepage other than CP437 (eg.
+    /// Shift-JIS). Any bytes which are invalid in that codepage are replaced with U+FFFD. As with
+    /// [`ZipString::as_str_cp437()`], strings with the UTF-8 flag (bit 11) set are never decoded from the codepage.
+    #[cfg(feature = "encoding")]
+    pub fn as_str_with_encoding(&self, encoding: &'static encoding_rs::Encoding) -> Cow<'_, str> {
+        match self.as_str() {
+            Ok(string) => Cow::Borrowed(string),
+            Err(_) if self.declared_utf8 => String::from_utf8_lossy(&self.raw),
+            Err(_) => encoding.decode_without_bom_handling(&self.raw).0,
+        }
+    }
+
+    /// Decodes a string without the UTF-8 flag (bit 11) set from the provided legacy codepage, keeping its raw bytes
+    /// as the alternative.
+    #[cfg(feature = "encoding")]
+    pub(crate) fn decode_legacy(self, encoding: &'static encoding_rs::Encoding) -> Self {
+        if !matches!(self.encoding, StringEncoding::Raw) || self.declared_utf8 {
+            return self;
+        }
+
+        let decoded = encoding.decode_without_bom_handling(&self.raw).0.into_owned();
+        Self::new_with_alternative(decoded, self.raw)
+    }
+
     /// Returns the raw bytes converted to an owned string.
     ///
     /// # Note
@@ -99,14 +166,21 @@ impl ZipString {
     }
 }
 
+fn decode_cp437(byte: u8) -> char {
+    match byte {
+        0x00..=0x7F => byte as char,
+        _ => CP437_UPPER[(byte - 0x80) as usize],
+    }
+}
+
 impl From<String> for ZipString {
     fn from(value: String) -> Self {
-        Self { encoding: StringEncoding::Utf8, raw: value.into_bytes(), alternative: None }
+        Self { encoding: StringEncoding::Utf8, raw: value.into_bytes(), alternative: None, declared_utf8: false }
     }
 }
 
 impl From<&str> for ZipString {
     fn from(value: &str) -> Self {
-        Self { encoding: StringEncoding::Utf8, raw: value.as_bytes().to_vec(), alternative: None }
+        Self { encoding: StringEncoding::Utf8, raw: value.as_bytes().to_vec(), alternative: None, declared_utf8: false }
     }
 }
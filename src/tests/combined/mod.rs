@@ -1,2 +1,51 @@
 // Copyright (c) 2022 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::base::read::mem::ZipFileReader;
+use crate::base::write::ZipFileWriter;
+use crate::spec::extra_field::ExtraFieldAsBytes;
+use crate::spec::header::{ExtraField, HeaderId, UnknownExtraField};
+use crate::{Compression, ZipDateTime, ZipEntry, ZipEntryBuilder};
+
+async fn write_single(builder: ZipEntryBuilder, data: &[u8]) -> ZipFileReader {
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.write_entry_whole(builder, data).await.unwrap();
+    ZipFileReader::new(writer.close().await.unwrap()).await.unwrap()
+}
+
+fn assert_metadata_eq(a: &ZipEntry, b: &ZipEntry) {
+    assert_eq!(a.filename().as_bytes(), b.filename().as_bytes());
+    assert_eq!(a.comment().as_bytes(), b.comment().as_bytes());
+    assert_eq!(a.compression(), b.compression());
+    assert_eq!(a.last_modification_date(), b.last_modification_date());
+    assert_eq!(a.internal_file_attribute(), b.internal_file_attribute());
+    assert_eq!(a.external_file_attribute(), b.external_file_attribute());
+    assert_eq!(a.extra_fields().as_bytes(), b.extra_fields().as_bytes());
+    assert_eq!(a.crc32(), b.crc32());
+    assert_eq!(a.uncompressed_size(), b.uncompressed_size());
+}
+
+#[tokio::test]
+async fn to_builder_preserves_metadata() {
+    crate::tests::init_logger();
+
+    let data = b"Some data which is copied between archives.";
+    let builder = ZipEntryBuilder::new(String::from("foo/bar.txt").into(), Compression::Stored)
+        .comment(String::from("A comment.").into())
+        .last_modification_date(ZipDateTime { date: 0x5621, time: 0x4a2b })
+        .internal_file_attribute(1)
+        .unix_permissions(0o100755)
+        .extra_fields(vec![ExtraField::Unknown(UnknownExtraField {
+            header_id: HeaderId(0xCAFE),
+            data_size: 4,
+            content: vec![1, 2, 3, 4],
+        })]);
+
+    let source = write_single(builder, data).await;
+    let source_entry: &ZipEntry = &source.file().entries()[0];
+
+    let destination = write_single(source_entry.to_builder(), data).await;
+    let destination_entry: &ZipEntry = &destination.file().entries()[0];
+
+    assert_metadata_eq(source_entry, destination_entry);
+}
@@ -0,0 +1,31 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::base::read::mem::ZipFileReader;
+use crate::base::write::ZipFileWriter;
+use crate::tests::init_logger;
+use crate::{Compression, ZipEntryBuilder};
+
+const CONTENTS: &[u8] = b"This data will be copied into a synchronous writer.";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_blocking_copy_entry_to() {
+    init_logger();
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let entry = ZipEntryBuilder::new("foo.txt".to_string().into(), Compression::Stored);
+    writer.write_entry_whole(entry, CONTENTS).await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = ZipFileReader::new(data).await.unwrap();
+    let (copied, sink) = tokio::task::spawn_blocking(move || {
+        let mut sink = Vec::new();
+        let copied = reader.blocking_copy_entry_to(0, &mut sink).unwrap();
+        (copied, sink)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(copied, CONTENTS.len() as u64);
+    assert_eq!(sink, CONTENTS);
+}
@@ -33,7 +33,7 @@ macro_rules! compressed_test_helper {
             let data_raw = $data_raw;
 
             let cursor = Cursor::new(data);
-            let mut reader = CompressedReader::new(cursor, $typ);
+            let mut reader = CompressedReader::new(cursor, $typ, None).unwrap();
 
             let mut read_data = String::new();
             reader.read_to_string(&mut read_data).await.expect("read into CompressedReader failed");
@@ -1,6 +1,9 @@
 // Copyright (c) 2022 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
 
+#[cfg(feature = "tokio")]
+pub(crate) mod blocking;
 pub(crate) mod compression;
 pub(crate) mod locator;
+pub(crate) mod stream;
 pub(crate) mod zip64;
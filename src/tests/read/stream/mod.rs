@@ -0,0 +1,27 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::base::read::stream::ZipFileReader;
+use crate::base::write::ZipFileWriter;
+use crate::error::ZipError;
+use crate::tests::init_logger;
+use crate::{Compression, ZipEntryBuilder};
+
+use futures_util::io::AsyncWriteExt;
+
+/// Tests that a stored entry written with a data descriptor (and no sizes) errors when stream read.
+#[tokio::test]
+async fn test_stream_stored_data_descriptor_errors() {
+    init_logger();
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let entry = ZipEntryBuilder::new("foo.txt".to_string().into(), Compression::Stored);
+    let mut entry_writer = writer.write_entry_stream(entry).await.unwrap();
+    entry_writer.write_all(b"Some data of an unknown size.").await.unwrap();
+    entry_writer.close().await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = ZipFileReader::new(data.as_slice());
+    let result = reader.next_without_entry().await;
+    assert!(matches!(result, Err(ZipError::StreamingStoredWithoutSize)));
+}
@@ -31,6 +31,19 @@ async fn test_read_zip64_archive_mem() {
     assert_eq!(read_data, ZIP64_ZIP_CONTENTS);
 }
 
+/// Tests that opening a zip64 archive errors when ZIP64 support has been disabled.
+#[tokio::test]
+async fn test_read_zip64_archive_force_no_zip64() {
+    use crate::base::read::{mem::ZipFileReader, ReadOptions};
+    use crate::error::ZipError;
+    init_logger();
+
+    let data = include_bytes!("zip64.zip").to_vec();
+
+    let result = ZipFileReader::with_options(data, ReadOptions::new().force_no_zip64()).await;
+    assert!(matches!(result, Err(ZipError::Zip64Required)));
+}
+
 /// Like test_read_zip64_archive_mem() but for the streaming version
 #[tokio::test]
 async fn test_read_zip64_archive_stream() {
@@ -12,3 +12,22 @@ fn date_conversion_test() {
     let result_dt = zip_dt.as_chrono().single().expect("expected single unique result");
     assert_eq!(result_dt, original_dt);
 }
+
+#[test]
+#[cfg(feature = "tokio-fs")]
+fn system_time_conversion_test() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 2022-10-23 16:55:02 UTC.
+    let zip_dt = crate::ZipDateTime { date: (42 << 9) | (10 << 5) | 23, time: (16 << 11) | (55 << 5) | 1 };
+    assert_eq!(zip_dt.as_system_time(), Some(UNIX_EPOCH + Duration::from_secs(1666544102)));
+
+    assert_eq!(crate::ZipDateTime::default().as_system_time(), None);
+
+    let from_system_time = crate::ZipDateTime::from_system_time(UNIX_EPOCH + Duration::from_secs(1666544103));
+    assert_eq!(from_system_time, Some(zip_dt));
+    assert_eq!(crate::ZipDateTime::from_system_time(UNIX_EPOCH), None);
+
+    let times = crate::NtfsTimes::new(116_444_736_000_000_000 + 15, 0, 0);
+    assert_eq!(times.modified_system_time(), Some(UNIX_EPOCH + Duration::from_nanos(1500)));
+}
@@ -0,0 +1,54 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::spec::extra_field::{extra_field_from_bytes, ExtraFieldAsBytes};
+use crate::spec::header::{ExtraField, HeaderId, NtfsAttribute};
+use crate::ExtendedTimestamp;
+
+#[test]
+fn ntfs_extra_field_skips_unknown_tags() {
+    let mut data = vec![0; 4];
+    data.extend_from_slice(&[0x02, 0x00, 0x03, 0x00, 0xAA, 0xBB, 0xCC]);
+    data.extend_from_slice(&[0x01, 0x00, 0x18, 0x00]);
+    for time in [1u64, 2, 3] {
+        data.extend_from_slice(&time.to_le_bytes());
+    }
+
+    let field = extra_field_from_bytes(HeaderId::NTFS_EXTRA_FIELD, data.len() as u16, &data, 0, 0).unwrap();
+    let ExtraField::Ntfs(ntfs) = &field else { panic!("expected an NTFS extra field") };
+    assert!(matches!(&ntfs.attributes[0], NtfsAttribute::Unknown { tag: 2, data } if data == &[0xAA, 0xBB, 0xCC]));
+    assert!(
+        matches!(&ntfs.attributes[1], NtfsAttribute::Times(times) if times.modified() == 1 && times.created() == 3)
+    );
+
+    let mut expected = vec![0x0A, 0x00, data.len() as u8, 0x00];
+    expected.extend_from_slice(&data);
+    assert_eq!(field.as_bytes(), expected);
+}
+
+#[test]
+fn ntfs_extra_field_truncated_is_kept_unknown() {
+    let data = [0, 0, 0, 0, 0x01, 0x00, 0x18, 0x00, 0xFF];
+
+    let field = extra_field_from_bytes(HeaderId::NTFS_EXTRA_FIELD, data.len() as u16, &data, 0, 0).unwrap();
+    assert!(matches!(field, ExtraField::Unknown(field) if field.content == data));
+}
+
+#[test]
+fn extended_timestamp_respects_field_length() {
+    let times = |modified, accessed, created| ExtendedTimestamp { modified, accessed, created };
+    let cases: [(&[u8], ExtendedTimestamp); 4] = [
+        (&[0x07, 0x10, 0, 0, 0, 0x20, 0, 0, 0, 0x30, 0, 0, 0], times(Some(0x10), Some(0x20), Some(0x30))),
+        (&[0x07, 0x10, 0, 0, 0], times(Some(0x10), None, None)),
+        (&[0x06, 0x20, 0, 0, 0, 0x30, 0, 0, 0], times(None, Some(0x20), Some(0x30))),
+        (&[0x03], times(None, None, None)),
+    ];
+
+    for (data, expected) in cases {
+        let field =
+            extra_field_from_bytes(HeaderId::EXTENDED_TIMESTAMP_EXTRA_FIELD, data.len() as u16, data, 0, 0).unwrap();
+        let ExtraField::ExtendedTimestamp(timestamp) = &field else { panic!("expected an extended timestamp field") };
+        assert_eq!(timestamp.times, expected);
+        assert_eq!(&field.as_bytes()[4..], data);
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+/// Tests that extra fields are written in ascending order of their header IDs, regardless of the order provided.
+#[tokio::test]
+async fn test_extra_field_ordering() {
+    use crate::base::write::ZipFileWriter;
+    use crate::spec::header::{ExtraField, HeaderId, UnknownExtraField};
+    use crate::{Compression, ZipEntryBuilder};
+    use futures_util::io::AsyncWriteExt;
+
+    crate::tests::init_logger();
+
+    let unknown = |header_id: u16, content: &[u8]| {
+        ExtraField::Unknown(UnknownExtraField {
+            header_id: HeaderId(header_id),
+            data_size: content.len() as u16,
+            content: content.to_vec(),
+        })
+    };
+    let fields = vec![
+        unknown(0x7875, &[1, 4, 232, 3, 0, 0, 4, 232, 3, 0, 0]),
+        unknown(0x5455, &[1, 0, 0, 0, 0]),
+        unknown(0x000a, &[0; 32]),
+    ];
+
+    let mut archives = Vec::new();
+    for fields in [fields.clone(), fields.into_iter().rev().collect()] {
+        let mut writer = ZipFileWriter::new(Vec::new());
+
+        let builder = ZipEntryBuilder::new("whole.txt".into(), Compression::Stored).extra_fields(fields.clone());
+        writer.write_entry_whole(builder, b"whole").await.unwrap();
+
+        let builder = ZipEntryBuilder::new("stream.txt".into(), Compression::Stored).extra_fields(fields);
+        let mut entry_writer = writer.write_entry_stream(builder).await.unwrap();
+        entry_writer.write_all(b"stream").await.unwrap();
+        entry_writer.close().await.unwrap();
+
+        archives.push(writer.close().await.unwrap());
+    }
+    assert_eq!(archives[0], archives[1]);
+
+    let reader = crate::base::read::mem::ZipFileReader::new(archives.remove(0)).await.unwrap();
+    for entry in reader.file().entries() {
+        let ids: Vec<u16> = entry.extra_fields().iter().map(|field| field.header_id().0).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(ids.ends_with(&[0x000a, 0x5455, 0x7875]));
+    }
+}
+
+/// Tests that separately set local and central extra fields are written to their respective headers, and that the
+/// writer's own fields are still written to both.
+#[tokio::test]
+async fn test_separate_local_and_central_extra_fields() {
+    use crate::base::read::{mem, stream};
+    use crate::base::write::ZipFileWriter;
+    use crate::spec::header::{ExtendedTimestampExtraField, ExtraField};
+    use crate::{Compression, ExtendedTimestamp, ZipEntryBuilder, ZipString};
+
+    crate::tests::init_logger();
+
+    let timestamp = |accessed| {
+        let times = ExtendedTimestamp { modified: Some(1686856496), accessed, created: None };
+        vec![ExtraField::ExtendedTimestamp(ExtendedTimestampExtraField { flags: 0x03, times })]
+    };
+
+    // The Info-ZIP Unicode Path field added by the writer should be written to both headers regardless.
+    let name = ZipString::new_with_alternative("caf\u{e9}.txt".into(), b"caf\x82.txt".to_vec());
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new(name, Compression::Stored)
+        .central_extra_field(timestamp(None))
+        .local_extra_field(timestamp(Some(1792043396)));
+    writer.write_entry_whole(builder, b"separate").await.unwrap();
+    let builder = ZipEntryBuilder::new("sync.txt".into(), Compression::Stored).extra_fields(timestamp(None));
+    writer.write_entry_whole(builder, b"sync").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let central = mem::ZipFileReader::new(data.clone()).await.unwrap();
+    let mut local = stream::ZipFileReader::new(data.as_slice());
+
+    for entry in central.file().entries() {
+        let reader = local.next_with_entry().await.unwrap().unwrap();
+        let local_entry = reader.reader().entry();
+
+        let accessed = (entry.index() == 0).then_some(1792043396);
+        assert_eq!(local_entry.extended_timestamp().unwrap().accessed(), accessed);
+        assert_eq!(entry.extended_timestamp().unwrap().accessed(), None);
+        assert_eq!(local_entry.filename().as_bytes(), entry.filename().as_bytes());
+        assert_eq!(
+            local_entry.extra_fields().iter().any(|field| matches!(field, ExtraField::InfoZipUnicodePath(_))),
+            entry.index() == 0
+        );
+
+        local = reader.skip().await.unwrap();
+    }
+}
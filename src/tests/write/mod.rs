@@ -6,6 +6,7 @@ use std::io::Error;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+mod extra_field;
 pub(crate) mod offset;
 mod zip64;
 
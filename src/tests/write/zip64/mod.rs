@@ -2,14 +2,17 @@
 
 use crate::base::write::ZipFileWriter;
 use crate::error::{Zip64ErrorCase, ZipError};
-use crate::spec::consts::NON_ZIP64_MAX_SIZE;
+use crate::spec::consts::{CDH_SIGNATURE, EOCDR_SIGNATURE, NON_ZIP64_MAX_SIZE};
 use crate::tests::init_logger;
 use crate::tests::write::AsyncSink;
 use crate::{Compression, ZipEntryBuilder};
 use std::io::Read;
 
 use crate::spec::header::ExtraField;
-use futures_util::io::AsyncWriteExt;
+use futures_util::io::{AsyncRead, AsyncSeek, AsyncWrite, AsyncWriteExt};
+use std::io::{Error, SeekFrom};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 // Useful constants for writing a large file.
 const BATCH_SIZE: usize = 100_000;
@@ -48,6 +51,69 @@ async fn test_write_zip64_file() {
     assert_eq!(buffer.as_slice(), &[0, 0, 0, 0]);
 }
 
+/// Test that the classic EOCDR is written alongside the zip64 records, and that it's usable by a classic-only parser.
+#[tokio::test]
+async fn test_force_zip64_writes_classic_eocdr() {
+    init_logger();
+
+    let mut buffer = Vec::new();
+    let mut writer = ZipFileWriter::new(&mut buffer).force_zip64();
+    for name in ["file1", "file2"] {
+        let entry = ZipEntryBuilder::new(name.into(), Compression::Stored);
+        writer.write_entry_whole(entry, &[0, 0, 0, 0]).await.unwrap();
+    }
+    writer.close().await.unwrap();
+
+    // A classic-only parser: locate the EOCDR at the end of the file and walk the central directory it points to.
+    let eocdr = &buffer[buffer.len() - 22..];
+    assert_eq!(u32::from_le_bytes(eocdr[0..4].try_into().unwrap()), EOCDR_SIGNATURE);
+    let num_entries = u16::from_le_bytes(eocdr[10..12].try_into().unwrap());
+    let cd_size = u32::from_le_bytes(eocdr[12..16].try_into().unwrap()) as usize;
+    let cd_offset = u32::from_le_bytes(eocdr[16..20].try_into().unwrap()) as usize;
+    assert_eq!(num_entries, 2);
+
+    let mut names = Vec::new();
+    let mut record = &buffer[cd_offset..cd_offset + cd_size];
+    while !record.is_empty() {
+        assert_eq!(u32::from_le_bytes(record[0..4].try_into().unwrap()), CDH_SIGNATURE);
+        let name_length = u16::from_le_bytes(record[28..30].try_into().unwrap()) as usize;
+        let extra_length = u16::from_le_bytes(record[30..32].try_into().unwrap()) as usize;
+        let comment_length = u16::from_le_bytes(record[32..34].try_into().unwrap()) as usize;
+        names.push(String::from_utf8(record[46..46 + name_length].to_vec()).unwrap());
+        record = &record[46 + name_length + extra_length + comment_length..];
+    }
+    assert_eq!(names, ["file1", "file2"]);
+
+    let reader = crate::base::read::mem::ZipFileReader::new(buffer).await.unwrap();
+    assert!(reader.file().zip64);
+    assert_eq!(reader.file().entries().len(), 2);
+}
+
+/// Test that the values within the zip64 EOCDR are preferred when the classic EOCDR holds sentinel values.
+#[tokio::test]
+async fn test_read_zip64_prefers_zip64_eocdr_with_sentinels() {
+    init_logger();
+
+    let mut buffer = Vec::new();
+    let mut writer = ZipFileWriter::new(&mut buffer).force_zip64();
+    let entry = ZipEntryBuilder::new("file1".into(), Compression::Stored);
+    writer.write_entry_whole(entry, &[1, 2, 3, 4]).await.unwrap();
+    writer.close().await.unwrap();
+
+    let eocdr_start = buffer.len() - 22;
+    buffer[eocdr_start + 8..eocdr_start + 12].copy_from_slice(&[0xFF; 4]);
+    buffer[eocdr_start + 12..eocdr_start + 20].copy_from_slice(&[0xFF; 8]);
+
+    let reader = crate::base::read::mem::ZipFileReader::new(buffer).await.unwrap();
+    assert!(reader.file().zip64);
+    assert_eq!(reader.file().entries().len(), 1);
+    assert_eq!(reader.file().entries()[0].filename().as_str().unwrap(), "file1");
+
+    let mut data = Vec::new();
+    reader.reader_with_entry(0).await.unwrap().read_to_end_checked(&mut data).await.unwrap();
+    assert_eq!(data, [1, 2, 3, 4]);
+}
+
 /// Test writing a large zip64 file. This test will use upwards of 4GB of memory.
 #[tokio::test]
 async fn test_write_large_zip64_file() {
@@ -134,6 +200,50 @@ async fn test_write_large_zip64_file_self_read() {
     assert_eq!(bytes_total, BATCHED_FILE_SIZE);
 }
 
+/// Test that an entry larger than ~4 GiB is given zip64 extra fields, as is an entry which follows it (for its offset),
+/// and that the zip64 EOCDR and locator are written. The data is never held in memory as it's entirely zeroed.
+#[tokio::test]
+async fn test_write_large_zip64_file_sparse() {
+    use crate::base::read::seek::ZipFileReader;
+    use crate::spec::consts::{ZIP64_EOCDL_LENGTH, ZIP64_EOCDL_SIGNATURE};
+    use futures_util::io::{AsyncReadExt, AsyncSeekExt};
+
+    init_logger();
+
+    const LARGE_FILE_SIZE: u64 = NON_ZIP64_MAX_SIZE as u64 + 1024;
+
+    let mut writer = ZipFileWriter::new(SparseBuffer::default());
+    let entry =
+        ZipEntryBuilder::new("large".into(), Compression::Stored).size(LARGE_FILE_SIZE, LARGE_FILE_SIZE).build();
+    writer.write_entry_raw(entry, AsyncZeroes).await.unwrap();
+    let entry = ZipEntryBuilder::new("small".into(), Compression::Stored);
+    writer.write_entry_whole(entry, b"small").await.unwrap();
+    assert!(writer.is_zip64);
+    let mut buffer = writer.close().await.unwrap();
+
+    // The EOCDL immediately precedes the (comment-less) classic EOCDR.
+    let mut eocdl_signature = [0; 4];
+    buffer.seek(SeekFrom::End(-22 - ZIP64_EOCDL_LENGTH as i64)).await.unwrap();
+    buffer.read_exact(&mut eocdl_signature).await.unwrap();
+    assert_eq!(u32::from_le_bytes(eocdl_signature), ZIP64_EOCDL_SIGNATURE);
+
+    let mut reader = ZipFileReader::new(buffer).await.unwrap();
+    assert!(reader.file().zip64);
+
+    let large = &reader.file().entries()[0];
+    assert_eq!(large.compressed_size(), LARGE_FILE_SIZE);
+    assert_eq!(large.uncompressed_size(), LARGE_FILE_SIZE);
+    assert!(large.extra_fields().iter().any(|field| matches!(field, ExtraField::Zip64ExtendedInformation(_))));
+
+    let small = &reader.file().entries()[1];
+    assert!(small.header_offset() > NON_ZIP64_MAX_SIZE as u64);
+    assert!(small.extra_fields().iter().any(|field| matches!(field, ExtraField::Zip64ExtendedInformation(_))));
+
+    let mut data = Vec::new();
+    reader.reader_with_entry(1).await.unwrap().read_to_end_checked(&mut data).await.unwrap();
+    assert_eq!(data, b"small");
+}
+
 /// Test writing a zip64 file with more than u16::MAX files.
 #[tokio::test]
 async fn test_write_zip64_file_many_entries() {
@@ -241,3 +351,146 @@ async fn test_force_no_zip64_errors_with_too_large_file_stream() {
 
     assert!(matches!(result, Err(ZipError::Zip64Needed(Zip64ErrorCase::LargeFile))));
 }
+
+/// Tests that when force_no_zip64 is true, closing errors if the central directory would start beyond ~4 GiB even
+/// though no single entry needed zip64.
+#[tokio::test]
+async fn test_force_no_zip64_errors_with_central_directory_beyond_limit() {
+    const FILE_SIZE: u64 = NON_ZIP64_MAX_SIZE as u64 - 1024;
+
+    let mut writer = ZipFileWriter::new(SparseBuffer::default()).force_no_zip64();
+    let entry = ZipEntryBuilder::new("large".into(), Compression::Stored).size(FILE_SIZE, FILE_SIZE).build();
+    writer.write_entry_raw(entry, AsyncZeroes).await.unwrap();
+    let entry = ZipEntryBuilder::new("small".into(), Compression::Stored);
+    writer.write_entry_whole(entry, &[0; 2048]).await.unwrap();
+    let result = writer.close().await;
+
+    assert!(matches!(result, Err(ZipError::Zip64Needed(Zip64ErrorCase::TooLargeCentralDirectory))));
+}
+
+/// Tests that central directory records are spilled once the in-memory limit is reached, and copied back on close.
+#[tokio::test]
+async fn test_spill_central_directory() {
+    use futures_util::io::Cursor;
+
+    init_logger();
+
+    let mut writer = ZipFileWriter::new(Vec::new()).spill_central_directory(Cursor::new(Vec::new()), 64).unwrap();
+    for index in 0..5000 {
+        let entry = ZipEntryBuilder::new(format!("{index}.txt").into(), Compression::Stored);
+        if index % 2 == 0 {
+            writer.write_entry_whole(entry, index.to_string().as_bytes()).await.unwrap();
+        } else {
+            let mut entry_writer = writer.write_entry_stream(entry).await.unwrap();
+            entry_writer.write_all(index.to_string().as_bytes()).await.unwrap();
+            entry_writer.close().await.unwrap();
+        }
+        assert!(writer.cd_entries.len() <= 64);
+    }
+    let data = writer.close().await.unwrap();
+
+    let reader = crate::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    assert_eq!(reader.file().entries().len(), 5000);
+
+    for index in [0, 63, 64, 4321, 4999] {
+        assert_eq!(reader.file().entries()[index].filename().as_str().unwrap(), format!("{index}.txt"));
+
+        let mut buffer = String::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+        assert_eq!(buffer, index.to_string());
+    }
+}
+
+/// An in-memory buffer which doesn't store runs of zeroes.
+/// Useful for tests that involve writing and reading back large amounts of (zeroed) data.
+#[derive(Default)]
+struct SparseBuffer {
+    segments: Vec<(u64, Vec<u8>)>,
+    len: u64,
+    position: u64,
+}
+
+const ZEROES: [u8; 8192] = [0; 8192];
+
+// Writes are always appended, with any write consisting solely of zeroes being left as a hole.
+impl AsyncWrite for SparseBuffer {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, Error>> {
+        let this = self.get_mut();
+
+        if !buf.chunks(ZEROES.len()).all(|chunk| chunk == &ZEROES[..chunk.len()]) {
+            match this.segments.last_mut() {
+                Some((start, data)) if *start + data.len() as u64 == this.len => data.extend_from_slice(buf),
+                _ => this.segments.push((this.len, buf.to_vec())),
+            }
+        }
+
+        this.len += buf.len() as u64;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncRead for SparseBuffer {
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize, Error>> {
+        let this = self.get_mut();
+        let mut read = 0;
+
+        // Fill the buffer across segments and holes, as some readers assume a short read means EOF is near.
+        while read < buf.len() && this.position < this.len {
+            let position = this.position;
+            let index = this.segments.partition_point(|(start, _)| *start <= position);
+            let remaining = &mut buf[read..];
+
+            let length = match index.checked_sub(1).map(|index| &this.segments[index]) {
+                Some((start, data)) if position < start + data.len() as u64 => {
+                    let available = &data[(position - start) as usize..];
+                    let length = std::cmp::min(available.len(), remaining.len());
+                    remaining[..length].copy_from_slice(&available[..length]);
+                    length
+                }
+                _ => {
+                    let hole_end = this.segments.get(index).map_or(this.len, |(start, _)| *start);
+                    let length = std::cmp::min(hole_end - position, remaining.len() as u64) as usize;
+                    remaining[..length].fill(0);
+                    length
+                }
+            };
+
+            this.position += length as u64;
+            read += length;
+        }
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl AsyncSeek for SparseBuffer {
+    fn poll_seek(self: Pin<&mut Self>, _: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64, Error>> {
+        let this = self.get_mut();
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => this.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => this.position.checked_add_signed(offset),
+        };
+
+        this.position = position.ok_or_else(|| Error::from(std::io::ErrorKind::InvalidInput))?;
+        Poll::Ready(Ok(this.position))
+    }
+}
+
+/// An endless source of zeroes for AsyncRead.
+struct AsyncZeroes;
+
+impl AsyncRead for AsyncZeroes {
+    fn poll_read(self: Pin<&mut Self>, _: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize, Error>> {
+        buf.fill(0);
+        Poll::Ready(Ok(buf.len()))
+    }
+}
@@ -0,0 +1,652 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+//! A module which supports extracting ZIP files to the file system.
+//!
+//! ### Example
+//! ```no_run
+//! # use async_zip::tokio::read::fs::ZipFileReader;
+//! # use async_zip::error::Result;
+//! # use std::path::Path;
+//! #
+//! async fn run() -> Result<()> {
+//!     let reader = ZipFileReader::new("./foo.zip").await?;
+//!
+//!     reader
+//!         .extractor()
+//!         .on_entry_written(|path| println!("extracted {}", path.display()))
+//!         .extract_to(Path::new("./out"))
+//!         .await
+//! }
+//! ```
+
+use crate::date::unix_time;
+use crate::entry::safety::{is_symlink, UnsafeReason};
+use crate::entry::ZipEntry;
+use crate::error::{Result, ZipError};
+use crate::tokio::read::fs::ZipFileReader;
+
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use futures_util::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::fs::File;
+use tokio_util::compat::TokioAsyncWriteCompatExt;
+
+type PathHook<'a> = Box<dyn FnMut(&Path) + Send + 'a>;
+type MetadataHook<'a> = Box<dyn FnMut(&ZipEntry, &Path) + Send + 'a>;
+type ProgressHook<'a> = Box<dyn FnMut(u64, u64) + Send + 'a>;
+type ErrorHook<'a> = Box<dyn FnMut(usize, &ZipError) -> ErrorAction + Send + 'a>;
+
+/// The size of the buffer used when copying an entry's data to its file, equal to 64KiB.
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+/// The maximum numeric suffix tried when renaming a file which conflicts with an existing one.
+const MAX_RENAME_SUFFIX: u32 = 10_000;
+
+/// The action taken after an entry fails to be extracted, as decided by the hook set via [`Extractor::on_error()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Skip the entry, removing any partially written file, and continue with the next.
+    Skip,
+    /// Remove any partially written file, and attempt to extract the entry again.
+    Retry,
+    /// Stop extracting and return the error.
+    Abort,
+}
+
+/// The action taken when an entry's file already exists within the output directory, as set via
+/// [`Extractor::on_conflict()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Truncate and overwrite the existing file.
+    #[default]
+    Overwrite,
+    /// Leave the existing file untouched and skip the entry.
+    Skip,
+    /// Fail with an [`ErrorKind::AlreadyExists`] I/O error, which is passed to the [error hook](Extractor::on_error).
+    Error,
+    /// Extract to a new file whose name has a numeric suffix (eg. `file (1).txt`), leaving the existing file untouched.
+    Rename,
+}
+
+/// A builder which extracts the entries of a ZIP file to the file system.
+///
+/// Constructed via [`ZipFileReader::extractor()`].
+pub struct Extractor<'a> {
+    reader: &'a ZipFileReader,
+    on_entry_written: Option<PathHook<'a>>,
+    on_metadata: Option<MetadataHook<'a>>,
+    only: Option<HashSet<usize>>,
+    on_progress: Option<ProgressHook<'a>>,
+    on_error: Option<ErrorHook<'a>>,
+    on_conflict: ConflictPolicy,
+    reject_unsafe_paths: bool,
+    allow_symlinks: bool,
+    partial_file: Option<PathBuf>,
+    #[cfg(unix)]
+    dir_permissions: Vec<(PathBuf, u32)>,
+}
+
+impl<'a> Extractor<'a> {
+    pub(crate) fn new(reader: &'a ZipFileReader) -> Self {
+        Self {
+            reader,
+            on_entry_written: None,
+            on_metadata: None,
+            only: None,
+            on_progress: None,
+            on_error: None,
+            on_conflict: ConflictPolicy::default(),
+            reject_unsafe_paths: true,
+            allow_symlinks: false,
+            partial_file: None,
+            #[cfg(unix)]
+            dir_permissions: Vec::new(),
+        }
+    }
+
+    /// Sets a hook which is called with the path of each file or directory once it has been written.
+    ///
+    /// Directories are only reported once, regardless of whether they were created because of an explicit directory
+    /// entry, because they were the parent of another entry, or both.
+    pub fn on_entry_written(mut self, hook: impl FnMut(&Path) + Send + 'a) -> Self {
+        self.on_entry_written = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets a hook which is called with each entry and the path it's being extracted to, before its content is
+    /// written.
+    ///
+    /// The path will already exist when the hook is called, allowing metadata held within the entry (eg. its
+    /// [external file attributes](ZipEntry::external_file_attribute) or [extra fields](ZipEntry::extra_fields)) to
+    /// be applied to it, such as extended attributes or ACLs.
+    pub fn on_metadata(mut self, hook: impl FnMut(&ZipEntry, &Path) + Send + 'a) -> Self {
+        self.on_metadata = Some(Box::new(hook));
+        self
+    }
+
+    /// Restricts extraction to the entries at the provided indices.
+    ///
+    /// Parent directories of the selected entries are still created, but unselected directory entries are skipped.
+    pub fn only(mut self, indices: impl IntoIterator<Item = usize>) -> Self {
+        self.only = Some(indices.into_iter().collect());
+        self
+    }
+
+    /// Sets a hook which is called with the index of an entry and the error it failed with, deciding whether it's
+    /// skipped, retried, or whether extraction is aborted.
+    ///
+    /// Without this hook, the first error aborts extraction. The hook is called again if a retried entry fails, so it's
+    /// up to the hook to bound the number of retries. When used with [`Extractor::verify_and_extract()`], entries
+    /// skipped during verification aren't extracted.
+    pub fn on_error(mut self, hook: impl FnMut(usize, &ZipError) -> ErrorAction + Send + 'a) -> Self {
+        self.on_error = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets the action taken when an entry's file already exists within the output directory.
+    ///
+    /// By default, existing files are overwritten. Conflicts are checked as each file is created, so an entry whose
+    /// name duplicates an earlier entry's also conflicts with the file that entry was extracted to. When renaming, the
+    /// suffix is inserted before the file's last extension, and the lowest free number is used.
+    pub fn on_conflict(mut self, policy: ConflictPolicy) -> Self {
+        self.on_conflict = policy;
+        self
+    }
+
+    /// Sets whether entries with unsafe names are rejected rather than sanitised.
+    ///
+    /// When set, an entry whose name [`ZipEntry::unsafe_reason()`] flags (eg. an absolute path or one containing a
+    /// `..` component) fails with [`ZipError::UnsafeEntryPath`], which is passed to the
+    /// [error hook](Extractor::on_error). This is set by default; when unset, such names are instead sanitised as per
+    /// [`Extractor::extract_to()`].
+    pub fn reject_unsafe_paths(mut self, reject: bool) -> Self {
+        self.reject_unsafe_paths = reject;
+        self
+    }
+
+    /// Sets whether symbolic link entries (those whose Unix mode has the file type `0o120000`) are extracted.
+    ///
+    /// By default, such entries fail with [`ZipError::UnsafeEntryPath`], as their target may point outside of the
+    /// output directory and later entries could then be written through them. When allowed, a link is created with
+    /// the entry's data as its target, so this should only be enabled for trusted ZIP files. Symbolic links can only
+    /// be created on Unix.
+    pub fn allow_symlinks(mut self, allow: bool) -> Self {
+        self.allow_symlinks = allow;
+        self
+    }
+
+    /// Extracts all entries (or those selected via [`Extractor::only()`]) into the provided output directory.
+    ///
+    /// Unless [unsafe names are rejected](Extractor::reject_unsafe_paths), entry names are sanitised before use:
+    /// backslashes are treated as separators, and any empty, `.`, or `..` components are removed, as are any containing
+    /// a colon (eg. a `C:` drive prefix). Missing parent directories are created.
+    ///
+    /// Each file's modification time is restored from its entry, preferring an extended timestamp or NTFS extra field
+    /// over the MS-DOS date & time. On Unix, the permission bits of entries which carry a Unix mode are also restored
+    /// (those of directories once all entries have been extracted, so that read-only directories can be populated).
+    pub async fn extract_to(self, out_dir: &Path) -> Result<()> {
+        self.extract_into(OutDir::Path(out_dir)).await
+    }
+
+    /// Extracts entries as per [`Extractor::extract_to()`], but into the provided [`cap_std::fs::Dir`] capability.
+    ///
+    /// Every file and directory is opened relative to the capability, so the operating system prevents any write from
+    /// escaping it even if sanitisation were bypassed (eg. by a symbolic link already within the directory, or one
+    /// [extracted from the ZIP file](Extractor::allow_symlinks)). The paths passed to hooks are relative to the
+    /// directory. Files are opened and directories created via `cap-std`'s blocking API, whereas entry data is written
+    /// asynchronously.
+    ///
+    /// Note that this requires the `cap-std` feature.
+    #[cfg(feature = "cap-std")]
+    pub async fn extract_to_dir(self, dir: &cap_std::fs::Dir) -> Result<()> {
+        self.extract_into(OutDir::Dir(dir)).await
+    }
+
+    async fn extract_into(mut self, out_dir: OutDir<'_>) -> Result<()> {
+        let zip = self.reader;
+        let mut created = HashSet::new();
+
+        let mut total = 0;
+        for (index, entry) in zip.file().entries().iter().enumerate() {
+            if self.is_selected(index) && !entry.dir()? {
+                total += entry.uncompressed_size();
+            }
+        }
+        let mut progress = (0, total);
+
+        for (index, entry) in zip.file().entries().iter().enumerate() {
+            if !self.is_selected(index) {
+                continue;
+            }
+
+            loop {
+                let written = progress.0;
+                let err = match self.extract_entry(out_dir, index, entry, &mut created, &mut progress).await {
+                    Ok(()) => break,
+                    Err(err) => err.in_entry(entry),
+                };
+
+                progress.0 = written;
+                match self.error_action(index, &err) {
+                    ErrorAction::Retry => {
+                        self.discard_partial_file(out_dir).await;
+                        continue;
+                    }
+                    ErrorAction::Skip => {
+                        self.discard_partial_file(out_dir).await;
+                        break;
+                    }
+                    ErrorAction::Abort => return Err(err),
+                }
+            }
+        }
+
+        #[cfg(unix)]
+        for (path, mode) in self.dir_permissions.drain(..).rev() {
+            out_dir.set_permissions(&path, mode).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Extracts a single entry, tracking the cumulative number of bytes written and the total to be written.
+    async fn extract_entry(
+        &mut self,
+        out_dir: OutDir<'_>,
+        index: usize,
+        entry: &ZipEntry,
+        created: &mut HashSet<PathBuf>,
+        (written, total): &mut (u64, u64),
+    ) -> Result<()> {
+        if self.reject_unsafe_paths {
+            match entry.unsafe_reason() {
+                None | Some(UnsafeReason::Symlink) => (),
+                Some(reason) => return Err(ZipError::UnsafeEntryPath(reason)),
+            }
+        }
+        if is_symlink(entry) && !self.allow_symlinks {
+            return Err(ZipError::UnsafeEntryPath(UnsafeReason::Symlink));
+        }
+
+        let path = out_dir.base().join(sanitize_path(entry.filename().as_str()?));
+
+        if entry.dir()? {
+            self.create_dir(out_dir, &path, created).await?;
+
+            if let Some(hook) = self.on_metadata.as_mut() {
+                hook(entry, &path);
+            }
+
+            #[cfg(unix)]
+            if let Some(mode) = permission_bits(entry) {
+                self.dir_permissions.push((path, mode));
+            }
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            self.create_dir(out_dir, parent, created).await?;
+        }
+
+        if is_symlink(entry) {
+            self.create_symlink(out_dir, index, path).await?;
+            *written += entry.uncompressed_size();
+            return Ok(());
+        }
+
+        let mut reader = self.reader.reader_with_entry(index).await?;
+        let Some((file, path)) = self.create_file(out_dir, path).await? else {
+            *written += entry.uncompressed_size();
+            return Ok(());
+        };
+        let mut file = file.compat_write();
+
+        if let Some(hook) = self.on_metadata.as_mut() {
+            hook(entry, &path);
+        }
+
+        let mut buffer = vec![0; COPY_BUFFER_SIZE];
+        loop {
+            let read = reader.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+
+            file.write_all(&buffer[..read]).await?;
+            *written += read as u64;
+
+            if let Some(hook) = self.on_progress.as_mut() {
+                hook(*written, *total);
+            }
+        }
+        file.flush().await?;
+        reader.check_crc()?;
+        restore_metadata(file.into_inner(), entry).await?;
+        self.partial_file = None;
+
+        if let Some(hook) = self.on_entry_written.as_mut() {
+            hook(&path);
+        }
+
+        Ok(())
+    }
+
+    /// Extracts entries as per [`Extractor::extract_to()`], calling the provided hook with the cumulative number of
+    /// bytes written across all entries and the total number of bytes to be written.
+    ///
+    /// The total is the sum of the uncompressed sizes of all extracted file entries, as recorded in the central
+    /// directory. The hook is called after each chunk of data is written, so the final call reports `written == total`
+    /// for a well-formed ZIP file.
+    pub async fn extract_to_with_progress(
+        mut self,
+        out_dir: &Path,
+        on_progress: impl FnMut(u64, u64) + Send + 'a,
+    ) -> Result<()> {
+        self.on_progress = Some(Box::new(on_progress));
+        self.extract_to(out_dir).await
+    }
+
+    /// Verifies all entries (or those selected via [`Extractor::only()`]) before extracting any of them as per
+    /// [`Extractor::extract_to()`].
+    ///
+    /// Every entry is rejected with [`ZipError::UnsafeEntryPath`] if [`ZipEntry::unsafe_reason()`] flags it, and the
+    /// data of every file entry is decompressed and its CRC32 value checked. If any check fails, the error is returned
+    /// before anything is written to the output directory. As a result, each entry's data is read twice.
+    pub async fn verify_and_extract(mut self, out_dir: &Path) -> Result<()> {
+        let mut verified = HashSet::new();
+
+        for (index, entry) in self.reader.file().entries().iter().enumerate() {
+            if !self.is_selected(index) {
+                continue;
+            }
+
+            loop {
+                let err = match self.verify_entry(index, entry).await {
+                    Ok(()) => {
+                        verified.insert(index);
+                        break;
+                    }
+                    Err(err) => err.in_entry(entry),
+                };
+
+                match self.error_action(index, &err) {
+                    ErrorAction::Retry => continue,
+                    ErrorAction::Skip => break,
+                    ErrorAction::Abort => return Err(err),
+                }
+            }
+        }
+
+        self.only = Some(verified);
+        self.extract_to(out_dir).await
+    }
+
+    async fn verify_entry(&self, index: usize, entry: &ZipEntry) -> Result<()> {
+        if let Some(reason) = entry.unsafe_reason() {
+            return Err(ZipError::UnsafeEntryPath(reason));
+        }
+
+        if entry.dir()? {
+            return Ok(());
+        }
+
+        let mut reader = self.reader.reader_with_entry(index).await?;
+        futures_util::io::copy(&mut reader, &mut futures_util::io::sink()).await?;
+        reader.check_crc()
+    }
+
+    fn error_action(&mut self, index: usize, err: &ZipError) -> ErrorAction {
+        self.on_error.as_mut().map_or(ErrorAction::Abort, |hook| hook(index, err))
+    }
+
+    fn is_selected(&self, index: usize) -> bool {
+        self.only.as_ref().is_none_or(|only| only.contains(&index))
+    }
+
+    /// Creates the file for an entry as per the conflict policy, returning it alongside the path it was created at, or
+    /// None if the entry should be skipped.
+    async fn create_file(&mut self, out_dir: OutDir<'_>, path: PathBuf) -> Result<Option<(File, PathBuf)>> {
+        if self.on_conflict == ConflictPolicy::Overwrite {
+            let file = out_dir.create_file(&path, false).await?;
+            self.partial_file = Some(path.clone());
+            return Ok(Some((file, path)));
+        }
+
+        let mut candidate = path.clone();
+        for suffix in 1..=MAX_RENAME_SUFFIX {
+            match out_dir.create_file(&candidate, true).await {
+                Ok(file) => {
+                    self.partial_file = Some(candidate.clone());
+                    return Ok(Some((file, candidate)));
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => match self.on_conflict {
+                    ConflictPolicy::Skip => return Ok(None),
+                    ConflictPolicy::Rename => candidate = with_numeric_suffix(&path, suffix),
+                    _ => return Err(err.into()),
+                },
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Err(std::io::Error::from(ErrorKind::AlreadyExists).into())
+    }
+
+    /// Creates a symbolic link whose target is the data of the entry at the provided index.
+    #[cfg(unix)]
+    async fn create_symlink(&mut self, out_dir: OutDir<'_>, index: usize, path: PathBuf) -> Result<()> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut target = Vec::new();
+        self.reader.reader_with_entry(index).await?.read_to_end_checked(&mut target).await?;
+        let target = Path::new(std::ffi::OsStr::from_bytes(&target));
+
+        if self.on_conflict == ConflictPolicy::Overwrite {
+            match out_dir.remove_file(&path).await {
+                Err(err) if err.kind() != ErrorKind::NotFound => return Err(err.into()),
+                _ => (),
+            }
+        }
+
+        let mut candidate = path.clone();
+        for suffix in 1..=MAX_RENAME_SUFFIX {
+            match out_dir.symlink(target, &candidate).await {
+                Ok(()) => {
+                    if let Some(hook) = self.on_entry_written.as_mut() {
+                        hook(&candidate);
+                    }
+                    return Ok(());
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => match self.on_conflict {
+                    ConflictPolicy::Skip => return Ok(()),
+                    ConflictPolicy::Rename => candidate = with_numeric_suffix(&path, suffix),
+                    _ => return Err(err.into()),
+                },
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Err(std::io::Error::from(ErrorKind::AlreadyExists).into())
+    }
+
+    /// Creates a symbolic link whose target is the data of the entry at the provided index.
+    #[cfg(not(unix))]
+    async fn create_symlink(&mut self, _out_dir: OutDir<'_>, _index: usize, _path: PathBuf) -> Result<()> {
+        Err(ZipError::FeatureNotSupported("Extracting symbolic links on this platform"))
+    }
+
+    /// Removes any file partially written for an entry which is being skipped or retried.
+    async fn discard_partial_file(&mut self, out_dir: OutDir<'_>) {
+        if let Some(path) = self.partial_file.take() {
+            let _ = out_dir.remove_file(&path).await;
+        }
+    }
+
+    /// Creates a directory and any missing parents below the output directory, calling the hook for each directory
+    /// which hasn't been seen previously.
+    async fn create_dir(&mut self, out_dir: OutDir<'_>, path: &Path, created: &mut HashSet<PathBuf>) -> Result<()> {
+        let mut missing = Vec::new();
+        let base = out_dir.base();
+
+        for ancestor in path.ancestors() {
+            if ancestor == base || !ancestor.starts_with(base) || created.contains(ancestor) {
+                break;
+            }
+            missing.push(ancestor.to_owned());
+        }
+
+        for dir in missing.into_iter().rev() {
+            match out_dir.create_dir(&dir).await {
+                Ok(()) => (),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists && out_dir.is_dir(&dir) => (),
+                Err(err) => return Err(err.into()),
+            }
+
+            if let Some(hook) = self.on_entry_written.as_mut() {
+                hook(&dir);
+            }
+            created.insert(dir);
+        }
+
+        Ok(())
+    }
+}
+
+/// The directory which entries are extracted into, either as a path or as a `cap-std` capability.
+///
+/// The paths passed to each method are those joined onto [`OutDir::base()`].
+#[derive(Clone, Copy)]
+enum OutDir<'d> {
+    Path(&'d Path),
+    #[cfg(feature = "cap-std")]
+    Dir(&'d cap_std::fs::Dir),
+}
+
+impl OutDir<'_> {
+    /// Returns the path which entries' sanitised names are joined onto, which is empty for a capability.
+    fn base(&self) -> &Path {
+        match self {
+            OutDir::Path(path) => path,
+            #[cfg(feature = "cap-std")]
+            OutDir::Dir(_) => Path::new(""),
+        }
+    }
+
+    async fn create_dir(&self, path: &Path) -> std::io::Result<()> {
+        match self {
+            OutDir::Path(_) => tokio::fs::create_dir(path).await,
+            #[cfg(feature = "cap-std")]
+            OutDir::Dir(dir) => dir.create_dir(path),
+        }
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        match self {
+            OutDir::Path(_) => path.is_dir(),
+            #[cfg(feature = "cap-std")]
+            OutDir::Dir(dir) => dir.is_dir(path),
+        }
+    }
+
+    /// Creates a file for writing, truncating any existing file unless `create_new` is set.
+    async fn create_file(&self, path: &Path, create_new: bool) -> std::io::Result<File> {
+        match self {
+            OutDir::Path(_) if create_new => File::options().write(true).create_new(true).open(path).await,
+            OutDir::Path(_) => File::create(path).await,
+            #[cfg(feature = "cap-std")]
+            OutDir::Dir(dir) => {
+                let mut options = cap_std::fs::OpenOptions::new();
+                match create_new {
+                    true => options.write(true).create_new(true),
+                    false => options.write(true).create(true).truncate(true),
+                };
+                Ok(File::from_std(dir.open_with(path, &options)?.into_std()))
+            }
+        }
+    }
+
+    async fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        match self {
+            OutDir::Path(_) => tokio::fs::remove_file(path).await,
+            #[cfg(feature = "cap-std")]
+            OutDir::Dir(dir) => dir.remove_file(path),
+        }
+    }
+
+    #[cfg(unix)]
+    async fn symlink(&self, target: &Path, path: &Path) -> std::io::Result<()> {
+        match self {
+            OutDir::Path(_) => tokio::fs::symlink(target, path).await,
+            #[cfg(feature = "cap-std")]
+            OutDir::Dir(dir) => dir.symlink(target, path),
+        }
+    }
+
+    #[cfg(unix)]
+    async fn set_permissions(&self, path: &Path, mode: u32) -> std::io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let permissions = std::fs::Permissions::from_mode(mode);
+        match self {
+            OutDir::Path(_) => tokio::fs::set_permissions(path, permissions).await,
+            #[cfg(feature = "cap-std")]
+            OutDir::Dir(dir) => dir.set_permissions(path, cap_std::fs::Permissions::from_std(permissions)),
+        }
+    }
+}
+
+/// Restores an extracted file's modification time and (on Unix) its permission bits from its entry, where present.
+async fn restore_metadata(file: File, entry: &ZipEntry) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = permission_bits(entry) {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(mode)).await?;
+    }
+
+    if let Some(time) = modification_time(entry) {
+        file.into_std().await.set_modified(time)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the permission bits of an entry's Unix mode, if it carries any.
+///
+/// The setuid, setgid, and sticky bits are dropped, as is a mode without any permission bits (which would otherwise
+/// leave the extracted file inaccessible).
+#[cfg(unix)]
+fn permission_bits(entry: &ZipEntry) -> Option<u32> {
+    entry.unix_permissions().map(|mode| mode & 0o777).filter(|mode| *mode != 0)
+}
+
+/// Returns an entry's modification time, preferring the higher-resolution times held within its extra fields.
+fn modification_time(entry: &ZipEntry) -> Option<SystemTime> {
+    let extended =
+        entry.extended_timestamp().and_then(|times| times.modified()).and_then(|time| unix_time(time.into()));
+    let ntfs = || entry.ntfs_times().and_then(|times| times.modified_system_time());
+
+    extended.or_else(ntfs).or_else(|| entry.last_modification_date().as_system_time())
+}
+
+/// Returns the path with a numeric suffix inserted before its file's last extension (eg. `file (1).txt`).
+fn with_numeric_suffix(path: &Path, suffix: u32) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem} ({suffix}).{}", extension.to_string_lossy()),
+        None => format!("{stem} ({suffix})"),
+    };
+
+    path.with_file_name(name)
+}
+
+/// Returns a relative path without redundant separators, `.`, `..`, or drive components.
+///
+/// Components containing a colon are removed as Windows would otherwise treat them as a drive prefix (eg. `C:`),
+/// replacing the output directory when joined. UNC prefixes (eg. `\\server\share`) reduce to relative components.
+fn sanitize_path(path: &str) -> PathBuf {
+    path.split(['/', '\\'])
+        .filter(|component| !matches!(*component, "" | "." | "..") && !component.contains(':'))
+        .collect()
+}
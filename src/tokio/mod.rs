@@ -24,6 +24,8 @@ use tokio;
 #[cfg(doc)]
 use tokio_util;
 
+#[cfg(feature = "tokio-fs")]
+pub mod extract;
 pub mod read;
 
 pub mod write {
@@ -33,6 +35,14 @@ pub mod write {
     use crate::base;
     use tokio_util::compat::Compat;
 
+    #[cfg(feature = "tokio-fs")]
+    mod directory;
+    #[cfg(feature = "tokio-fs")]
+    mod split;
+
+    #[cfg(feature = "tokio-fs")]
+    pub use split::{VolumeWriter, ZipSplitWriter};
+
     /// A [`tokio`]-specific type alias for [`base::write::ZipFileWriter`];
     pub type ZipFileWriter<W> = crate::base::write::ZipFileWriter<Compat<W>>;
 
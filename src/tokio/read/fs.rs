@@ -66,24 +66,54 @@
 //!     Ok(data)
 //! }
 //! ```
+//!
+//! ### List then Extract Example
+//! The central directory is only parsed once when the reader is constructed, so the same reader can be used to list
+//! the entries and later extract a chosen subset of them without reopening or re-parsing the file.
+//! ```no_run
+//! # use async_zip::tokio::read::fs::ZipFileReader;
+//! # use async_zip::error::Result;
+//! # use std::path::Path;
+//! #
+//! async fn run() -> Result<()> {
+//!     let reader = ZipFileReader::new("./foo.zip").await?;
+//!
+//!     for (index, entry) in reader.file().entries().iter().enumerate() {
+//!         println!("{index}: {}", entry.filename().as_str()?);
+//!     }
+//!
+//!     // Extract the entries picked by the user.
+//!     reader.extractor().only([0, 2]).extract_to(Path::new("./out")).await
+//! }
+//! ```
 
 #[cfg(doc)]
 use crate::base::read::seek;
 
 use crate::base::read::io::entry::{WithEntry, WithoutEntry, ZipEntryReader};
+use crate::base::read::io::owned::OwnedReader;
+use crate::base::read::{mem, ReadOptions, VerifyReport};
+use crate::entry::ZipEntry;
 use crate::error::{Result, ZipError};
 use crate::file::ZipFile;
+use crate::tokio::extract::Extractor;
 
+use std::borrow::Cow;
+use std::io::SeekFrom;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use futures_util::io::BufReader;
+use futures_util::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader, Take};
+use futures_util::Stream;
 use tokio::fs::File;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_util::compat::{Compat, TokioAsyncReadCompatExt};
 
 struct Inner {
     path: PathBuf,
     file: ZipFile,
+    options: ReadOptions,
 }
 
 /// A concurrent ZIP reader which acts over a file system path.
@@ -98,7 +128,29 @@ impl ZipFileReader {
     where
         P: AsRef<Path>,
     {
-        let file = crate::base::read::file(File::open(&path).await?.compat()).await?;
+        Self::with_options(path, ReadOptions::default()).await
+    }
+
+    /// Constructs a new ZIP reader from a file system path with the provided options.
+    pub async fn with_options<P>(path: P, options: ReadOptions) -> Result<ZipFileReader>
+    where
+        P: AsRef<Path>,
+    {
+        let file = crate::base::read::file(File::open(&path).await?.compat(), &options).await?;
+        Ok(ZipFileReader { inner: Arc::new(Inner { path: path.as_ref().to_owned(), file, options }) })
+    }
+
+    /// Constructs a new ZIP reader from a file system path whose central directory is missing or corrupt.
+    ///
+    /// Rather than parsing the central directory, the entries are reconstructed by walking the local file headers from
+    /// the start of the file, stopping at the first record which isn't one. As such, any information only held within
+    /// the central directory (eg. entry comments and file attributes) is unavailable, and the ZIP file's comment is
+    /// empty. The sizes and CRC32 value of an entry written with a data descriptor are taken from that descriptor.
+    pub async fn repair<P>(path: P) -> Result<ZipFileReader>
+    where
+        P: AsRef<Path>,
+    {
+        let file = crate::base::read::repair::repair(File::open(&path).await?.compat()).await?;
         Ok(ZipFileReader::from_raw_parts(path, file))
     }
 
@@ -109,7 +161,9 @@ impl ZipFileReader {
     where
         P: AsRef<Path>,
     {
-        ZipFileReader { inner: Arc::new(Inner { path: path.as_ref().to_owned(), file }) }
+        ZipFileReader {
+            inner: Arc::new(Inner { path: path.as_ref().to_owned(), file, options: ReadOptions::default() }),
+        }
     }
 
     /// Returns this ZIP file's information.
@@ -117,11 +171,45 @@ impl ZipFileReader {
         &self.inner.file
     }
 
+    /// Returns an iterator over the decoded names of this ZIP file's entries, as per [`ZipFile::entry_names()`].
+    pub fn entry_names(&self) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+        self.file().entry_names()
+    }
+
+    /// Returns whether or not any bytes follow the end of this ZIP file's comment, as per
+    /// [`ZipFile::has_trailing_data()`].
+    pub fn has_trailing_data(&self) -> bool {
+        self.file().has_trailing_data()
+    }
+
     /// Returns the file system path provided to the reader during construction.
     pub fn path(&self) -> &Path {
         &self.inner.path
     }
 
+    /// Returns the raw bytes of this ZIP file's central directory.
+    pub async fn central_directory_bytes(&self) -> Result<Vec<u8>> {
+        let fs_file = File::open(&self.inner.path).await?.compat();
+        crate::base::read::central_directory_bytes(fs_file, &self.inner.file).await
+    }
+
+    /// Returns each entry alongside the absolute range of its compressed data within the ZIP file.
+    ///
+    /// Every local file header is read to account for any differences in length from the central directory.
+    pub async fn entries_with_ranges(&self) -> Result<Vec<(ZipEntry, Range<u64>)>> {
+        let fs_file = File::open(&self.inner.path).await?.compat();
+        crate::base::read::entries_with_ranges(fs_file, &self.inner.file).await
+    }
+
+    /// Reads the whole file into memory, returning a reader over those bytes.
+    ///
+    /// The returned reader shares this reader's already-parsed ZIP file information and options, so the central
+    /// directory isn't parsed again. As such, the file shouldn't have been modified since this reader was constructed.
+    pub async fn load_into_memory(&self) -> Result<mem::ZipFileReader> {
+        let data = tokio::fs::read(&self.inner.path).await?;
+        Ok(mem::ZipFileReader::from_raw_parts_with_options(data, self.inner.file.clone(), self.inner.options.clone()))
+    }
+
     /// Returns a new entry reader if the provided index is valid.
     pub async fn reader_without_entry(
         &self,
@@ -132,26 +220,230 @@ impl ZipFileReader {
 
         stored_entry.seek_to_data_offset(&mut fs_file).await?;
 
-        Ok(ZipEntryReader::new_with_owned(
-            fs_file,
-            stored_entry.entry.compression(),
-            stored_entry.entry.compressed_size(),
-        ))
+        let reader = ZipEntryReader::new_with_owned(fs_file, stored_entry)?;
+        let reader = self.with_options_and_spill(reader, stored_entry).await?;
+
+        Ok(reader.expect_crc(stored_entry.entry.crc32()))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, without verifying the entry's CRC32 value at EOF.
+    ///
+    /// See [`ZipEntryReader::unchecked()`].
+    pub async fn reader_without_entry_unchecked(
+        &self,
+        index: usize,
+    ) -> Result<ZipEntryReader<'static, Compat<File>, WithoutEntry>> {
+        Ok(self.reader_without_entry(index).await?.unchecked())
     }
 
     /// Returns a new entry reader if the provided index is valid.
     pub async fn reader_with_entry(&self, index: usize) -> Result<ZipEntryReader<'_, Compat<File>, WithEntry<'_>>> {
+        if let Some(password) = self.inner.options.password.as_deref() {
+            return self.reader_with_entry_and_password(index, password).await;
+        }
+
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut fs_file = BufReader::new(File::open(&self.inner.path).await?.compat());
+
+        stored_entry.seek_to_data_offset(&mut fs_file).await?;
+
+        let reader = ZipEntryReader::new_with_owned(fs_file, stored_entry)?;
+        let reader = self.with_options_and_spill(reader, stored_entry).await?;
+
+        Ok(reader.into_with_entry(stored_entry))
+    }
+
+    /// Returns a new entry reader if the provided index is valid, decrypting the entry's data with the provided password.
+    ///
+    /// Both traditional PKWARE (ZipCrypto) and WinZip AES encryption are supported, the latter requiring the `aes`
+    /// feature. The password is checked against the entry's encryption header before the reader is returned, erroring
+    /// with [`ZipError::WrongPassword`] if it's incorrect. The authentication code of AES-encrypted entries is then
+    /// verified once EOF is reached. Entries which aren't encrypted are read as normal.
+    pub async fn reader_with_entry_and_password(
+        &self,
+        index: usize,
+        password: &[u8],
+    ) -> Result<ZipEntryReader<'_, Compat<File>, WithEntry<'_>>> {
         let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
         let mut fs_file = BufReader::new(File::open(&self.inner.path).await?.compat());
 
         stored_entry.seek_to_data_offset(&mut fs_file).await?;
 
-        let reader = ZipEntryReader::new_with_owned(
-            fs_file,
-            stored_entry.entry.compression(),
-            stored_entry.entry.compressed_size(),
-        );
+        let reader = ZipEntryReader::new_with_password(OwnedReader::Owned(fs_file), stored_entry, password).await?;
+        let reader = self.with_options_and_spill(reader, stored_entry).await?;
 
         Ok(reader.into_with_entry(stored_entry))
     }
+
+    /// Applies this reader's options to an entry reader, decompressing the entry into a temporary file if it exceeds
+    /// the threshold set via [`ReadOptions::spill_threshold()`].
+    async fn with_options_and_spill(
+        &self,
+        reader: ZipEntryReader<'static, Compat<File>, WithoutEntry>,
+        entry: &ZipEntry,
+    ) -> Result<ZipEntryReader<'static, Compat<File>, WithoutEntry>> {
+        let options = &self.inner.options;
+        let mut reader = reader.with_options(options);
+        if options.spill_threshold.is_none_or(|threshold| entry.uncompressed_size() <= threshold) {
+            return Ok(reader);
+        }
+
+        reader = reader.expect_crc(entry.crc32());
+        let mut temp_file = File::from_std(tempfile::tempfile()?).compat();
+        let size = futures_util::io::copy(&mut reader, &mut temp_file)
+            .await
+            .map_err(|err| ZipError::from(err).in_entry(entry))?;
+        temp_file.flush().await?;
+        temp_file.seek(SeekFrom::Start(0)).await?;
+
+        Ok(ZipEntryReader::new_decompressed(BufReader::new(temp_file), size).with_options(options))
+    }
+
+    /// Returns a reader over the compressed (and possibly encrypted) data of the entry at the provided index.
+    ///
+    /// The data is returned exactly as stored, so it can be copied into another ZIP file without being decompressed
+    /// and recompressed via [`ZipFileWriter::copy_entry_raw()`](crate::base::write::ZipFileWriter::copy_entry_raw).
+    pub async fn raw_reader_without_entry(&self, index: usize) -> Result<Take<BufReader<Compat<File>>>> {
+        let stored_entry = self.inner.file.entries.get(index).ok_or(ZipError::EntryIndexOutOfBounds)?;
+        let mut fs_file = BufReader::new(File::open(&self.inner.path).await?.compat());
+
+        stored_entry.seek_to_data_offset(&mut fs_file).await?;
+
+        Ok(fs_file.take(stored_entry.entry.compressed_size()))
+    }
+
+    /// Reads the entry at the provided index into a string, as per [`ZipEntryReader::read_to_string_checked()`].
+    ///
+    /// At most `max_bytes` bytes are decompressed, with [`ZipError::EntryTooLarge`] being returned if the entry holds
+    /// any more. Data which isn't valid UTF-8 is rejected with [`ZipError::InvalidUtf8`].
+    pub async fn read_entry_to_string(&self, index: usize, max_bytes: u64) -> Result<String> {
+        self.reader_with_entry(index).await?.read_to_string_capped(max_bytes).await
+    }
+
+    /// Verifies that every entry decompresses cleanly and matches its recorded CRC32 value and uncompressed size.
+    ///
+    /// Rather than returning the first error encountered, each entry's outcome is recorded within the returned
+    /// [`VerifyReport`], akin to `unzip -t`. The decompressed data is discarded. Encrypted entries are skipped unless a
+    /// password was provided via [`ReadOptions::password()`].
+    pub async fn verify(&self) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+
+        for (index, stored_entry) in self.inner.file.entries.iter().enumerate() {
+            if report.skip(index, &stored_entry.entry, &self.inner.options) {
+                continue;
+            }
+
+            let result = match self.reader_with_entry(index).await {
+                Ok(mut reader) => reader.verify_checked().await,
+                Err(err) => Err(err),
+            };
+            report.record(index, result);
+        }
+
+        Ok(report)
+    }
+
+    /// Returns a new entry reader for the entry with the provided name, as located by [`ZipFile::entry_index()`].
+    ///
+    /// [`ZipError::EntryNotFound`] is returned if no such entry exists.
+    pub async fn reader_with_entry_by_name(
+        &self,
+        name: &str,
+    ) -> Result<ZipEntryReader<'_, Compat<File>, WithEntry<'_>>> {
+        let index = self.inner.file.entry_index(name).ok_or_else(|| ZipError::EntryNotFound(name.to_owned()))?;
+        self.reader_with_entry(index).await
+    }
+
+    /// Returns a stream of handles to each entry's reader, of which at most `concurrency` are alive at once.
+    ///
+    /// The next handle is only yielded once fewer than `concurrency` previously yielded handles remain, so consuming
+    /// the stream from a pool of workers applies backpressure rather than opening every entry up front. As each
+    /// handle owns its reader, handles may be moved into spawned tasks. A concurrency of zero is treated as one.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use async_zip::tokio::read::fs::ZipFileReader;
+    /// # use async_zip::error::Result;
+    /// # use futures_util::StreamExt;
+    /// #
+    /// # async fn run(reader: ZipFileReader) -> Result<()> {
+    /// let mut handles = Box::pin(reader.entry_handles(4));
+    ///
+    /// while let Some(handle) = handles.next().await {
+    ///     let mut handle = handle?;
+    ///     tokio::spawn(async move {
+    ///         let mut data = Vec::new();
+    ///         handle.reader_mut().read_to_end_checked(&mut data).await
+    ///     });
+    /// }
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn entry_handles(&self, concurrency: usize) -> impl Stream<Item = Result<EntryHandle>> + Send + 'static {
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+
+        futures_util::stream::unfold((self.clone(), semaphore, 0), |(reader, semaphore, index)| async move {
+            let entry = reader.file().entries().get(index)?.entry.clone();
+            let permit = semaphore.clone().acquire_owned().await.expect("the semaphore is never closed");
+
+            let handle = reader
+                .reader_without_entry(index)
+                .await
+                .map(|entry_reader| EntryHandle { reader: entry_reader.into_with_entry_owned(entry), _permit: permit });
+            Some((handle, (reader, semaphore, index + 1)))
+        })
+    }
+
+    /// Returns a new extractor which writes this ZIP file's entries to the file system.
+    pub fn extractor(&self) -> Extractor<'_> {
+        Extractor::new(self)
+    }
+
+    /// Extracts all entries into the provided output directory, rejecting any with unsafe names.
+    ///
+    /// This is equivalent to extracting via [`ZipFileReader::extractor()`], which sets
+    /// [`reject_unsafe_paths()`](Extractor::reject_unsafe_paths) by default, so an entry whose name is an absolute
+    /// path or contains a `..` component fails with [`ZipError::UnsafeEntryPath`] rather than escaping the output
+    /// directory. Symbolic links are refused likewise. Entries prior to a rejected one will have been extracted.
+    pub async fn extract_to(&self, out_dir: &Path) -> Result<()> {
+        self.extractor().extract_to(out_dir).await
+    }
+
+    /// Copies the data of the entry at the provided index into a synchronous writer, verifying its CRC32 value.
+    ///
+    /// Returns the number of bytes copied.
+    ///
+    /// # Runtime
+    /// This function drives the asynchronous read to completion on the current [`tokio`] runtime via
+    /// [`Handle::block_on()`](tokio::runtime::Handle::block_on), and therefore must be called from within the context
+    /// of a runtime but outside of an asynchronous context (eg. from within [`tokio::task::spawn_blocking()`]).
+    /// Calling it from elsewhere will panic.
+    pub fn blocking_copy_entry_to<W: std::io::Write>(&self, index: usize, mut writer: W) -> Result<u64> {
+        tokio::runtime::Handle::current().block_on(async {
+            let mut reader = self.reader_with_entry(index).await?;
+            reader.copy_to_sync_checked(&mut writer).await
+        })
+    }
+}
+
+/// A handle to an entry and its reader, yielded by [`ZipFileReader::entry_handles()`].
+///
+/// The handle counts towards the stream's concurrency limit until it's dropped. As the reader owns its entry, the
+/// entry's CRC32 value is verified once the reader reaches EOF, and the checked read functions (eg.
+/// [`ZipEntryReader::read_to_end_checked()`]) are available.
+pub struct EntryHandle {
+    reader: ZipEntryReader<'static, Compat<File>, WithEntry<'static>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl EntryHandle {
+    /// Returns the entry which this handle reads.
+    pub fn entry(&self) -> &ZipEntry {
+        self.reader.entry()
+    }
+
+    /// Returns a mutable reference to the entry's reader.
+    pub fn reader_mut(&mut self) -> &mut ZipEntryReader<'static, Compat<File>, WithEntry<'static>> {
+        &mut self.reader
+    }
 }
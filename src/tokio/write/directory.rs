@@ -0,0 +1,134 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::base::write::ZipFileWriter;
+use crate::entry::safety::UNIX_SYMLINK_MODE;
+use crate::error::{Result, ZipError};
+use crate::{Compression, ZipDateTime, ZipEntryBuilder};
+
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use futures_util::io::AsyncWrite;
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+impl<W: AsyncWrite + Unpin> ZipFileWriter<W> {
+    /// Adds the contents of a directory on the file system, recursing into any subdirectories.
+    ///
+    /// Each file is streamed from the file system as per [`ZipFileWriter::write_entry_from_reader()`] using the provided
+    /// compression method, so only small files are held in memory whole. Entries are named by their paths relative to
+    /// the root with components separated by forward slashes. Directories are only written as entries of their own when they're
+    /// empty, as the others are implied by the names of the entries within them. Symbolic links are stored as symbolic
+    /// link entries (whose data is the link's target) rather than followed, so cycles can't occur.
+    ///
+    /// Each entry's last modification time is taken from the file system, and on Unix, so is its mode. Entries are
+    /// added in order of their names within each directory, so the resulting ZIP file is reproducible. A path which
+    /// isn't valid UTF-8 is rejected with [`ZipError::StringNotUtf8`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use async_zip::{tokio::write::ZipFileWriter, Compression};
+    /// # use async_zip::error::Result;
+    /// # use std::path::Path;
+    /// #
+    /// async fn run() -> Result<()> {
+    ///     let file = tokio::fs::File::create("./foo.zip").await?;
+    ///     let mut writer = ZipFileWriter::with_tokio(file);
+    ///
+    ///     writer.add_directory(Path::new("./foo"), Compression::Deflate).await?;
+    ///     writer.close().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn add_directory(&mut self, root: &Path, compression: Compression) -> Result<()> {
+        let mut pending = vec![(root.to_path_buf(), String::new())];
+
+        while let Some((dir, prefix)) = pending.pop() {
+            let mut children = Vec::new();
+            let mut read_dir = tokio::fs::read_dir(&dir).await?;
+            while let Some(child) = read_dir.next_entry().await? {
+                let name = child.file_name().into_string().map_err(|_| ZipError::StringNotUtf8)?;
+                children.push((name, child.path()));
+            }
+            children.sort();
+
+            if children.is_empty() && !prefix.is_empty() {
+                let metadata = tokio::fs::metadata(&dir).await?;
+                let builder = with_metadata(ZipEntryBuilder::new(prefix.into(), Compression::Stored), &metadata);
+                self.write_entry_whole(builder, &[]).await?;
+                continue;
+            }
+
+            let mut subdirs = Vec::new();
+            for (name, path) in children {
+                let metadata = tokio::fs::symlink_metadata(&path).await?;
+                let name = format!("{prefix}{name}");
+
+                if metadata.is_dir() {
+                    subdirs.push((path, name + "/"));
+                } else if metadata.is_symlink() {
+                    let target = link_target(&path).await?;
+                    let builder = with_metadata(ZipEntryBuilder::new(name.into(), Compression::Stored), &metadata);
+                    self.write_entry_whole(with_symlink_mode(builder), &target).await?;
+                } else {
+                    let file = tokio::fs::File::open(&path).await?.compat();
+                    let builder = with_metadata(ZipEntryBuilder::new(name.into(), compression), &metadata);
+                    self.write_entry_from_reader(builder, file).await?;
+                }
+            }
+
+            // Subdirectories are popped in reverse, so they're reversed here to be added in order of their names.
+            pending.extend(subdirs.into_iter().rev());
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets an entry's last modification time and (on Unix) its mode from its file system metadata.
+fn with_metadata(mut builder: ZipEntryBuilder, metadata: &Metadata) -> ZipEntryBuilder {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder = builder.unix_permissions(metadata.permissions().mode());
+    }
+
+    if let Ok(modified) = metadata.modified() {
+        if let Some(date) = ZipDateTime::from_system_time(modified) {
+            builder = builder.last_modification_date(date);
+        }
+
+        let seconds = modified.duration_since(UNIX_EPOCH).ok().and_then(|duration| duration.as_secs().try_into().ok());
+        if let Some(seconds) = seconds {
+            builder = builder.last_modification_unix_time(seconds);
+        }
+    }
+
+    builder
+}
+
+/// Marks an entry as a symbolic link, where its mode wasn't already taken from the file system.
+fn with_symlink_mode(builder: ZipEntryBuilder) -> ZipEntryBuilder {
+    if cfg!(unix) {
+        builder
+    } else {
+        builder.unix_permissions(UNIX_SYMLINK_MODE | 0o777)
+    }
+}
+
+/// Returns the raw target of a symbolic link.
+async fn link_target(path: &Path) -> Result<Vec<u8>> {
+    let target = tokio::fs::read_link(path).await?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Ok(target.into_os_string().into_vec())
+    }
+
+    #[cfg(not(unix))]
+    {
+        Ok(target.into_os_string().into_string().map_err(|_| ZipError::StringNotUtf8)?.into_bytes())
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+
+use crate::base::write::io::volume::Volumes;
+use crate::base::write::ZipFileWriter;
+use crate::error::{Result, ZipError};
+use crate::spec::consts::{MIN_VOLUME_SIZE, NON_ZIP64_MAX_SIZE, SPLIT_SIGNATURE};
+
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::future::BoxFuture;
+use futures_util::io::{AsyncWrite, AsyncWriteExt};
+use tokio::fs::File;
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+/// A ZIP file writer which splits the ZIP file across multiple volumes on the file system, each under a size cap.
+///
+/// The volumes are named after the provided prefix, in the form of `{prefix}.z01`, `{prefix}.z02`, ..., with the final
+/// volume named `{prefix}.zip`, and can be read back via [`ZipFileReader::new_split()`]. Entries are written via the
+/// [`ZipFileWriter`] this dereferences to.
+///
+/// An entry's data may be split across volumes, but no header record is; when one wouldn't fit within the remainder
+/// of a volume, that volume is ended early and the record is written at the start of the next one. As such, a volume
+/// may be smaller than the size cap.
+///
+/// # Example
+/// ```no_run
+/// # use async_zip::tokio::write::ZipSplitWriter;
+/// # use async_zip::error::Result;
+/// #
+/// async fn run(data: &[u8]) -> Result<()> {
+///     let mut writer = ZipSplitWriter::new("./foo", 64 * 1024 * 1024).await?;
+///
+///     writer.write_entry("bar.txt", data).await?;
+///     let volumes = writer.close().await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`ZipFileReader::new_split()`]: crate::base::read::seek::ZipFileReader::new_split
+pub struct ZipSplitWriter {
+    writer: ZipFileWriter<VolumeWriter>,
+}
+
+impl ZipSplitWriter {
+    /// Constructs a new split ZIP file writer, creating its first volume.
+    ///
+    /// The maximum volume size must be at least 64KiB and less than 4GiB, else [`ZipError::InvalidVolumeSize`] is
+    /// returned.
+    pub async fn new(prefix: impl AsRef<Path>, max_volume_size: u64) -> Result<Self> {
+        if !(MIN_VOLUME_SIZE..=NON_ZIP64_MAX_SIZE.into()).contains(&max_volume_size) {
+            return Err(ZipError::InvalidVolumeSize(max_volume_size));
+        }
+
+        let prefix = prefix.as_ref().to_path_buf();
+        let path = volume_path(&prefix, 1);
+        let file = File::create(&path).await?;
+
+        let volumes = VolumeWriter {
+            prefix,
+            max_volume_size,
+            current: file.compat_write(),
+            paths: vec![path],
+            starts: vec![0],
+            written: 0,
+            pending: false,
+            opening: None,
+        };
+
+        let mut writer = ZipFileWriter::new(volumes);
+        writer.volumes = Some(|volumes| volumes);
+        writer.writer.write_all(&SPLIT_SIGNATURE.to_le_bytes()).await?;
+
+        Ok(Self { writer })
+    }
+
+    /// Consumes this writer and completes all closing tasks as per [`ZipFileWriter::close()`], returning the paths of
+    /// the volumes in order.
+    pub async fn close(self) -> Result<Vec<PathBuf>> {
+        let mut volumes = self.writer.close().await?;
+        volumes.close().await?;
+
+        let mut paths = volumes.paths;
+        let last = paths.pop().expect("at least one volume");
+        let mut path = volumes.prefix.into_os_string();
+        path.push(".zip");
+        let path = PathBuf::from(path);
+
+        tokio::fs::rename(&last, &path).await?;
+        paths.push(path);
+
+        Ok(paths)
+    }
+}
+
+impl Deref for ZipSplitWriter {
+    type Target = ZipFileWriter<VolumeWriter>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.writer
+    }
+}
+
+impl DerefMut for ZipSplitWriter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.writer
+    }
+}
+
+/// The writer of a [`ZipSplitWriter`]'s volumes, which starts a new volume when the current one is full.
+pub struct VolumeWriter {
+    prefix: PathBuf,
+    max_volume_size: u64,
+    current: Compat<File>,
+    /// The path of each volume created so far, the last of which is the current volume.
+    paths: Vec<PathBuf>,
+    /// The offset at which each volume created so far starts.
+    starts: Vec<u64>,
+    written: u64,
+    /// Whether the next write starts a new volume.
+    pending: bool,
+    opening: Option<BoxFuture<'static, std::io::Result<(PathBuf, File)>>>,
+}
+
+impl VolumeWriter {
+    fn volume_length(&self) -> u64 {
+        self.written - self.starts[self.starts.len() - 1]
+    }
+
+    /// Flushes the current volume and creates the next one in its place.
+    fn poll_next_volume(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if self.opening.is_none() {
+            ready!(Pin::new(&mut self.current).poll_flush(cx))?;
+
+            let path = volume_path(&self.prefix, self.paths.len() + 1);
+            self.opening = Some(Box::pin(async move {
+                let file = File::create(&path).await?;
+                Ok((path, file))
+            }));
+        }
+
+        let opened = ready!(self.opening.as_mut().expect("volume being opened").as_mut().poll(cx));
+        self.opening = None;
+        let (path, file) = opened?;
+
+        self.current = file.compat_write();
+        self.paths.push(path);
+        self.starts.push(self.written);
+        self.pending = false;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Volumes for VolumeWriter {
+    fn reserve(&mut self, length: u64) -> (u32, u64) {
+        let volume_length = self.volume_length();
+        if volume_length > 0 && volume_length + length > self.max_volume_size {
+            self.pending = true;
+        }
+
+        let disk = (self.starts.len() - 1) as u32;
+        if self.pending {
+            (disk + 1, 0)
+        } else {
+            (disk, volume_length)
+        }
+    }
+
+    fn locate(&self, offset: u64) -> (u32, u64) {
+        let disk = self.starts.partition_point(|start| *start <= offset).saturating_sub(1);
+        (disk as u32, offset - self.starts[disk])
+    }
+}
+
+impl AsyncWrite for VolumeWriter {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        if this.pending || this.volume_length() >= this.max_volume_size {
+            ready!(this.poll_next_volume(cx))?;
+        }
+
+        let remaining = this.max_volume_size - this.volume_length();
+        let length = buf.len().min(remaining.try_into().unwrap_or(usize::MAX));
+        let written = ready!(Pin::new(&mut this.current).poll_write(cx, &buf[..length]))?;
+        this.written += written as u64;
+
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().current).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().current).poll_close(cx)
+    }
+}
+
+/// Returns the path of the volume with the provided number (starting at one), prior to the final volume's renaming.
+fn volume_path(prefix: &Path, number: usize) -> PathBuf {
+    let mut path = prefix.as_os_str().to_owned();
+    path.push(format!(".z{number:02}"));
+    PathBuf::from(path)
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2023 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-async-zip/blob/main/LICENSE)
+#![allow(dead_code)]
 
 use async_zip::base::read::mem;
 use async_zip::base::read::seek;
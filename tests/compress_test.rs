@@ -13,6 +13,20 @@ async fn zip_zstd_in_out() {
     common::check_decompress_mem(zip_data).await
 }
 
+#[cfg(feature = "bzip2")]
+#[tokio::test]
+async fn zip_bzip2_in_out() {
+    let zip_data = common::compress_to_mem(Compression::Bz).await;
+    common::check_decompress_mem(zip_data).await
+}
+
+#[cfg(feature = "lzma")]
+#[tokio::test]
+async fn zip_lzma_in_out() {
+    let zip_data = common::compress_to_mem(Compression::Lzma).await;
+    common::check_decompress_mem(zip_data).await
+}
+
 #[cfg(feature = "deflate")]
 #[tokio::test]
 async fn zip_decompress_in_out() {
@@ -79,3 +93,1139 @@ async fn zip_utf8_extra_in_out_whole() {
         assert_eq!(zip_entries[0].filename().alternative(), Some(b"\xD6\xD0\xCe\xC4.txt".as_ref()));
     }
 }
+
+#[tokio::test]
+async fn zip_sorted_central_directory() {
+    let mut zip_bytes = Vec::new();
+    let mut writer = async_zip::base::write::ZipFileWriter::new(&mut zip_bytes).sort_central_directory(true).unwrap();
+
+    for name in ["charlie.txt", "alpha.txt", "bravo.txt"] {
+        let opts = ZipEntryBuilder::new(name.to_string().into(), Compression::Stored);
+        writer.write_entry_whole(opts, name.as_bytes()).await.unwrap();
+    }
+    writer.close().await.unwrap();
+
+    let zip = async_zip::base::read::mem::ZipFileReader::new(zip_bytes).await.unwrap();
+    let names: Vec<_> = zip.file().entries().iter().map(|entry| entry.filename().as_str().unwrap()).collect();
+    assert_eq!(names, ["alpha.txt", "bravo.txt", "charlie.txt"]);
+
+    // Data was written in the original order, so the header offsets should no longer be ascending.
+    let offsets: Vec<_> = zip.file().entries().iter().map(|entry| entry.header_offset()).collect();
+    assert!(offsets[2] < offsets[0]);
+
+    for (index, name) in names.iter().enumerate() {
+        let mut reader = zip.reader_with_entry(index).await.unwrap();
+        let mut data = String::new();
+        reader.read_to_string_checked(&mut data).await.unwrap();
+        assert_eq!(&data, name);
+    }
+}
+
+#[tokio::test]
+async fn zip_large_comment_in_out() {
+    use async_zip::base::read::{mem, seek};
+    use async_zip::base::write::ZipFileWriter;
+
+    // The EOCDR is located by scanning backwards from the end of the file, so the largest possible comment spans many
+    // of the locator's buffers.
+    for length in [4 * 1024, u16::MAX as usize] {
+        let comment: String = (0..length).map(|index| char::from(b'a' + (index % 26) as u8)).collect();
+
+        let mut writer = ZipFileWriter::new(Vec::new());
+        writer.write_entry_whole(ZipEntryBuilder::new("entry.txt".into(), Compression::Stored), b"data").await.unwrap();
+        writer.comment(comment.clone());
+        let data = writer.close().await.unwrap();
+
+        let zip = seek::ZipFileReader::new(futures_util::io::Cursor::new(&data)).await.unwrap();
+        assert_eq!(zip.file().comment().as_str().unwrap(), comment);
+
+        let zip = mem::ZipFileReader::new(data).await.unwrap();
+        assert_eq!(zip.file().comment().as_str().unwrap(), comment);
+        assert_eq!(zip.file().entries().len(), 1);
+    }
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.comment("a".repeat(u16::MAX as usize + 1));
+    assert!(matches!(writer.close().await, Err(async_zip::error::ZipError::CommentTooLarge)));
+}
+
+#[tokio::test]
+async fn zip_producer_in_out() {
+    let mut zip_bytes = Vec::new();
+    let mut writer = async_zip::base::write::ZipFileWriter::new(&mut zip_bytes);
+    writer.comment(String::from("Some comment."));
+    writer.producer(String::from("my-tool 1.2.3"));
+    writer.close().await.unwrap();
+
+    let zip = async_zip::base::read::mem::ZipFileReader::new(zip_bytes).await.unwrap();
+    assert_eq!(zip.file().producer(), Some("my-tool 1.2.3"));
+    assert_eq!(zip.file().comment().as_str().unwrap(), "Some comment.\nProducer: my-tool 1.2.3");
+
+    let mut zip_bytes = Vec::new();
+    let mut writer = async_zip::base::write::ZipFileWriter::new(&mut zip_bytes);
+    writer.comment(String::from("Some comment."));
+    writer.close().await.unwrap();
+
+    let zip = async_zip::base::read::mem::ZipFileReader::new(zip_bytes).await.unwrap();
+    assert_eq!(zip.file().producer(), None);
+}
+
+#[tokio::test]
+async fn zip_pad_to_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+
+    let mut writer = ZipFileWriter::new(Vec::new()).pad_to(4096);
+    writer.write_entry_whole(ZipEntryBuilder::new("a.txt".into(), Compression::Stored), b"first").await.unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("b.txt".into(), Compression::Stored), b"second").await.unwrap();
+    writer.producer(String::from("my-tool 1.2.3"));
+    let data = writer.close().await.unwrap();
+    assert_eq!(data.len(), 4096);
+
+    let zip = ZipFileReader::new(data.clone()).await.unwrap();
+    assert_eq!(zip.file().entries().len(), 2);
+    assert_eq!(zip.file().producer(), Some("my-tool 1.2.3"));
+    for (index, expected) in [b"first".as_slice(), b"second"].into_iter().enumerate() {
+        let mut buffer = Vec::new();
+        zip.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+        assert_eq!(buffer, expected);
+    }
+
+    let archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+    assert_eq!(archive.len(), 2);
+
+    let mut writer = ZipFileWriter::new(Vec::new()).pad_to(16);
+    writer.write_entry_whole(ZipEntryBuilder::new("a.txt".into(), Compression::Stored), b"first").await.unwrap();
+    assert!(matches!(writer.close().await, Err(ZipError::InvalidPaddedSize(_, 16))));
+
+    let writer = ZipFileWriter::new(Vec::new()).pad_to(22 + u16::MAX as u64 + 1);
+    assert!(matches!(writer.close().await, Err(ZipError::InvalidPaddedSize(22, _))));
+}
+
+#[tokio::test]
+async fn zip_append_in_memory() {
+    use async_zip::base::write::ZipFileWriter;
+    use futures_util::io::Cursor;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.comment("Existing comment".to_string());
+    for (name, data) in [("a.txt", "Alpha"), ("b.txt", "Beta")] {
+        let opts = ZipEntryBuilder::new(name.to_string().into(), Compression::Stored);
+        writer.write_entry_whole(opts, data.as_bytes()).await.unwrap();
+    }
+    let existing = writer.close().await.unwrap();
+
+    let mut writer = ZipFileWriter::append(Cursor::new(existing)).await.unwrap();
+    let opts = ZipEntryBuilder::new("c.txt".to_string().into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"Gamma").await.unwrap();
+    let appended = writer.close().await.unwrap().into_inner();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(appended).await.unwrap();
+    assert_eq!(reader.file().comment().as_str().unwrap(), "Existing comment");
+    assert_eq!(reader.file().entries().len(), 3);
+
+    for (index, (name, expected)) in [("a.txt", "Alpha"), ("b.txt", "Beta"), ("c.txt", "Gamma")].iter().enumerate() {
+        let mut entry_reader = reader.reader_with_entry(index).await.unwrap();
+        assert_eq!(entry_reader.entry().filename().as_str().unwrap(), *name);
+
+        let mut data = String::new();
+        entry_reader.read_to_string_checked(&mut data).await.unwrap();
+        assert_eq!(data, *expected);
+    }
+}
+
+#[tokio::test]
+async fn zip_stream_from_pipe() {
+    use async_zip::base::write::ZipFileWriter;
+    use tokio::io::AsyncWriteExt as TokioAsyncWriteExt;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    let input = b"Piped through a non-seekable source.\n".repeat(256);
+    let (stdin, mut source) = tokio::io::duplex(64);
+
+    let feeder = {
+        let input = input.clone();
+        tokio::spawn(async move {
+            source.write_all(&input).await.unwrap();
+        })
+    };
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let opts = ZipEntryBuilder::new("stdin.txt".to_string().into(), Compression::Stored);
+    let mut entry_writer = writer.write_entry_stream(opts).await.unwrap();
+    futures_util::io::copy(&mut stdin.compat(), &mut entry_writer).await.unwrap();
+    entry_writer.close().await.unwrap();
+    let stdout = writer.close().await.unwrap();
+    feeder.await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(stdout).await.unwrap();
+    let mut output = Vec::new();
+    reader.reader_with_entry(0).await.unwrap().read_to_end_checked(&mut output).await.unwrap();
+    assert_eq!(output, input);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_default_compression() {
+    use async_zip::base::write::ZipFileWriter;
+
+    let mut writer = ZipFileWriter::new(Vec::new()).with_default_compression(Compression::Deflate);
+    writer.write_entry("a.txt", b"Alpha").await.unwrap();
+    writer.write_entry("b.txt", b"Beta").await.unwrap();
+    let opts = ZipEntryBuilder::new("c.txt".into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"Gamma").await.unwrap();
+    writer.write_entry("d.txt", b"Delta").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let methods: Vec<_> = reader.file().entries().iter().map(|entry| entry.compression()).collect();
+    assert_eq!(methods, [Compression::Deflate, Compression::Deflate, Compression::Stored, Compression::Deflate]);
+}
+
+#[tokio::test]
+async fn zip_aligned_entries() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use futures_util::io::Cursor;
+
+    let alignments = [4u16, 16, 4096, 3];
+    let mut writer = ZipFileWriter::new(Vec::new());
+
+    for (index, alignment) in alignments.iter().enumerate() {
+        let name = format!("{}.bin", "x".repeat(index + 1));
+        let opts = ZipEntryBuilder::new(name.into(), Compression::Stored).align(*alignment);
+
+        if index % 2 == 0 {
+            writer.write_entry_whole(opts, b"Aligned data.").await.unwrap();
+        } else {
+            let mut entry_writer = writer.write_entry_stream(opts).await.unwrap();
+            entry_writer.write_all(b"Aligned data.").await.unwrap();
+            entry_writer.close().await.unwrap();
+        }
+    }
+
+    let data = writer.close().await.unwrap();
+    let mut reader = ZipFileReader::new(Cursor::new(data)).await.unwrap();
+
+    for (index, alignment) in alignments.iter().enumerate() {
+        let entry = reader.file().entries()[index].clone();
+        let offset = entry.data_offset(reader.inner_mut()).await.unwrap();
+        assert_eq!(offset % *alignment as u64, 0, "entry {index} wasn't aligned to {alignment}");
+
+        let mut output = String::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut output).await.unwrap();
+        assert_eq!(output, "Aligned data.");
+    }
+}
+
+#[tokio::test]
+async fn zip_align_stored_entries() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use futures_util::io::Cursor;
+
+    let mut writer = ZipFileWriter::new(Vec::new()).align_stored_entries(4);
+    for name in ["AndroidManifest.xml", "res/a.png", "resources.arsc"] {
+        writer.write_entry(name, b"Stored data.").await.unwrap();
+    }
+    let opts = ZipEntryBuilder::new("lib/arm64-v8a/libfoo.so".into(), Compression::Stored).align(16384);
+    writer.write_entry_whole(opts, b"Shared library.").await.unwrap();
+
+    let data = writer.close().await.unwrap();
+    let mut reader = ZipFileReader::new(Cursor::new(data)).await.unwrap();
+    let entries = reader.file().entries().to_vec();
+
+    for entry in &entries[..3] {
+        assert_eq!(entry.data_offset(reader.inner_mut()).await.unwrap() % 4, 0);
+    }
+    assert_eq!(entries[3].data_offset(reader.inner_mut()).await.unwrap() % 16384, 0);
+}
+
+#[tokio::test]
+async fn zip_abort() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.write_entry("a.txt", b"Alpha").await.unwrap();
+    let data = writer.abort();
+
+    assert!(!data.is_empty());
+    let result = async_zip::base::read::mem::ZipFileReader::new(data).await;
+    assert!(matches!(result, Err(ZipError::UnableToLocateEOCDR)));
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn zip_abort_and_truncate_fs() {
+    use async_zip::tokio::write::ZipFileWriter;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("partial.zip");
+
+    let mut writer = ZipFileWriter::with_tokio(tokio::fs::File::create(&path).await.unwrap());
+    writer.write_entry("a.txt", b"Alpha").await.unwrap();
+    writer.abort_and_truncate().await.unwrap();
+
+    assert_eq!(tokio::fs::metadata(&path).await.unwrap().len(), 0);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_empty_entries_stored() {
+    use async_zip::base::write::ZipFileWriter;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let opts = ZipEntryBuilder::new("empty.txt".into(), Compression::Deflate);
+    writer.write_entry_whole(opts, b"").await.unwrap();
+    let opts = ZipEntryBuilder::new("kept.txt".into(), Compression::Deflate).no_compression_for_empty(false);
+    writer.write_entry_whole(opts, b"").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let empty = &reader.file().entries()[0];
+    assert_eq!(empty.compression(), Compression::Stored);
+    assert_eq!(empty.compressed_size(), 0);
+    assert_eq!(reader.file().entries()[1].compression(), Compression::Deflate);
+
+    let mut buffer = Vec::new();
+    reader.reader_with_entry(0).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+    assert!(buffer.is_empty());
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_compress_by_extension() {
+    use async_zip::base::write::ZipFileWriter;
+    use std::collections::HashMap;
+
+    let map = HashMap::from([(".png".to_string(), Compression::Stored), ("TXT".to_string(), Compression::Deflate)]);
+    let mut writer = ZipFileWriter::new(Vec::new()).compress_by_extension(map);
+    writer.write_path("image.png", b"\x89PNG").await.unwrap();
+    writer.write_path("notes.Txt", b"Some notes.").await.unwrap();
+    writer.write_path("dir.txt/README", b"No extension.").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let methods: Vec<_> = reader.file().entries().iter().map(|entry| entry.compression()).collect();
+    assert_eq!(methods, [Compression::Stored, Compression::Deflate, Compression::Stored]);
+}
+
+#[cfg(feature = "encoding")]
+#[tokio::test]
+async fn zip_filename_shift_jis() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::encoding_rs::SHIFT_JIS;
+    use async_zip::error::ZipError;
+
+    let name = "\u{65e5}\u{672c}\u{8a9e}.txt";
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let opts = ZipEntryBuilder::new(name.into(), Compression::Stored).filename_encoding(SHIFT_JIS).unwrap();
+    writer.write_entry_whole(opts, b"Shift-JIS").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let filename = reader.file().entries()[0].filename();
+    let (decoded, _, had_errors) = SHIFT_JIS.decode(filename.alternative().unwrap());
+    assert!(!had_errors);
+    assert_eq!(decoded, name);
+    assert_eq!(filename.as_str().unwrap(), name);
+
+    let result = ZipEntryBuilder::new("\u{1f600}.txt".into(), Compression::Stored).filename_encoding(SHIFT_JIS);
+    assert!(matches!(result, Err(ZipError::StringNotRepresentable("Shift_JIS"))));
+}
+
+#[tokio::test]
+async fn zip_filename_cp437() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::StringEncoding;
+
+    // "Müller.txt" as encoded by legacy Windows tools, where 0x81 is 'ü' in CP437.
+    let raw = b"M\x81ller.txt".to_vec();
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let opts = ZipEntryBuilder::new(ZipString::new(raw.clone(), StringEncoding::Raw), Compression::Stored);
+    writer.write_entry_whole(opts, b"CP437").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.unwrap();
+    let filename = reader.file().entries()[0].filename();
+    assert!(filename.as_str().is_err());
+    assert_eq!(filename.as_str_cp437(), "M\u{fc}ller.txt");
+    assert_eq!(filename.as_bytes(), raw.as_slice());
+
+    #[cfg(feature = "encoding")]
+    assert_eq!(filename.as_str_with_encoding(async_zip::encoding_rs::WINDOWS_1252), "M\u{81}ller.txt");
+
+    #[cfg(feature = "encoding")]
+    {
+        use async_zip::base::read::ReadOptions;
+
+        let options = ReadOptions::new().fallback_encoding(async_zip::encoding_rs::IBM866);
+        let reader = async_zip::base::read::mem::ZipFileReader::with_options(data, options).await.unwrap();
+        let filename = reader.file().entries()[0].filename();
+        assert_eq!(filename.as_str().unwrap(), "M\u{411}ller.txt");
+        assert_eq!(filename.alternative(), Some(raw.as_slice()));
+    }
+
+    let utf8 = ZipString::from("M\u{fc}ller.txt");
+    assert_eq!(utf8.as_str_cp437(), "M\u{fc}ller.txt");
+
+    // Bytes flagged as UTF-8 are never decoded as CP437, even when they're invalid.
+    let flagged = ZipString::new(raw.clone(), StringEncoding::Utf8);
+    assert_eq!(flagged.as_str_cp437(), "M\u{fffd}ller.txt");
+    #[cfg(feature = "encoding")]
+    assert_eq!(flagged.as_str_with_encoding(async_zip::encoding_rs::WINDOWS_1252), "M\u{fffd}ller.txt");
+}
+
+#[cfg(feature = "deflate-dictionary")]
+#[tokio::test]
+async fn zip_deflate_dictionary() {
+    use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+    use async_zip::base::write::ZipFileWriter;
+
+    let dictionary = b"{\"type\": \"event\", \"source\": \"sensor\", \"value\": ".to_vec();
+    let content = b"{\"type\": \"event\", \"source\": \"sensor\", \"value\": 42}";
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let opts = ZipEntryBuilder::new("with.json".into(), Compression::Deflate).deflate_dictionary(dictionary.clone());
+    writer.write_entry_whole(opts, content).await.unwrap();
+    let opts = ZipEntryBuilder::new("without.json".into(), Compression::Deflate);
+    writer.write_entry_whole(opts, content).await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let options = ReadOptions::new().deflate_dictionary(dictionary);
+    let reader = ZipFileReader::with_options(data.clone(), options).await.unwrap();
+    let entries = reader.file().entries();
+    assert!(entries[0].compressed_size() < entries[1].compressed_size());
+
+    for index in 0..2 {
+        let mut buffer = Vec::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+        assert_eq!(buffer, content);
+    }
+
+    let reader = ZipFileReader::new(data).await.unwrap();
+    let mut buffer = Vec::new();
+    assert!(reader.reader_with_entry(0).await.unwrap().read_to_end_checked(&mut buffer).await.is_err());
+}
+
+#[tokio::test]
+async fn zip_flush_completed_entries() {
+    use async_zip::base::read::stream::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use futures_util::io::BufWriter;
+
+    let mut writer = ZipFileWriter::new(BufWriter::new(Vec::new()));
+    let opts = ZipEntryBuilder::new("first.txt".into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"Flushed before close.").await.unwrap();
+
+    assert!(writer.inner_mut().get_ref().is_empty());
+    writer.flush().await.unwrap();
+    let flushed = writer.inner_mut().get_ref().clone();
+
+    let reader = ZipFileReader::new(flushed.as_slice());
+    let mut entry = reader.next_with_entry().await.unwrap().unwrap();
+    let mut buffer = String::new();
+    entry.reader_mut().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(entry.reader().entry().filename().as_str().unwrap(), "first.txt");
+    assert_eq!(buffer, "Flushed before close.");
+
+    writer.close().await.unwrap();
+}
+
+#[cfg(all(feature = "deflate", feature = "fingerprint"))]
+#[tokio::test]
+async fn zip_content_fingerprint() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+
+    async fn fingerprint(compression: Compression, names: &[&str], content: &[u8]) -> [u8; 32] {
+        let mut writer = ZipFileWriter::new(Vec::new());
+        for name in names {
+            let opts = ZipEntryBuilder::new(name.to_string().into(), compression);
+            writer.write_entry_whole(opts, content).await.unwrap();
+        }
+        let data = writer.close().await.unwrap();
+        ZipFileReader::new(data).await.unwrap().file().content_fingerprint()
+    }
+
+    let stored = fingerprint(Compression::Stored, &["a.txt", "b.txt"], b"Same content.").await;
+    let deflated = fingerprint(Compression::Deflate, &["b.txt", "a.txt"], b"Same content.").await;
+    let changed = fingerprint(Compression::Stored, &["a.txt", "b.txt"], b"Other content.").await;
+
+    assert_eq!(stored, deflated);
+    assert_ne!(stored, changed);
+}
+
+#[cfg(feature = "chrono")]
+#[tokio::test]
+async fn zip_with_clock() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::ZipDateTime;
+    use chrono::{TimeZone, Utc};
+
+    let pinned = ZipDateTime::from_chrono(&Utc.with_ymd_and_hms(2021, 6, 15, 12, 30, 44).unwrap());
+    let explicit = ZipDateTime::from_chrono(&Utc.with_ymd_and_hms(1999, 12, 31, 23, 59, 58).unwrap());
+
+    let mut writer = ZipFileWriter::new(Vec::new()).with_clock(move || pinned);
+    writer.write_entry("clocked.txt", b"Pinned.").await.unwrap();
+    let opts = ZipEntryBuilder::new("explicit.txt".into(), Compression::Stored).last_modification_date(explicit);
+    writer.write_entry_whole(opts, b"Explicit.").await.unwrap();
+    // An explicitly zeroed date is kept rather than being replaced by the clock.
+    let opts =
+        ZipEntryBuilder::new("zeroed.txt".into(), Compression::Stored).last_modification_date(ZipDateTime::default());
+    writer.write_entry_whole(opts, b"Zeroed.").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let entries = reader.file().entries();
+    assert_eq!(*entries[0].last_modification_date(), pinned);
+    assert_eq!(entries[0].last_modification_date().year(), 2021);
+    assert_eq!(*entries[1].last_modification_date(), explicit);
+    assert_eq!(*entries[2].last_modification_date(), ZipDateTime::default());
+}
+
+#[cfg(feature = "time")]
+#[tokio::test]
+async fn zip_with_time_clock() {
+    use async_zip::base::write::ZipFileWriter;
+    use time::{Date, Month, Time, UtcOffset};
+
+    // A clock in another offset is converted to UTC before being written.
+    let date = Date::from_calendar_date(2021, Month::June, 15).unwrap();
+    let pinned =
+        date.with_time(Time::from_hms(14, 30, 44).unwrap()).assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+
+    let mut writer = ZipFileWriter::new(Vec::new()).with_clock(move || pinned);
+    writer.write_entry("clocked.txt", b"Pinned.").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let written = reader.file().entries()[0].last_modification_date();
+    assert_eq!((written.year(), written.month(), written.day()), (2021, 6, 15));
+    assert_eq!((written.hour(), written.minute(), written.second()), (12, 30, 44));
+    assert_eq!(written.as_time(), Some(pinned));
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_deflate_compression_levels() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::Level;
+
+    let payload: Vec<u8> = (0..64 * 1024u32).map(|index| ((index * index % 251) ^ (index / 7)) as u8).collect();
+
+    let mut sizes = Vec::new();
+    for level in [Level::Fastest, Level::Best] {
+        let mut writer = ZipFileWriter::new(Vec::new());
+        let builder = ZipEntryBuilder::new("data.bin".into(), Compression::Deflate).compression_level(level);
+        writer.write_entry_whole(builder, &payload).await.unwrap();
+
+        let builder = ZipEntryBuilder::new("stream.bin".into(), Compression::Deflate).compression_level(level);
+        let mut entry_writer = writer.write_entry_stream(builder).await.unwrap();
+        entry_writer.write_all(&payload).await.unwrap();
+        entry_writer.close().await.unwrap();
+
+        let reader = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+        for index in 0..2 {
+            let mut buffer = Vec::new();
+            reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+            assert_eq!(buffer, payload);
+        }
+
+        let entries = reader.file().entries();
+        sizes.push((entries[0].compressed_size(), entries[1].compressed_size()));
+    }
+
+    assert!(sizes[1].0 <= sizes[0].0, "{sizes:?}");
+    assert!(sizes[1].1 <= sizes[0].1, "{sizes:?}");
+}
+
+#[cfg(all(feature = "aes", feature = "deflate"))]
+#[tokio::test]
+async fn zip_aes_encrypted_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::encryption::AesStrength;
+    use async_zip::error::ZipError;
+    use futures_util::io::AsyncReadExt;
+    use std::io::Read;
+
+    let content = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(10);
+    let mut data = Vec::new();
+    let mut writer = ZipFileWriter::new(&mut data);
+
+    let builder =
+        ZipEntryBuilder::new("secret.txt".into(), Compression::Deflate).encrypt_aes(b"password", AesStrength::Aes256);
+    writer.write_entry_whole(builder, &content).await.unwrap();
+    writer.close().await.unwrap();
+
+    // Check interoperability with another implementation of the scheme.
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&data)).unwrap();
+    let mut file = archive.by_name_decrypt("secret.txt", b"password").unwrap().unwrap();
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).unwrap();
+    assert_eq!(buffer, content);
+    drop(file);
+
+    let zip = ZipFileReader::new(data.clone()).await.unwrap();
+    let mut buffer = Vec::new();
+    zip.reader_with_entry_and_password(0, b"password").await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, content);
+
+    // Tampering with the final byte of the authentication code is caught once EOF is reached.
+    let offset = zip.file().entries()[0].header_offset() as usize;
+    let end = offset + 30 + "secret.txt".len() + 11 + zip.file().entries()[0].compressed_size() as usize;
+    data[end - 1] ^= 0xFF;
+
+    let zip = ZipFileReader::new(data).await.unwrap();
+    let mut reader = zip.reader_with_entry_and_password(0, b"password").await.unwrap();
+    let err = reader.read_to_end(&mut Vec::new()).await.unwrap_err();
+    assert!(matches!(ZipError::from(err), ZipError::WrongPassword));
+}
+
+#[tokio::test]
+async fn zip_unix_permissions_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::AttributeCompatibility;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("run.sh".into(), Compression::Stored).unix_permissions(0o100755);
+    writer.write_entry_whole(builder, b"#!/bin/sh\n").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let zip = ZipFileReader::new(data.clone()).await.unwrap();
+    let entry = &zip.file().entries()[0];
+    assert_eq!(entry.attribute_compatibility(), AttributeCompatibility::Unix);
+    assert_eq!(entry.unix_permissions(), Some(0o100755));
+
+    // Other tools only honour the mode if the "version made by" field records a Unix host.
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+    assert_eq!(archive.by_index(0).unwrap().unix_mode(), Some(0o100755));
+}
+
+#[tokio::test]
+async fn zip_ntfs_times_in_out() {
+    use async_zip::base::read::{mem, stream};
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::NtfsTimes;
+    use futures_util::io::AsyncWriteExt;
+
+    // 2023-06-15 19:14:56.789 UTC, a second later, and a day earlier, as FILETIME values.
+    let times = NtfsTimes::new(133_313_300_967_890_000, 133_313_300_977_890_000, 133_312_436_967_890_000);
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("whole.txt".into(), Compression::Stored).ntfs_times(times);
+    writer.write_entry_whole(builder, b"whole").await.unwrap();
+    let builder = ZipEntryBuilder::new("stream.txt".into(), Compression::Stored).ntfs_times(times);
+    let mut entry_writer = writer.write_entry_stream(builder).await.unwrap();
+    entry_writer.write_all(b"stream").await.unwrap();
+    entry_writer.close().await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let zip = mem::ZipFileReader::new(data.clone()).await.unwrap();
+    for entry in zip.file().entries() {
+        assert_eq!(entry.ntfs_times(), Some(times));
+    }
+
+    let reader = stream::ZipFileReader::new(data.as_slice());
+    let reader = reader.next_with_entry().await.unwrap().unwrap();
+    assert_eq!(reader.reader().entry().ntfs_times(), Some(times));
+
+    let builder = ZipEntryBuilder::new("none.txt".into(), Compression::Stored);
+    assert_eq!(builder.build().ntfs_times(), None);
+}
+
+#[tokio::test]
+async fn zip_extended_timestamp_in_out() {
+    use async_zip::base::read::{mem, stream};
+    use async_zip::base::write::ZipFileWriter;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder =
+        ZipEntryBuilder::new("stamped.txt".into(), Compression::Stored).last_modification_unix_time(1686856496);
+    writer.write_entry_whole(builder, b"timestamped").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let zip = mem::ZipFileReader::new(data.clone()).await.unwrap();
+    let timestamp = zip.file().entries()[0].extended_timestamp().unwrap();
+    assert_eq!((timestamp.modified(), timestamp.accessed(), timestamp.created()), (Some(1686856496), None, None));
+
+    let reader = stream::ZipFileReader::new(data.as_slice());
+    let reader = reader.next_with_entry().await.unwrap().unwrap();
+    assert_eq!(reader.reader().entry().extended_timestamp(), Some(timestamp));
+
+    // Other tools take the modification time from the field in preference to the MS-DOS date.
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+    let entry = archive.by_index(0).unwrap();
+    assert!(entry.extra_data().starts_with(&[0x55, 0x54, 0x05, 0x00, 0x01, 0x30, 0x63, 0x8b, 0x64]));
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_write_entry_from_reader_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+
+    // An xorshift sequence, so that the data is incompressible.
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let large: Vec<u8> = (0..10 * 1024 * 1024)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect();
+    let small = b"a small entry".to_vec();
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for (name, data) in [("small.bin", &small), ("large.bin", &large)] {
+        let builder = ZipEntryBuilder::new(name.into(), Compression::Deflate);
+        let source = futures_util::io::Cursor::new(data.as_slice());
+        assert_eq!(writer.write_entry_from_reader(builder, source).await.unwrap(), data.len() as u64);
+    }
+    let data = writer.close().await.unwrap();
+
+    // Only the entry which exceeded the buffer is written with a data descriptor (bit 3 of its flags).
+    let zip = ZipFileReader::new(data.clone()).await.unwrap();
+    for (index, (expected, descriptor)) in [(small, false), (large, true)].into_iter().enumerate() {
+        let offset = zip.file().entries()[index].header_offset() as usize;
+        assert_eq!(data[offset + 6] & 0x8 != 0, descriptor);
+
+        let mut buffer = Vec::new();
+        zip.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+        assert!(buffer == expected);
+    }
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_copy_entry_raw_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use futures_util::io::AsyncReadExt;
+
+    let content = "A deflated entry which is copied without being recompressed. ".repeat(64);
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("copied.txt".into(), Compression::Deflate);
+    writer.write_entry_whole(builder, content.as_bytes()).await.unwrap();
+    let source = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    let mut raw = Vec::new();
+    source.raw_reader_without_entry(0).await.unwrap().read_to_end(&mut raw).await.unwrap();
+    assert_eq!(raw.len() as u64, source.file().entries()[0].compressed_size());
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let stored_entry = &source.file().entries()[0];
+    writer.copy_entry_raw(stored_entry, source.raw_reader_without_entry(0).await.unwrap()).await.unwrap();
+    let copy = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    let entry = &copy.file().entries()[0];
+    assert_eq!(entry.filename().as_str().unwrap(), "copied.txt");
+    assert_eq!(entry.compression(), Compression::Deflate);
+    assert_eq!((entry.crc32(), entry.compressed_size()), (stored_entry.crc32(), stored_entry.compressed_size()));
+
+    let mut copied_raw = Vec::new();
+    copy.raw_reader_without_entry(0).await.unwrap().read_to_end(&mut copied_raw).await.unwrap();
+    assert_eq!(copied_raw, raw);
+
+    let mut buffer = String::new();
+    copy.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, content);
+
+    let encrypted =
+        ZipFileReader::new(std::fs::read("tests/test_inputs/zipcrypto_encrypted.zip").unwrap()).await.unwrap();
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let reader = encrypted.raw_reader_without_entry(0).await.unwrap();
+    let err = writer.copy_entry_raw(&encrypted.file().entries()[0], reader).await.unwrap_err();
+    assert!(matches!(err, ZipError::FeatureNotSupported(_)));
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_write_entry_raw_with_crc_and_sizes() {
+    use async_zip::base::read::{mem::ZipFileReader, stream::ZipFileReader as StreamZipFileReader};
+    use async_zip::base::write::ZipFileWriter;
+    use futures_util::io::AsyncReadExt;
+
+    let content = "An entry whose CRC32 value and sizes are supplied upfront. ".repeat(64);
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("source.txt".into(), Compression::Deflate);
+    writer.write_entry_whole(builder, content.as_bytes()).await.unwrap();
+    let source = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+    let source_entry = &source.file().entries()[0];
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("copied.txt".into(), Compression::Deflate).with_crc_and_sizes(
+        source_entry.crc32(),
+        source_entry.compressed_size(),
+        source_entry.uncompressed_size(),
+    );
+    writer.write_entry_raw(builder, source.raw_reader_without_entry(0).await.unwrap()).await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let copy = ZipFileReader::new(data.clone()).await.unwrap();
+    let entry = &copy.file().entries()[0];
+    assert!(!entry.has_data_descriptor());
+    assert_eq!(entry.uncompressed_size(), content.len() as u64);
+
+    let mut buffer = String::new();
+    copy.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, content);
+
+    // The local file header alone is complete, so the entry can also be read without the central directory.
+    let zip = StreamZipFileReader::new(futures_util::io::Cursor::new(data));
+    let mut entry = zip.next_with_entry().await.unwrap().unwrap();
+    assert_eq!(entry.reader().entry().crc32(), source_entry.crc32());
+
+    let mut buffer = String::new();
+    entry.reader_mut().read_to_string(&mut buffer).await.unwrap();
+    assert_eq!(buffer, content);
+
+    // Supplying a compressed size which exceeds the data errors rather than writing a truncated entry.
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("short.txt".into(), Compression::Stored).with_crc_and_sizes(0, 16, 16);
+    assert!(writer.write_entry_raw(builder, futures_util::io::Cursor::new([0; 8])).await.is_err());
+}
+
+#[tokio::test]
+async fn zip_write_entries_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+
+    let entries: Vec<_> = (0..5)
+        .map(|index| {
+            let builder = ZipEntryBuilder::new(format!("entry{index}.txt").into(), Compression::Stored);
+            (builder, format!("data for entry {index}").into_bytes())
+        })
+        .collect();
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.write_entries(entries).await.unwrap();
+    let zip = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    assert_eq!(zip.file().entries().len(), 5);
+    for index in 0..5 {
+        assert_eq!(zip.file().entries()[index].filename().as_str().unwrap(), format!("entry{index}.txt"));
+
+        let mut buffer = String::new();
+        zip.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+        assert_eq!(buffer, format!("data for entry {index}"));
+    }
+
+    // A filename which is too long to be stored fails the second entry, leaving only the first written.
+    let entries = vec![
+        (ZipEntryBuilder::new("first.txt".into(), Compression::Stored), b"first".to_vec()),
+        (ZipEntryBuilder::new("x".repeat(70_000).into(), Compression::Stored), b"second".to_vec()),
+        (ZipEntryBuilder::new("third.txt".into(), Compression::Stored), b"third".to_vec()),
+    ];
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    match writer.write_entries(entries).await {
+        Err(ZipError::EntryError { index, source, .. }) => {
+            assert_eq!(index, 1);
+            assert!(matches!(*source, ZipError::FileNameTooLarge));
+        }
+        other => panic!("expected an entry error, got {other:?}"),
+    }
+
+    let zip = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+    assert_eq!(zip.file().entries().len(), 1);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn zip_mem_writer_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::mem::MemZipFileWriter;
+
+    let mut writer = MemZipFileWriter::new();
+    writer.write_entry_whole(ZipEntryBuilder::new("first.txt".into(), Compression::Deflate), b"first").await.unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("second.txt".into(), Compression::Stored), b"second").await.unwrap();
+
+    let zip = ZipFileReader::new(writer.into_inner().await.unwrap()).await.unwrap();
+    assert_eq!(zip.file().entries().len(), 2);
+
+    for (index, (name, expected)) in [("first.txt", "first"), ("second.txt", "second")].into_iter().enumerate() {
+        assert_eq!(zip.file().entries()[index].filename().as_str().unwrap(), name);
+
+        let mut buffer = String::new();
+        zip.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+        assert_eq!(buffer, expected);
+    }
+}
+
+#[tokio::test]
+async fn zip_general_purpose_flags_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("whole.txt".into(), Compression::Stored).general_purpose_flags(0x1000 | 0x0009);
+    writer.write_entry_whole(builder, b"whole").await.unwrap();
+
+    let builder = ZipEntryBuilder::new("stream.txt".into(), Compression::Stored).general_purpose_flags(0x1000);
+    let mut entry_writer = writer.write_entry_stream(builder).await.unwrap();
+    entry_writer.write_all(b"stream").await.unwrap();
+    entry_writer.close().await.unwrap();
+
+    let zip = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+    let entries = zip.file().entries();
+
+    // The writer's own encrypted and data descriptor bits take precedence over those provided.
+    assert_eq!(entries[0].general_purpose_flags(), 0x1000 | 0x800);
+    assert_eq!(entries[1].general_purpose_flags(), 0x1000 | 0x800 | 0x8);
+
+    assert!(!entries[0].has_data_descriptor() && entries[1].has_data_descriptor());
+    assert!(entries.iter().all(|entry| entry.is_utf8() && !entry.is_encrypted()));
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn zip_add_directory_in_out() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+
+    let root = tempfile::tempdir().unwrap();
+    tokio::fs::create_dir_all(root.path().join("a/empty")).await.unwrap();
+    tokio::fs::write(root.path().join("a/b.txt"), "nested").await.unwrap();
+    tokio::fs::write(root.path().join("c.txt"), "top").await.unwrap();
+    // Large enough to be streamed rather than buffered whole.
+    let large: Vec<u8> = (0..200_000u32).map(|index| (index % 251) as u8).collect();
+    tokio::fs::write(root.path().join("a/large.bin"), &large).await.unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(root.path().join("c.txt"), std::fs::Permissions::from_mode(0o640)).await.unwrap();
+        tokio::fs::symlink("c.txt", root.path().join("link")).await.unwrap();
+    }
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.add_directory(root.path(), Compression::Stored).await.unwrap();
+    let zip = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    let mut expected = vec!["c.txt", "link", "a/b.txt", "a/large.bin", "a/empty/"];
+    if cfg!(not(unix)) {
+        expected.retain(|name| *name != "link");
+    }
+    assert_eq!(zip.entry_names().collect::<Vec<_>>(), expected);
+
+    let entries = zip.file().entries();
+    let empty = entries.iter().find(|entry| entry.filename().as_str().unwrap() == "a/empty/").unwrap();
+    assert!(empty.dir().unwrap());
+    assert!(entries.iter().all(|entry| entry.extended_timestamp().is_some_and(|times| times.modified().is_some())));
+
+    let mut buffer = String::new();
+    zip.reader_with_entry(2).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "nested");
+
+    let index = zip.file().entry_index("a/large.bin").unwrap();
+    let mut data = Vec::new();
+    zip.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut data).await.unwrap();
+    assert_eq!(data, large);
+
+    #[cfg(unix)]
+    {
+        assert_eq!(entries[0].unix_permissions(), Some(0o100640));
+        assert_eq!(entries[1].unix_permissions().map(|mode| mode & 0o170000), Some(0o120000));
+
+        let mut target = String::new();
+        zip.reader_with_entry(1).await.unwrap().read_to_string_checked(&mut target).await.unwrap();
+        assert_eq!(target, "c.txt");
+    }
+}
+
+#[tokio::test]
+async fn zip_append_keeps_raw_comment() {
+    use async_zip::base::write::ZipFileWriter;
+    use futures_util::io::Cursor;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.comment("Comment".to_string());
+    let mut existing = writer.close().await.unwrap();
+    // Replace the comment's final byte with one which is invalid UTF-8.
+    *existing.last_mut().unwrap() = 0xFF;
+
+    let mut writer = ZipFileWriter::append(Cursor::new(existing)).await.unwrap();
+    let opts = ZipEntryBuilder::new("a.txt".to_string().into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"Alpha").await.unwrap();
+    let appended = writer.close().await.unwrap().into_inner();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(appended).await.unwrap();
+    assert_eq!(reader.file().comment().as_bytes(), b"Commen\xFF");
+}
+
+#[tokio::test]
+async fn zip_append_truncates_shorter_file() {
+    use async_zip::base::write::ZipFileWriter;
+    use futures_util::io::Cursor;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.comment("A long comment which is replaced by a shorter one. ".repeat(4));
+    let existing = writer.close().await.unwrap();
+    let existing_len = existing.len();
+
+    let mut writer = ZipFileWriter::append(Cursor::new(existing)).await.unwrap();
+    writer.comment("Short".to_string());
+    let opts = ZipEntryBuilder::new("a.txt".to_string().into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"Alpha").await.unwrap();
+    let appended = writer.close().await.unwrap().into_inner();
+    assert!(appended.len() < existing_len);
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(appended).await.unwrap();
+    assert_eq!(reader.file().comment().as_str().unwrap(), "Short");
+    assert_eq!(reader.file().trailing_bytes(), 0);
+    assert_eq!(reader.file().entries().len(), 1);
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn zip_append_to_file() {
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::tokio::write::ZipFileWriter;
+
+    let root = tempfile::tempdir().unwrap();
+
+    for force_zip64 in [false, true] {
+        let path = root.path().join(format!("append_{force_zip64}.zip"));
+
+        let mut writer = ZipFileWriter::with_tokio(tokio::fs::File::create(&path).await.unwrap());
+        if force_zip64 {
+            writer = writer.force_zip64();
+        }
+        let opts = ZipEntryBuilder::new("a.txt".to_string().into(), Compression::Stored);
+        writer.write_entry_whole(opts, b"Alpha").await.unwrap();
+        writer.close().await.unwrap();
+
+        let file = tokio::fs::OpenOptions::new().read(true).write(true).open(&path).await.unwrap();
+        let mut writer = ZipFileWriter::append_to(file).await.unwrap();
+        let opts = ZipEntryBuilder::new("b.txt".to_string().into(), Compression::Stored);
+        writer.write_entry_whole(opts, b"Beta").await.unwrap();
+        writer.close().await.unwrap();
+
+        let reader = ZipFileReader::new(&path).await.unwrap();
+        assert_eq!(reader.file().zip64(), force_zip64);
+        assert_eq!(reader.entry_names().collect::<Vec<_>>(), ["a.txt", "b.txt"]);
+
+        for (index, expected) in ["Alpha", "Beta"].iter().enumerate() {
+            let mut data = String::new();
+            reader.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut data).await.unwrap();
+            assert_eq!(data, *expected);
+        }
+    }
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn zip_split_into_volumes() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use async_zip::tokio::write::ZipSplitWriter;
+    use futures_util::io::AsyncWriteExt;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    const VOLUME_SIZE: u64 = 64 * 1024;
+
+    let root = tempfile::tempdir().unwrap();
+    let prefix = root.path().join("split");
+
+    // The first entry leaves too little of the first volume for the next local file header, which must move to the
+    // second volume rather than be split, while the second entry's data spans the second and third volumes.
+    let first = vec![b'a'; VOLUME_SIZE as usize - 4 - 30 - "a.bin".len() - 8];
+    let second: Vec<u8> = (0..100 * 1024u32).map(|value| (value % 251) as u8).collect();
+
+    let mut writer = ZipSplitWriter::new(&prefix, VOLUME_SIZE).await.unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("a.bin".into(), Compression::Stored), &first).await.unwrap();
+    let mut entry_writer =
+        writer.write_entry_stream(ZipEntryBuilder::new("b.bin".into(), Compression::Stored)).await.unwrap();
+    entry_writer.write_all(&second).await.unwrap();
+    entry_writer.close().await.unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("c.txt".into(), Compression::Stored), b"Gamma").await.unwrap();
+    let paths = writer.close().await.unwrap();
+
+    let names: Vec<_> = paths.iter().map(|path| path.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(names, ["split.z01", "split.z02", "split.zip"]);
+
+    let mut volumes = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        let length = tokio::fs::metadata(path).await.unwrap().len();
+        assert!(length <= VOLUME_SIZE);
+        if index == 0 {
+            assert!(length < VOLUME_SIZE);
+        }
+        volumes.push(tokio::fs::File::open(path).await.unwrap().compat());
+    }
+
+    let mut reader = ZipFileReader::new_split(volumes).await.unwrap();
+    assert_eq!(reader.entry_names().collect::<Vec<_>>(), ["a.bin", "b.bin", "c.txt"]);
+
+    for (index, expected) in [&first[..], &second[..], b"Gamma"].into_iter().enumerate() {
+        let mut data = Vec::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut data).await.unwrap();
+        assert_eq!(data, expected);
+    }
+}
+
+/// Tests that spilling and sorting the central directory are rejected in combination, in either order.
+#[test]
+fn zip_spill_central_directory_rejects_sorting() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use futures_util::io::Cursor;
+
+    let writer = ZipFileWriter::new(Vec::new()).spill_central_directory(Cursor::new(Vec::new()), 64).unwrap();
+    assert!(matches!(writer.sort_central_directory(true), Err(ZipError::FeatureNotSupported(_))));
+
+    let writer = ZipFileWriter::new(Vec::new()).sort_central_directory(true).unwrap();
+    let result = writer.spill_central_directory(Cursor::new(Vec::new()), 64);
+    assert!(matches!(result, Err(ZipError::FeatureNotSupported(_))));
+}
+
+/// Tests that entries are copied raw under their new names, and that unsafe new names are rejected.
+#[tokio::test]
+async fn zip_rewrite_names() {
+    use async_zip::base::read::{mem, seek};
+    use async_zip::base::write::{rewrite_names, ZipFileWriter};
+    use async_zip::error::ZipError;
+    use async_zip::UnsafeReason;
+    use futures_util::io::Cursor;
+
+    #[cfg(feature = "deflate")]
+    let compression = Compression::Deflate;
+    #[cfg(not(feature = "deflate"))]
+    let compression = Compression::Stored;
+
+    let entries = [("a.txt", Compression::Stored), ("dir/", Compression::Stored), ("dir/b.txt", compression)];
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for (name, compression) in entries {
+        let builder = ZipEntryBuilder::new(name.into(), compression);
+        writer.write_entry_whole(builder, name.repeat(64).as_bytes()).await.unwrap();
+    }
+    let data = writer.close().await.unwrap();
+
+    let mut reader = seek::ZipFileReader::new(Cursor::new(data)).await.unwrap();
+    let mut writer = ZipFileWriter::new(Vec::new());
+    rewrite_names(&mut reader, &mut writer, |name| format!("payload/{name}")).await.unwrap();
+    let rewritten = writer.close().await.unwrap();
+
+    let rewritten_reader = mem::ZipFileReader::new(rewritten).await.unwrap();
+    for (index, (name, compression)) in entries.iter().enumerate() {
+        let entry = &rewritten_reader.file().entries()[index];
+        assert_eq!(entry.filename().as_str().unwrap(), format!("payload/{name}"));
+        assert_eq!(entry.compression(), *compression);
+        assert_eq!(entry.compressed_size(), reader.file().entries()[index].compressed_size());
+
+        let mut buffer = String::new();
+        rewritten_reader.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+        assert_eq!(buffer, name.repeat(64));
+    }
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let err = rewrite_names(&mut reader, &mut writer, |name| format!("../{name}")).await.unwrap_err();
+    assert!(matches!(err.without_entry(), ZipError::UnsafeEntryPath(UnsafeReason::ParentComponent)));
+}
@@ -6,9 +6,48 @@ use tokio_util::compat::TokioAsyncReadCompatExt;
 mod common;
 
 const ZSTD_ZIP_FILE: &str = "tests/test_inputs/sample_data.zstd.zip";
+#[cfg(feature = "zstd")]
+const ZSTD_LARGE_WINDOW_ZIP_FILE: &str = "tests/test_inputs/zstd_large_window.zip";
+#[cfg(feature = "deflate")]
 const DEFLATE_ZIP_FILE: &str = "tests/test_inputs/sample_data.deflate.zip";
+#[cfg(feature = "bzip2")]
+const BZIP2_ZIP_FILE: &str = "tests/test_inputs/sample_data.bz2.zip";
+#[cfg(feature = "lzma")]
+const LZMA_ZIP_FILE: &str = "tests/test_inputs/sample_data.lzma.zip";
 const STORE_ZIP_FILE: &str = "tests/test_inputs/sample_data.store.zip";
 const UTF8_EXTRA_ZIP_FILE: &str = "tests/test_inputs/sample_data_utf8_extra.zip";
+const ZERO_CRC_ZIP_FILE: &str = "tests/test_inputs/zero_crc.store.zip";
+const UNSAFE_ENTRIES_ZIP_FILE: &str = "tests/test_inputs/unsafe_entries.zip";
+const MISSING_ZIP64_EXTRA_ZIP_FILE: &str = "tests/test_inputs/missing_zip64_extra.zip";
+const NESTED_ZIP_FILE: &str = "tests/test_inputs/nested.zip";
+const UNICODE_COMMENT_ZIP_FILE: &str = "tests/test_inputs/unicode_comment_extra.zip";
+const SHORT_COMMENT_LENGTH_ZIP_FILE: &str = "tests/test_inputs/short_comment_length.zip";
+const ALIGNED_STORED_APK_FILE: &str = "tests/test_inputs/aligned_stored.apk";
+const LOCAL_CENTRAL_MISMATCH_ZIP_FILE: &str = "tests/test_inputs/local_central_mismatch.zip";
+const RAW_NAME_BYTES_ZIP_FILE: &str = "tests/test_inputs/raw_name_bytes.zip";
+const BINARY_ENTRY_COMMENT_ZIP_FILE: &str = "tests/test_inputs/binary_entry_comment.zip";
+const EXTENDED_TIMESTAMP_ZIP_FILE: &str = "tests/test_inputs/extended_timestamp.zip";
+const INFLATED_ENTRY_COUNT_ZIP_FILE: &str = "tests/test_inputs/inflated_entry_count.zip";
+const WRONG_ENTRY_COUNT_ZIP_FILE: &str = "tests/test_inputs/wrong_entry_count.zip";
+const DIRECTORY_ATTRIBUTES_ZIP_FILE: &str = "tests/test_inputs/directory_attributes.zip";
+const INCONSISTENT_STORED_ZIP_FILE: &str = "tests/test_inputs/inconsistent_stored.zip";
+const TRAILING_DATA_ZIP_FILE: &str = "tests/test_inputs/trailing_data.zip";
+const CORRUPTED_CRC_ZIP_FILE: &str = "tests/test_inputs/corrupted_crc.zip";
+#[cfg(feature = "deflate")]
+const ZEROED_CENTRAL_DIRECTORY_ZIP_FILE: &str = "tests/test_inputs/zeroed_central_directory.zip";
+#[cfg(feature = "deflate")]
+const SFX_PREPENDED_ZIP_FILE: &str = "tests/test_inputs/sfx_prepended.zip";
+const AES_ENCRYPTED_ZIP_FILE: &str = "tests/test_inputs/aes_encrypted.zip";
+#[cfg(feature = "tokio-fs")]
+const REDUNDANT_DIRS_ZIP_FILE: &str = "tests/test_inputs/redundant_dirs.zip";
+#[cfg(feature = "deflate")]
+const ZIPCRYPTO_ENCRYPTED_ZIP_FILE: &str = "tests/test_inputs/zipcrypto_encrypted.zip";
+#[cfg(feature = "lzma")]
+const LZMA_WITHOUT_EOS_FLAG_ZIP_FILE: &str = "tests/test_inputs/lzma_without_eos_flag.zip";
+#[cfg(feature = "deflate")]
+const DEFLATE_WITHOUT_FINAL_BLOCK_ZIP_FILE: &str = "tests/test_inputs/deflate_without_final_block.zip";
+#[cfg(feature = "deflate")]
+const UNKNOWN_SIZES_ZIP_FILE: &str = "tests/test_inputs/unknown_sizes.zip";
 
 #[cfg(feature = "zstd")]
 #[tokio::test]
@@ -22,6 +61,18 @@ async fn decompress_deflate_zip_seek() {
     common::check_decompress_seek(DEFLATE_ZIP_FILE).await
 }
 
+#[cfg(feature = "bzip2")]
+#[tokio::test]
+async fn decompress_bzip2_zip_seek() {
+    common::check_decompress_seek(BZIP2_ZIP_FILE).await
+}
+
+#[cfg(feature = "lzma")]
+#[tokio::test]
+async fn decompress_lzma_zip_seek() {
+    common::check_decompress_seek(LZMA_ZIP_FILE).await
+}
+
 #[tokio::test]
 async fn check_empty_zip_seek() {
     let mut data: Vec<u8> = Vec::new();
@@ -48,6 +99,101 @@ async fn decompress_deflate_zip_mem() {
     common::check_decompress_mem(content).await
 }
 
+#[cfg(feature = "zstd")]
+#[tokio::test]
+async fn decompress_zstd_max_window_log_mem() {
+    use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+    use async_zip::error::ZipError;
+
+    // The entry's frame header declares a 16MiB (2^24 byte) window.
+    let content = tokio::fs::read(ZSTD_LARGE_WINDOW_ZIP_FILE).await.unwrap();
+
+    let zip = ZipFileReader::new(content.clone()).await.unwrap();
+    let mut buffer = String::new();
+    zip.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "hello, zstd window");
+
+    let zip = ZipFileReader::with_options(content.clone(), ReadOptions::new().zstd_max_window_log(24)).await.unwrap();
+    zip.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut String::new()).await.unwrap();
+
+    let zip = ZipFileReader::with_options(content, ReadOptions::new().zstd_max_window_log(20)).await.unwrap();
+    let result = zip.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut String::new()).await;
+    assert!(matches!(result.unwrap_err().without_entry(), ZipError::WindowTooLarge));
+}
+
+#[cfg(feature = "zstd")]
+#[tokio::test]
+async fn decompress_zstd_max_window_log_single_segment_mem() {
+    use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    // Single-segment frames have no window descriptor, their window instead spanning their content size.
+    let frame = |descriptor: u8, content_size: &[u8]| {
+        let mut frame = vec![0x28, 0xB5, 0x2F, 0xFD, descriptor];
+        frame.extend_from_slice(content_size);
+        // A single raw block holding the data, marked as the last block.
+        frame.extend_from_slice(&[(5 << 3) | 1, 0, 0]);
+        frame.extend_from_slice(b"hello");
+        frame
+    };
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let crc = crc32fast::hash(b"hello");
+    for (name, data) in [("small", frame(0x20, &[5])), ("large", frame(0xA0, &(1u32 << 21).to_le_bytes()))] {
+        let builder =
+            ZipEntryBuilder::new(name.into(), Compression::Zstd).with_crc_and_sizes(crc, data.len() as u64, 5);
+        writer.write_entry_raw(builder, data.as_slice()).await.unwrap();
+    }
+    let content = writer.close().await.unwrap();
+
+    let zip = ZipFileReader::with_options(content, ReadOptions::new().zstd_max_window_log(20)).await.unwrap();
+    let mut buffer = String::new();
+    zip.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "hello");
+
+    let result = zip.reader_with_entry(1).await.unwrap().read_to_string_checked(&mut String::new()).await;
+    assert!(matches!(result.unwrap_err().without_entry(), ZipError::WindowTooLarge));
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn compressed_consumed_deflate_mem() {
+    use futures_util::io::AsyncReadExt;
+
+    let content = tokio::fs::read(DEFLATE_ZIP_FILE).await.unwrap();
+    let zip = async_zip::base::read::mem::ZipFileReader::new(content).await.unwrap();
+    let index =
+        (0..zip.file().entries().len()).max_by_key(|&index| zip.file().entries()[index].compressed_size()).unwrap();
+    let compressed_size = zip.file().entries()[index].compressed_size();
+
+    let mut reader = zip.reader_with_entry(index).await.unwrap();
+    assert_eq!(reader.compressed_consumed(), 0);
+
+    let mut buffer = [0; 16];
+    reader.read_exact(&mut buffer).await.unwrap();
+    let consumed = reader.compressed_consumed();
+    assert!(consumed > 0 && consumed <= compressed_size);
+
+    reader.read_to_end_checked(&mut Vec::new()).await.unwrap();
+    assert_eq!(reader.compressed_consumed(), compressed_size);
+}
+
+#[cfg(feature = "bzip2")]
+#[tokio::test]
+async fn decompress_bzip2_zip_mem() {
+    let content = tokio::fs::read(BZIP2_ZIP_FILE).await.unwrap();
+    common::check_decompress_mem(content).await
+}
+
+#[cfg(feature = "lzma")]
+#[tokio::test]
+async fn decompress_lzma_zip_mem() {
+    let content = tokio::fs::read(LZMA_ZIP_FILE).await.unwrap();
+    common::check_decompress_mem(content).await
+}
+
 #[tokio::test]
 async fn decompress_store_zip_mem() {
     let content = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
@@ -68,12 +214,41 @@ async fn decompress_deflate_zip_fs() {
     common::check_decompress_fs(DEFLATE_ZIP_FILE).await
 }
 
+#[cfg(feature = "bzip2")]
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn decompress_bzip2_zip_fs() {
+    common::check_decompress_fs(BZIP2_ZIP_FILE).await
+}
+
+#[cfg(feature = "lzma")]
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn decompress_lzma_zip_fs() {
+    common::check_decompress_fs(LZMA_ZIP_FILE).await
+}
+
 #[cfg(feature = "tokio-fs")]
 #[tokio::test]
 async fn decompress_store_zip_fs() {
     common::check_decompress_fs(STORE_ZIP_FILE).await
 }
 
+#[cfg(feature = "smol")]
+#[test]
+fn decompress_store_zip_smol_fs() {
+    futures::executor::block_on(async {
+        let reader = async_zip::smol::read::fs::ZipFileReader::new(STORE_ZIP_FILE).await.unwrap();
+        let index = reader.file().entry_index("sample_data/alpha/back_to_front.txt").unwrap();
+
+        let mut output = String::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut output).await.unwrap();
+
+        let expected = std::fs::read_to_string("tests/test_inputs/sample_data/alpha/back_to_front.txt").unwrap();
+        assert_eq!(output, expected);
+    })
+}
+
 #[tokio::test]
 async fn decompress_zip_with_utf8_extra() {
     let file = tokio::fs::File::open(UTF8_EXTRA_ZIP_FILE).await.unwrap();
@@ -84,3 +259,1712 @@ async fn decompress_zip_with_utf8_extra() {
     assert_eq!(zip_entries[0].filename().as_str().unwrap(), "\u{4E2D}\u{6587}.txt");
     assert_eq!(zip_entries[0].filename().alternative(), Some(b"\xD6\xD0\xCe\xC4.txt".as_ref()));
 }
+
+#[cfg(not(feature = "zstd"))]
+#[tokio::test]
+async fn unsupported_zstd_zip_seek() {
+    let file = tokio::fs::File::open(ZSTD_ZIP_FILE).await.unwrap();
+    let mut file_compat = file.compat();
+    let mut zip = async_zip::base::read::seek::ZipFileReader::new(&mut file_compat).await.unwrap();
+
+    let index = zip.file().entries().iter().position(|entry| !entry.dir().unwrap()).unwrap();
+    let entry = &zip.file().entries()[index];
+    assert_eq!(entry.compression(), async_zip::Compression::Unsupported(93));
+    assert!(!entry.is_supported());
+
+    let err = zip.reader_without_entry(index).await.err().unwrap();
+    assert!(matches!(err.without_entry(), async_zip::error::ZipError::CompressionNotSupported(93)));
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_redundant_directories_fs() {
+    use std::path::PathBuf;
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let reader = async_zip::tokio::read::fs::ZipFileReader::new(REDUNDANT_DIRS_ZIP_FILE).await.unwrap();
+
+    let mut written: Vec<PathBuf> = Vec::new();
+    reader.extractor().on_entry_written(|path| written.push(path.to_owned())).extract_to(out_dir.path()).await.unwrap();
+
+    let expected: Vec<PathBuf> =
+        ["dir", "dir/a.txt", "dir/sub", "dir/sub/b.txt"].iter().map(|path| out_dir.path().join(path)).collect();
+    assert_eq!(written, expected);
+    assert_eq!(std::fs::read_to_string(out_dir.path().join("dir/sub/b.txt")).unwrap(), "beta");
+}
+
+#[tokio::test]
+async fn central_directory_bytes_mem() {
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.unwrap();
+
+    let cd_bytes = reader.central_directory_bytes().await.unwrap();
+    let offset = reader.file().central_directory_offset() as usize;
+    let size = reader.file().central_directory_size() as usize;
+
+    assert!(cd_bytes.starts_with(&[0x50, 0x4b, 0x01, 0x02]));
+    assert_eq!(cd_bytes, &data[offset..offset + size]);
+}
+
+#[tokio::test]
+async fn decompress_zero_crc_zip_mem() {
+    use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+    use async_zip::error::ZipError;
+
+    let data = tokio::fs::read(ZERO_CRC_ZIP_FILE).await.unwrap();
+    let expected = "This entry was written without a CRC32 value.\n";
+
+    let reader = ZipFileReader::new(data.clone()).await.unwrap();
+    let mut output = String::new();
+    let err = reader.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut output).await.unwrap_err();
+    assert!(matches!(err, ZipError::EntryError { index: 0, .. }));
+    assert!(matches!(err.without_entry(), ZipError::CRC32CheckError { .. }));
+
+    // The mismatch raised by the final read is also attributed to the entry.
+    let mut reader = reader.reader_with_entry(0).await.unwrap();
+    let err = ZipError::from(futures_util::io::copy(&mut reader, &mut futures_util::io::sink()).await.unwrap_err());
+    assert!(matches!(err, ZipError::EntryError { index: 0, .. }));
+
+    let reader = ZipFileReader::with_options(data, ReadOptions::new().trust_zero_crc()).await.unwrap();
+    let mut output = String::new();
+    reader.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut output).await.unwrap();
+    assert_eq!(output, expected);
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_zero_crc_zip_fs() {
+    use async_zip::base::read::ReadOptions;
+    use async_zip::tokio::read::fs::ZipFileReader;
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let options = ReadOptions::new().trust_zero_crc();
+    let reader = ZipFileReader::with_options(ZERO_CRC_ZIP_FILE, options).await.unwrap();
+    reader.extractor().extract_to(out_dir.path()).await.unwrap();
+
+    let output = std::fs::read_to_string(out_dir.path().join("zero_crc.txt")).unwrap();
+    assert_eq!(output, "This entry was written without a CRC32 value.\n");
+}
+
+#[tokio::test]
+async fn find_entries_glob_mem() {
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let file = reader.file();
+
+    let names = |indices: Vec<usize>| -> Vec<&str> {
+        indices.into_iter().map(|index| file.entries()[index].filename().as_str().unwrap()).collect()
+    };
+
+    let mut txt = names(file.find_entries("**/*.txt"));
+    txt.sort();
+    assert_eq!(
+        txt,
+        [
+            "sample_data/alpha/back_to_front.txt",
+            "sample_data/alpha/front_to_back.txt",
+            "sample_data/numeric/forward.txt",
+            "sample_data/numeric/reverse.txt"
+        ]
+    );
+
+    assert_eq!(names(file.find_entries("sample_data/*/f*.txt")).len(), 2);
+    assert_eq!(names(file.find_entries("sample_data/[!a]*/")), ["sample_data/numeric/"]);
+    assert!(file.find_entries("*.txt").is_empty());
+}
+
+#[tokio::test]
+async fn find_entries_glob_pathological_mem() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    let name = format!("{}/{}", "a".repeat(64), "a".repeat(64));
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.write_entry_whole(ZipEntryBuilder::new(name.into(), Compression::Stored), b"").await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    // These would take exponential time to reject with a backtracking matcher.
+    assert!(reader.file().find_entries(&format!("{}b", "*a".repeat(16))).is_empty());
+    assert!(reader.file().find_entries(&format!("{}b", "**a".repeat(16))).is_empty());
+    assert_eq!(reader.file().find_entries(&format!("{}/**a", "*a".repeat(16))), [0]);
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_on_metadata_fs() {
+    use std::path::PathBuf;
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let reader = async_zip::tokio::read::fs::ZipFileReader::new(STORE_ZIP_FILE).await.unwrap();
+
+    let mut calls: Vec<(String, u32, PathBuf, bool)> = Vec::new();
+    reader
+        .extractor()
+        .on_metadata(|entry, path| {
+            let name = entry.filename().as_str().unwrap().to_owned();
+            calls.push((name, entry.external_file_attribute(), path.to_owned(), path.exists()));
+        })
+        .extract_to(out_dir.path())
+        .await
+        .unwrap();
+
+    assert_eq!(calls.len(), reader.file().entries().len());
+    for ((name, attribute, path, existed), entry) in calls.into_iter().zip(reader.file().entries()) {
+        assert_eq!(name, entry.filename().as_str().unwrap());
+        assert_eq!(attribute, entry.external_file_attribute());
+        assert_eq!(path, out_dir.path().join(name.trim_end_matches('/')));
+        assert!(existed);
+    }
+}
+
+#[tokio::test]
+async fn extraction_footprint_seek() {
+    let file = tokio::fs::File::open(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::seek::ZipFileReader::new(file.compat()).await.unwrap();
+
+    assert_eq!(reader.file().extraction_footprint().unwrap(), (1006, 4, 3));
+}
+
+#[tokio::test]
+async fn extraction_footprint_counts_attribute_directories() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let opts = ZipEntryBuilder::new("dir".to_string().into(), Compression::Stored).unix_permissions(0o40755);
+    writer.write_entry_whole(opts, b"").await.unwrap();
+    let opts = ZipEntryBuilder::new("file.txt".to_string().into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"Data").await.unwrap();
+    let data = writer.close().await.unwrap();
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    assert_eq!(reader.file().extraction_footprint().unwrap(), (4, 1, 1));
+}
+
+#[tokio::test]
+async fn detect_unsafe_entries_mem() {
+    use async_zip::UnsafeReason;
+
+    let data = tokio::fs::read(UNSAFE_ENTRIES_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let reasons: Vec<_> = reader.file().entries().iter().map(|entry| entry.unsafe_reason()).collect();
+
+    assert_eq!(
+        reasons,
+        [
+            Some(UnsafeReason::AbsolutePath),
+            Some(UnsafeReason::AbsolutePath),
+            Some(UnsafeReason::ParentComponent),
+            Some(UnsafeReason::ReservedName),
+            Some(UnsafeReason::NulByte),
+            Some(UnsafeReason::Symlink),
+            None,
+        ]
+    );
+    assert!(!reader.file().entries()[6].is_potentially_unsafe());
+}
+
+#[tokio::test]
+async fn detect_reserved_names_with_trailing_characters_mem() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, UnsafeReason, ZipEntryBuilder};
+
+    let names = ["CON  ", "NUL. .", "dir/aux .txt", "com1 . ", "CONSOLE", "CON_"];
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for name in names {
+        writer.write_entry_whole(ZipEntryBuilder::new(name.into(), Compression::Stored), b"").await.unwrap();
+    }
+    let reader = async_zip::base::read::mem::ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    let reasons: Vec<_> = reader.file().entries().iter().map(|entry| entry.unsafe_reason()).collect();
+    let reserved = Some(UnsafeReason::ReservedName);
+    assert_eq!(reasons, [reserved, reserved, reserved, reserved, None, None]);
+}
+
+#[tokio::test]
+async fn detect_drive_prefixed_names_mem() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, UnsafeReason, ZipEntryBuilder};
+
+    let names = ["C:\\x", "C:/x", "dir/D:/x", "dir\\d:x", "dir/file:stream", "1:/x"];
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for name in names {
+        writer.write_entry_whole(ZipEntryBuilder::new(name.into(), Compression::Stored), b"").await.unwrap();
+    }
+    let reader = async_zip::base::read::mem::ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    let reasons: Vec<_> = reader.file().entries().iter().map(|entry| entry.unsafe_reason()).collect();
+    let absolute = Some(UnsafeReason::AbsolutePath);
+    assert_eq!(reasons, [absolute, absolute, absolute, absolute, None, None]);
+}
+
+#[tokio::test]
+async fn decompress_mixed_methods_interleaved_seek() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use futures_util::io::{AsyncReadExt, Cursor};
+
+    #[allow(unused_mut)]
+    let mut methods = vec![Compression::Stored];
+    #[cfg(feature = "zstd")]
+    methods.push(Compression::Zstd);
+    #[cfg(feature = "deflate")]
+    methods.push(Compression::Deflate);
+    methods.push(Compression::Stored);
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for (index, method) in methods.iter().enumerate() {
+        let data = format!("Entry {index} compressed with {method:?}.\n").repeat(64);
+        let opts = ZipEntryBuilder::new(format!("{index}.txt").into(), *method);
+        writer.write_entry_whole(opts, data.as_bytes()).await.unwrap();
+    }
+    let data = writer.close().await.unwrap();
+    let mut reader = ZipFileReader::new(Cursor::new(data)).await.unwrap();
+
+    // Partially read each entry in reverse before fully reading them all in an interleaved order.
+    for index in (0..methods.len()).rev() {
+        let mut buffer = [0; 16];
+        reader.reader_without_entry(index).await.unwrap().read_exact(&mut buffer).await.unwrap();
+    }
+
+    let order: Vec<usize> = (0..methods.len()).step_by(2).chain((1..methods.len()).step_by(2)).collect();
+    for index in order {
+        let mut output = String::new();
+        let mut entry_reader = reader.reader_with_entry(index).await.unwrap();
+        entry_reader.read_to_string_checked(&mut output).await.unwrap();
+        assert_eq!(output, format!("Entry {index} compressed with {:?}.\n", methods[index]).repeat(64));
+    }
+}
+
+#[tokio::test]
+async fn missing_zip64_extra_field_mem() {
+    use async_zip::error::ZipError;
+
+    let data = tokio::fs::read(MISSING_ZIP64_EXTRA_ZIP_FILE).await.unwrap();
+    let result = async_zip::base::read::mem::ZipFileReader::new(data).await;
+    assert!(matches!(result, Err(ZipError::MissingZip64ExtraField)));
+}
+
+#[tokio::test]
+async fn scan_recursive_nested_mem() {
+    use async_zip::base::read::mem::{scan_recursive, ZipFileReader};
+
+    let data = tokio::fs::read(NESTED_ZIP_FILE).await.unwrap();
+    let reader = ZipFileReader::new(data).await.unwrap();
+
+    let mut visited = Vec::new();
+    scan_recursive(&reader, 1, u64::MAX, |depth, entry| {
+        visited.push((depth, entry.filename().as_str().unwrap().to_owned()))
+    })
+    .await
+    .unwrap();
+
+    let expected = [(0, "top.txt"), (0, "inner.zip"), (1, "middle.txt"), (1, "innermost.zip")];
+    assert_eq!(visited, expected.map(|(depth, name)| (depth, name.to_owned())));
+
+    let mut visited = Vec::new();
+    scan_recursive(&reader, 2, u64::MAX, |depth, _| visited.push(depth)).await.unwrap();
+    assert_eq!(visited, [0, 0, 1, 1, 2]);
+
+    // Nested ZIP files which are too large to be read into memory are visited as regular entries.
+    let mut visited = Vec::new();
+    scan_recursive(&reader, 2, 8, |depth, _| visited.push(depth)).await.unwrap();
+    assert_eq!(visited, [0, 0]);
+}
+
+#[tokio::test]
+async fn scan_recursive_skips_unparsable_nested_mem() {
+    use async_zip::base::read::mem::{scan_recursive, ZipFileReader};
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    // An entry which starts with a local file header signature, but isn't a ZIP file.
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let opts = ZipEntryBuilder::new("fake.zip".to_string().into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"PK\x03\x04 but nothing else").await.unwrap();
+    let opts = ZipEntryBuilder::new("after.txt".to_string().into(), Compression::Stored);
+    writer.write_entry_whole(opts, b"Still visited.").await.unwrap();
+    let reader = ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    let mut visited = Vec::new();
+    scan_recursive(&reader, 2, u64::MAX, |depth, entry| {
+        visited.push((depth, entry.filename().as_str().unwrap().to_owned()))
+    })
+    .await
+    .unwrap();
+    assert_eq!(visited, [(0, "fake.zip".to_owned()), (0, "after.txt".to_owned())]);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_large_entry_to_file_seek() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use futures_util::io::AsyncWriteExt;
+    use tokio_util::compat::TokioAsyncWriteCompatExt;
+
+    const CHUNK_SIZE: usize = 64 * 1024;
+    const CHUNKS: usize = 256;
+
+    let dir = tempfile::tempdir().unwrap();
+    let zip_path = dir.path().join("large.zip");
+    let chunk: Vec<u8> = (0..CHUNK_SIZE).map(|index| (index % 251) as u8).collect();
+
+    let file = tokio::fs::File::create(&zip_path).await.unwrap();
+    let mut writer = ZipFileWriter::new(file.compat_write());
+    let opts = ZipEntryBuilder::new("large.bin".into(), Compression::Deflate);
+    let mut entry_writer = writer.write_entry_stream(opts).await.unwrap();
+    for _ in 0..CHUNKS {
+        entry_writer.write_all(&chunk).await.unwrap();
+    }
+    entry_writer.close().await.unwrap();
+    writer.close().await.unwrap().close().await.unwrap();
+
+    let file = tokio::fs::File::open(&zip_path).await.unwrap();
+    let mut reader = ZipFileReader::new(file.compat()).await.unwrap();
+    let mut entry_reader = reader.reader_with_entry(0).await.unwrap();
+    assert!(entry_reader.entry().compressed_size() < entry_reader.entry().uncompressed_size());
+
+    let out_path = dir.path().join("large.bin");
+    let mut out_file = tokio::fs::File::create(&out_path).await.unwrap().compat_write();
+    let copied = futures_util::io::copy(&mut entry_reader, &mut out_file).await.unwrap();
+    out_file.close().await.unwrap();
+
+    assert_eq!(copied, (CHUNK_SIZE * CHUNKS) as u64);
+    assert_eq!(entry_reader.compute_hash(), entry_reader.entry().crc32());
+    assert_eq!(std::fs::metadata(&out_path).unwrap().len(), copied);
+}
+
+#[cfg(all(feature = "deflate", feature = "tokio-fs"))]
+#[tokio::test]
+async fn decompress_spill_threshold_fs() {
+    use async_zip::base::read::ReadOptions;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    let large: Vec<u8> = (0..1024 * 1024u64).map(|index| ((index * index % 251) ^ (index / 7)) as u8).collect();
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.write_entry_whole(ZipEntryBuilder::new("large.bin".into(), Compression::Deflate), &large).await.unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("small.txt".into(), Compression::Deflate), b"Small.").await.unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let zip_path = dir.path().join("spill.zip");
+    tokio::fs::write(&zip_path, writer.close().await.unwrap()).await.unwrap();
+
+    // Only the large entry exceeds the threshold, so it's decompressed into a temporary file upfront.
+    let options = ReadOptions::new().spill_threshold(64 * 1024);
+    let reader = ZipFileReader::with_options(&zip_path, options).await.unwrap();
+    assert!(reader.file().entries()[0].compressed_size() < large.len() as u64);
+
+    let mut data = Vec::new();
+    let mut entry_reader = reader.reader_with_entry(0).await.unwrap();
+    entry_reader.read_to_end_checked(&mut data).await.unwrap();
+    assert_eq!(data, large);
+    assert_eq!(entry_reader.compressed_consumed(), large.len() as u64);
+
+    let mut data = Vec::new();
+    futures_util::io::AsyncReadExt::read_to_end(&mut reader.reader_without_entry(1).await.unwrap(), &mut data)
+        .await
+        .unwrap();
+    assert_eq!(data, b"Small.");
+}
+
+#[tokio::test]
+async fn decompress_unicode_comment_extra_mem() {
+    let data = tokio::fs::read(UNICODE_COMMENT_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let entry = &reader.file().entries()[0];
+
+    assert_eq!(entry.unicode_comment().as_deref(), Some("caf\u{e9} comment"));
+    assert_eq!(entry.comment().as_str().unwrap(), "caf\u{e9} comment");
+    assert_eq!(entry.comment().alternative(), Some(b"caf\x82 comment".as_ref()));
+    assert_eq!(entry.comment_bytes(), b"caf\x82 comment");
+    assert_eq!(entry.comment_lossy(), "caf\u{e9} comment");
+}
+
+#[tokio::test]
+async fn decompress_extended_timestamp_mem() {
+    let data = tokio::fs::read(EXTENDED_TIMESTAMP_ZIP_FILE).await.unwrap();
+
+    // Info-ZIP writes the modification and access times locally, but only the former centrally (with the same flags).
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.unwrap();
+    let timestamp = reader.file().entries()[0].extended_timestamp().unwrap();
+    assert_eq!((timestamp.modified(), timestamp.accessed(), timestamp.created()), (Some(1686856496), None, None));
+
+    let reader = async_zip::base::read::stream::ZipFileReader::new(data.as_slice());
+    let reader = reader.next_with_entry().await.unwrap().unwrap();
+    let timestamp = reader.reader().entry().extended_timestamp().unwrap();
+    assert_eq!(
+        (timestamp.modified(), timestamp.accessed(), timestamp.created()),
+        (Some(1686856496), Some(1792043396), None)
+    );
+}
+
+#[tokio::test]
+async fn decompress_binary_entry_comment_mem() {
+    let data = tokio::fs::read(BINARY_ENTRY_COMMENT_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let entry = &reader.file().entries()[0];
+
+    assert!(entry.comment().as_str().is_err());
+    assert_eq!(entry.comment_bytes(), b"\xff\xfebinary\x00comment\x80");
+    assert_eq!(entry.comment_lossy(), "\u{fffd}\u{fffd}binary\u{0}comment\u{fffd}");
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn decompress_from_std_file_seek() {
+    let file = std::fs::File::open(STORE_ZIP_FILE).unwrap();
+    let mut reader = async_zip::tokio::read::seek::ZipFileReader::new_from_std_file(file).await.unwrap();
+
+    let entry = reader.file().entries().iter().position(|entry| !entry.dir().unwrap()).unwrap();
+    let mut data = Vec::new();
+    reader.reader_with_entry(entry).await.unwrap().read_to_end_checked(&mut data).await.unwrap();
+    assert!(!data.is_empty());
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn list_then_extract_subset_fs() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let reader = async_zip::tokio::read::fs::ZipFileReader::new(REDUNDANT_DIRS_ZIP_FILE).await.unwrap();
+
+    let chosen: Vec<usize> = reader
+        .file()
+        .entries()
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.filename().as_str().unwrap().ends_with("b.txt"))
+        .map(|(index, _)| index)
+        .collect();
+    assert_eq!(chosen.len(), 1);
+
+    reader.extractor().only(chosen).extract_to(out_dir.path()).await.unwrap();
+
+    assert_eq!(std::fs::read_to_string(out_dir.path().join("dir/sub/b.txt")).unwrap(), "beta");
+    assert!(!out_dir.path().join("dir/a.txt").exists());
+}
+
+#[tokio::test]
+async fn decompress_under_reported_comment_length_mem() {
+    let data = tokio::fs::read(SHORT_COMMENT_LENGTH_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+
+    assert_eq!(reader.file().comment().as_str().unwrap(), "archive comment");
+
+    let mut buffer = String::new();
+    reader.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "Alpha");
+}
+
+#[tokio::test]
+async fn decompress_with_progress_mem() {
+    use futures_util::AsyncReadExt;
+
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+
+    for index in 0..reader.file().entries().len() {
+        let reported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_reported = reported.clone();
+        let mut entry_reader = reader
+            .reader_with_entry(index)
+            .await
+            .unwrap()
+            .with_progress(move |read| hook_reported.lock().unwrap().push(read));
+
+        let mut buffer = [0; 16];
+        while entry_reader.read(&mut buffer).await.unwrap() != 0 {
+            assert_eq!(entry_reader.read(&mut []).await.unwrap(), 0);
+        }
+        assert_eq!(entry_reader.read(&mut buffer).await.unwrap(), 0);
+        drop(entry_reader);
+
+        let reported = reported.lock().unwrap();
+        let uncompressed_size = reader.file().entries()[index].uncompressed_size();
+        let deltas: Vec<u64> = std::iter::once(0).chain(reported.iter().copied()).collect();
+        let deltas: Vec<u64> = deltas.windows(2).map(|pair| pair[1] - pair[0]).collect();
+
+        // Each read which returned data is reported, followed by a single report at EOF.
+        assert_eq!(deltas.iter().sum::<u64>(), uncompressed_size);
+        assert_eq!(reported.len() as u64, uncompressed_size.div_ceil(16) + 1);
+        assert_eq!(deltas.last(), Some(&0));
+    }
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_into_chunks_mem() {
+    use futures_util::TryStreamExt;
+
+    let data = tokio::fs::read(DEFLATE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+
+    for index in 0..reader.file().entries().len() {
+        let mut expected = Vec::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut expected).await.unwrap();
+
+        for chunk_size in [1, 7, 64 * 1024] {
+            let stream = reader.reader_with_entry(index).await.unwrap().into_chunks(chunk_size);
+            let chunks: Vec<bytes::Bytes> = stream.try_collect().await.unwrap();
+
+            if let Some((last, rest)) = chunks.split_last() {
+                assert!(rest.iter().all(|chunk| chunk.len() == chunk_size));
+                assert!(!last.is_empty() && last.len() <= chunk_size);
+            }
+            assert_eq!(chunks.concat(), expected);
+        }
+    }
+}
+
+#[tokio::test]
+async fn read_entry_to_string_mem() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer
+        .write_entry_whole(ZipEntryBuilder::new("config.toml".into(), Compression::Stored), b"key = 1")
+        .await
+        .unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("binary".into(), Compression::Stored), &[0xC3, 0x28]).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(writer.close().await.unwrap()).await.unwrap();
+
+    assert_eq!(reader.read_entry_to_string(0, 7).await.unwrap(), "key = 1");
+    assert!(matches!(reader.read_entry_to_string(0, 6).await.unwrap_err().without_entry(), ZipError::EntryTooLarge(6)));
+    assert!(matches!(reader.read_entry_to_string(1, 1024).await.unwrap_err().without_entry(), ZipError::InvalidUtf8));
+}
+
+#[tokio::test]
+async fn decompress_trailing_data_mem() {
+    let data = tokio::fs::read(TRAILING_DATA_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+
+    assert!(reader.has_trailing_data());
+    assert_eq!(reader.file().trailing_bytes(), "appended after the EOCDR".len() as u64);
+    assert_eq!(reader.file().comment().as_str().unwrap(), "archive comment");
+
+    let mut buffer = String::new();
+    reader.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "Alpha");
+
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    assert!(!reader.has_trailing_data());
+}
+
+#[tokio::test]
+async fn decompress_verify_mem() {
+    use async_zip::error::ZipError;
+
+    // The data of the second entry has had a byte changed after the ZIP file was written.
+    let data = tokio::fs::read(CORRUPTED_CRC_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let report = reader.verify().await.unwrap();
+
+    assert!(!report.is_ok());
+    assert_eq!(report.verified(), [0, 2]);
+    assert!(report.skipped().is_empty());
+    assert!(matches!(report.failed(), [(1, err)] if matches!(err.without_entry(), ZipError::CRC32CheckError { .. })));
+
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let report = reader.verify().await.unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.verified().len(), reader.file().entries().len());
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_verify_encrypted_mem() {
+    use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+
+    let data = tokio::fs::read(ZIPCRYPTO_ENCRYPTED_ZIP_FILE).await.unwrap();
+    let reader = ZipFileReader::new(data.clone()).await.unwrap();
+    let report = reader.verify().await.unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.skipped(), [0, 1, 2]);
+
+    let reader = ZipFileReader::with_options(data, ReadOptions::new().password(b"password".to_vec())).await.unwrap();
+    let report = reader.verify().await.unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.verified(), [0, 1, 2]);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_repair_mem() {
+    let data = tokio::fs::read(ZEROED_CENTRAL_DIRECTORY_ZIP_FILE).await.unwrap();
+    assert!(async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.is_err());
+
+    let reader = async_zip::base::read::mem::ZipFileReader::repair(data).await.unwrap();
+    let names: Vec<_> = reader.entry_names().collect();
+    assert_eq!(names, ["alpha.txt", "beta.txt", "gamma.txt"]);
+
+    let expected = ["Alpha".to_string(), "Beta ".repeat(200), "Gamma".to_string()];
+    for (index, expected) in expected.iter().enumerate() {
+        let mut buffer = String::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, expected);
+        assert_eq!(reader.file().entries()[index].uncompressed_size(), expected.len() as u64);
+    }
+
+    // An intact ZIP file is repaired into the same entries as are listed within its central directory.
+    let data = tokio::fs::read(DEFLATE_ZIP_FILE).await.unwrap();
+    let repaired = async_zip::base::read::mem::ZipFileReader::repair(data.clone()).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    assert!(repaired.entry_names().eq(reader.entry_names()));
+    for index in 0..repaired.file().entries().len() {
+        let mut buffer = Vec::new();
+        repaired.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+    }
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_entries_with_ranges_mem() {
+    use futures_util::io::AsyncReadExt;
+
+    let data = tokio::fs::read(DEFLATE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.unwrap();
+
+    let ranges = reader.entries_with_ranges().await.unwrap();
+    assert_eq!(ranges.len(), reader.file().entries().len());
+
+    for (index, (entry, range)) in ranges.into_iter().enumerate() {
+        assert_eq!(entry.filename().as_bytes(), reader.file().entries()[index].filename().as_bytes());
+        assert_eq!(range.end - range.start, entry.compressed_size());
+
+        let mut raw = Vec::new();
+        reader.raw_reader_without_entry(index).await.unwrap().read_to_end(&mut raw).await.unwrap();
+        assert_eq!(&data[range.start as usize..range.end as usize], raw.as_slice());
+    }
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_with_progress_fs() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let reader = async_zip::tokio::read::fs::ZipFileReader::new(STORE_ZIP_FILE).await.unwrap();
+
+    let mut updates = Vec::new();
+    reader
+        .extractor()
+        .extract_to_with_progress(out_dir.path(), |written, total| updates.push((written, total)))
+        .await
+        .unwrap();
+
+    let (written, total) = *updates.last().unwrap();
+    assert_eq!(written, total);
+    assert_eq!(total, 1006);
+    assert!(updates.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+}
+
+#[tokio::test]
+async fn decompress_aligned_stored_entries() {
+    use futures_util::io::Cursor;
+
+    let expected: [(&str, Vec<u8>, u64); 4] = [
+        ("[Content_Types].xml", b"<Types/>".to_vec(), 1),
+        ("resources.arsc", b"ARSC".repeat(16), 4),
+        ("lib/x86/libfoo.so", [b"\x7fELF".as_slice(), &[0; 60]].concat(), 4096),
+        ("classes.dex", b"dex\n035\0".to_vec(), 4),
+    ];
+    let data = tokio::fs::read(ALIGNED_STORED_APK_FILE).await.unwrap();
+
+    // The alignment padding is only present within the local file headers' extra fields.
+    let mut reader = async_zip::base::read::seek::ZipFileReader::new(Cursor::new(data.clone())).await.unwrap();
+    for (index, (name, content, alignment)) in expected.iter().enumerate() {
+        let entry = reader.file().entries()[index].clone();
+        assert_eq!(entry.filename().as_str().unwrap(), *name);
+        assert!(entry.extra_fields().is_empty());
+        assert_eq!(entry.data_offset(&mut Cursor::new(&data)).await.unwrap() % alignment, 0);
+
+        let mut buffer = Vec::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, content);
+    }
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.unwrap();
+    for (index, (_, content, _)) in expected.iter().enumerate() {
+        let mut buffer = Vec::new();
+        reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, content);
+    }
+
+    let mut reader = async_zip::base::read::stream::ZipFileReader::new(data.as_slice());
+    for (name, content, _) in expected.iter() {
+        let mut entry = reader.next_with_entry().await.unwrap().unwrap();
+        assert_eq!(entry.reader().entry().filename().as_str().unwrap(), *name);
+
+        let mut buffer = Vec::new();
+        entry.reader_mut().read_to_end_checked(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, content);
+        reader = entry.done().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn stream_verify_against_central_directory() {
+    use async_zip::base::read::stream::{verify_against_central_directory, ZipFileReader};
+    use async_zip::error::ZipError;
+    use futures_util::io::Cursor;
+
+    for (path, expected_mismatch) in [(STORE_ZIP_FILE, None), (LOCAL_CENTRAL_MISMATCH_ZIP_FILE, Some(1))] {
+        let mut source = Cursor::new(tokio::fs::read(path).await.unwrap());
+        let mut zip = ZipFileReader::new(&mut source);
+        let mut entries = Vec::new();
+
+        while let Some(entry) = zip.next_with_entry().await.unwrap() {
+            entries.push(entry.reader().entry().clone());
+            zip = entry.skip().await.unwrap();
+        }
+
+        let result = verify_against_central_directory(&mut source, &entries).await;
+        match expected_mismatch {
+            None => result.unwrap(),
+            Some(index) => assert!(matches!(result, Err(ZipError::CentralDirectoryMismatch(i)) if i == index)),
+        }
+    }
+}
+
+#[tokio::test]
+async fn stream_finish_verified() {
+    use async_zip::base::read::stream::ZipFileReader;
+    use async_zip::error::ZipError;
+    use futures_util::io::Cursor;
+
+    for (path, expected_mismatch) in [(STORE_ZIP_FILE, None), (LOCAL_CENTRAL_MISMATCH_ZIP_FILE, Some(1))] {
+        let data = tokio::fs::read(path).await.unwrap();
+
+        // Only the first entry is opened, with the remaining entries being read past by finish_verified().
+        let zip = ZipFileReader::new(Cursor::new(data));
+        let zip = zip.next_with_entry().await.unwrap().unwrap().skip().await.unwrap();
+
+        let result = zip.finish_verified().await;
+        match expected_mismatch {
+            None => assert!(result.is_ok()),
+            Some(index) => assert!(matches!(result, Err(ZipError::CentralDirectoryMismatch(i)) if i == index)),
+        }
+    }
+}
+
+#[tokio::test]
+async fn stream_verify_rejects_zeroed_crc_without_data_descriptor() {
+    use async_zip::base::read::stream::{verify_against_central_directory, ZipFileReader};
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use futures_util::io::Cursor;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    writer.write_entry_whole(ZipEntryBuilder::new("a.txt".into(), Compression::Stored), b"Alpha").await.unwrap();
+    let mut data = writer.close().await.unwrap();
+    // Zero the CRC32 value within the local file header, which has no data descriptor to defer it to.
+    data[14..18].fill(0);
+
+    let mut source = Cursor::new(data);
+    let zip = ZipFileReader::new(&mut source);
+    let entry = zip.next_with_entry().await.unwrap().unwrap();
+    let entries = vec![entry.reader().entry().clone()];
+    entry.skip().await.unwrap();
+
+    let result = verify_against_central_directory(&mut source, &entries).await;
+    assert!(matches!(result, Err(ZipError::CentralDirectoryMismatch(0))));
+}
+
+#[tokio::test]
+async fn decompress_raw_name_bytes_mem() {
+    let data = tokio::fs::read(RAW_NAME_BYTES_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.unwrap();
+    let entry = &reader.file().entries()[0];
+
+    assert_eq!(entry.raw_name_bytes(), b"\x8e\xa6\x01dir\\name.txt");
+    assert_eq!(entry.filename().as_str().unwrap(), "\u{c4}\u{a6}\u{1}dir\\name.txt");
+
+    let reader = async_zip::base::read::stream::ZipFileReader::new(data.as_slice());
+    let entry = reader.next_with_entry().await.unwrap().unwrap();
+    assert_eq!(entry.reader().entry().raw_name_bytes(), b"\x8e\xa6\x01dir\\name.txt");
+}
+
+#[tokio::test]
+async fn entry_index_mem() {
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+
+    for (index, entry) in reader.file().entries().iter().enumerate() {
+        assert_eq!(reader.file().entries().get(index).unwrap().index(), index);
+        assert_eq!(
+            reader.reader_with_entry(entry.index()).await.unwrap().entry().filename().as_bytes(),
+            entry.filename().as_bytes()
+        );
+    }
+}
+
+#[tokio::test]
+async fn decompress_inflated_entry_count_reconciled() {
+    let data = tokio::fs::read(INFLATED_ENTRY_COUNT_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    assert_eq!(reader.file().entry_count_mismatch(), Some(0xFFFE));
+}
+
+#[tokio::test]
+async fn decompress_wrong_entry_count_reconciled() {
+    let data = tokio::fs::read(WRONG_ENTRY_COUNT_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+
+    let names: Vec<_> = reader.file().entries().iter().map(|entry| entry.filename().as_str().unwrap()).collect();
+    assert_eq!(names, ["a.txt", "b.txt", "c.txt"]);
+    assert_eq!(reader.file().entry_count_mismatch(), Some(2));
+
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    assert_eq!(reader.file().entry_count_mismatch(), None);
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_error_names_entry_fs() {
+    use async_zip::error::ZipError;
+    use async_zip::tokio::read::fs::ZipFileReader;
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let reader = ZipFileReader::new(ZERO_CRC_ZIP_FILE).await.unwrap();
+    let err = reader.extractor().extract_to(out_dir.path()).await.unwrap_err();
+
+    assert!(matches!(err, ZipError::EntryError { index: 0, .. }));
+    assert!(matches!(err.without_entry(), ZipError::CRC32CheckError { .. }));
+    assert!(err.to_string().contains("zero_crc.txt"), "{err}");
+}
+
+#[tokio::test]
+async fn decompress_stored_random_access() {
+    use futures_util::io::{AsyncReadExt, AsyncSeekExt, Cursor};
+    use std::io::SeekFrom;
+
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let mut reader = async_zip::base::read::seek::ZipFileReader::new(Cursor::new(data.clone())).await.unwrap();
+    let index = (0..reader.file().entries().len())
+        .max_by_key(|index| reader.file().entries()[*index].uncompressed_size())
+        .unwrap();
+
+    let mut expected = Vec::new();
+    reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut expected).await.unwrap();
+    assert!(expected.len() > 64);
+
+    let offset = expected.len() / 3;
+    let mut stored = reader.stored_reader(index).await.unwrap();
+    assert_eq!(stored.entry().uncompressed_size(), expected.len() as u64);
+    assert_eq!(stored.seek(SeekFrom::Start(offset as u64)).await.unwrap(), offset as u64);
+
+    let mut buffer = [0; 16];
+    stored.read_exact(&mut buffer).await.unwrap();
+    assert_eq!(&buffer, &expected[offset..offset + 16]);
+
+    // Seeks are clamped to the entry's range, and reads stop at its end.
+    assert_eq!(stored.seek(SeekFrom::Current(-(expected.len() as i64) * 2)).await.unwrap(), 0);
+    assert_eq!(stored.seek(SeekFrom::End(8)).await.unwrap(), expected.len() as u64);
+    assert_eq!(stored.seek(SeekFrom::End(-4)).await.unwrap(), expected.len() as u64 - 4);
+
+    let mut tail = Vec::new();
+    stored.read_to_end(&mut tail).await.unwrap();
+    assert_eq!(tail, &expected[expected.len() - 4..]);
+
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let mut stored = reader.stored_reader(index).await.unwrap();
+    stored.seek(SeekFrom::Start(offset as u64)).await.unwrap();
+    stored.read_exact(&mut buffer).await.unwrap();
+    assert_eq!(&buffer, &expected[offset..offset + 16]);
+}
+
+#[tokio::test]
+async fn decompress_stored_entry_reader_seek() {
+    use futures_util::io::{AsyncReadExt, AsyncSeekExt, Cursor};
+    use std::io::SeekFrom;
+
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let mut reader = async_zip::base::read::seek::ZipFileReader::new(Cursor::new(data)).await.unwrap();
+    let index = (0..reader.file().entries().len())
+        .max_by_key(|index| reader.file().entries()[*index].uncompressed_size())
+        .unwrap();
+
+    let mut expected = Vec::new();
+    reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut expected).await.unwrap();
+    let middle = expected.len() / 2;
+
+    // Data is read (and buffered) before seeking, so that seeks must account for the reader's position.
+    let mut entry_reader = reader.reader_with_entry(index).await.unwrap();
+    let mut buffer = [0; 8];
+    entry_reader.read_exact(&mut buffer).await.unwrap();
+    assert_eq!(entry_reader.seek(SeekFrom::Start(middle as u64)).await.unwrap(), middle as u64);
+
+    let mut tail = Vec::new();
+    entry_reader.read_to_end_checked(&mut tail).await.unwrap();
+    assert_eq!(tail, &expected[middle..]);
+
+    // Seeks are clamped to the entry's range.
+    assert_eq!(entry_reader.seek(SeekFrom::Current(-8)).await.unwrap(), expected.len() as u64 - 8);
+    entry_reader.read_exact(&mut buffer).await.unwrap();
+    assert_eq!(&buffer, &expected[expected.len() - 8..]);
+    assert_eq!(entry_reader.seek(SeekFrom::End(8)).await.unwrap(), expected.len() as u64);
+    assert_eq!(entry_reader.seek(SeekFrom::Current(-(expected.len() as i64) * 2)).await.unwrap(), 0);
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_compressed_entry_reader_seek_unsupported() {
+    use futures_util::io::AsyncSeekExt;
+    use std::io::SeekFrom;
+
+    let data = tokio::fs::read(DEFLATE_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let index = reader.file().entries().iter().position(|entry| !entry.dir().unwrap()).unwrap();
+
+    let err = reader.reader_with_entry(index).await.unwrap().seek(SeekFrom::Start(1)).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+
+    let result = reader.stored_reader(index).await;
+    assert!(matches!(result, Err(async_zip::error::ZipError::FeatureNotSupported(_))));
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn verify_and_extract_writes_nothing_on_corruption_fs() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    let mut data = Vec::new();
+    let mut writer = ZipFileWriter::new(futures_util::io::Cursor::new(&mut data));
+    for (name, content) in [("dir/first.txt", b"first entry"), ("second.txt", b"other entry")] {
+        let builder = ZipEntryBuilder::new(name.into(), Compression::Stored);
+        writer.write_entry_whole(builder, content).await.unwrap();
+    }
+    writer.close().await.unwrap();
+
+    // Corrupt the second entry's data so that only its CRC32 check fails.
+    let position = data.windows(11).position(|window| window == b"other entry").unwrap();
+    data[position] = b'O';
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("corrupt.zip");
+    tokio::fs::write(&path, data).await.unwrap();
+
+    let out_dir = temp_dir.path().join("out");
+    tokio::fs::create_dir(&out_dir).await.unwrap();
+
+    let reader = ZipFileReader::new(&path).await.unwrap();
+    let err = reader.extractor().verify_and_extract(&out_dir).await.unwrap_err();
+
+    assert!(matches!(err, ZipError::EntryError { index: 1, .. }));
+    assert!(matches!(err.without_entry(), ZipError::CRC32CheckError { .. }));
+    assert_eq!(std::fs::read_dir(&out_dir).unwrap().count(), 0);
+
+    let reader = ZipFileReader::new(UNSAFE_ENTRIES_ZIP_FILE).await.unwrap();
+    let err = reader.extractor().verify_and_extract(&out_dir).await.unwrap_err();
+
+    assert!(matches!(err.without_entry(), ZipError::UnsafeEntryPath(async_zip::UnsafeReason::AbsolutePath)));
+    assert_eq!(std::fs::read_dir(&out_dir).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn detect_directories_by_attribute() {
+    let data = tokio::fs::read(DIRECTORY_ATTRIBUTES_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let entries = reader.file().entries();
+
+    let dirs: Vec<_> = entries.iter().map(|entry| entry.dir().unwrap()).collect();
+    assert_eq!(dirs, [true, true, true, true, false]);
+
+    let strict_dirs: Vec<_> = entries.iter().map(|entry| entry.dir_strict()).collect();
+    assert_eq!(strict_dirs, [true, true, false, false, false]);
+
+    // The mode is only interpreted for entries whose "version made by" field records a Unix host.
+    assert_eq!(entries[0].unix_permissions(), Some(0o040755));
+    assert_eq!(entries[1].attribute_compatibility(), async_zip::AttributeCompatibility::MsDos);
+    assert_eq!(entries[1].unix_permissions(), None);
+}
+
+#[tokio::test]
+async fn find_entry_by_name_seek() {
+    use async_zip::error::ZipError;
+    use futures_util::io::Cursor;
+
+    let data = tokio::fs::read(STORE_ZIP_FILE).await.unwrap();
+    let mut reader = async_zip::base::read::seek::ZipFileReader::new(Cursor::new(data)).await.unwrap();
+
+    assert_eq!(reader.file().entry_index("sample_data/alpha/back_to_front.txt"), Some(6));
+    assert_eq!(reader.file().entry_index("sample_data/Alpha/Back_To_Front.txt"), None);
+    assert_eq!(reader.file().entry_index_ignore_case("SAMPLE_DATA/Alpha/Back_To_Front.TXT"), Some(6));
+
+    let mut expected = Vec::new();
+    reader.reader_with_entry(3).await.unwrap().read_to_end_checked(&mut expected).await.unwrap();
+
+    let mut buffer = Vec::new();
+    let mut entry_reader = reader.reader_with_entry_by_name("sample_data/numeric/reverse.txt").await.unwrap();
+    entry_reader.read_to_end_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, expected);
+
+    let err = reader.reader_with_entry_by_name("missing.txt").await.err().unwrap();
+    assert!(matches!(err, ZipError::EntryNotFound(name) if name == "missing.txt"));
+}
+
+#[tokio::test]
+async fn decompress_inconsistent_stored_entry() {
+    use async_zip::error::ZipError;
+
+    let data = tokio::fs::read(INCONSISTENT_STORED_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data.clone()).await.unwrap();
+
+    let mut buffer = String::new();
+    reader.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "consistent");
+
+    let err = reader.reader_with_entry(1).await.err().unwrap();
+    assert!(matches!(err.without_entry(), ZipError::InconsistentStoredEntry(17, 5)));
+
+    let reader = async_zip::base::read::stream::ZipFileReader::new(data.as_slice());
+    let entry = reader.next_without_entry().await.unwrap().unwrap();
+    let reader = entry.skip().await.unwrap();
+
+    let err = reader.next_without_entry().await.err().unwrap();
+    assert!(matches!(err, ZipError::InconsistentStoredEntry(17, 5)));
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_prepended_stub_seek() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use futures::io::Cursor;
+
+    const STUB_LENGTH: u64 = 1071;
+    let expected = [
+        ("readme.txt", "This archive has a stub prepended to it.\n".to_string()),
+        ("dir/", String::new()),
+        ("dir/data.txt", "hello from a self-extracting archive\n".repeat(20)),
+    ];
+
+    let data = tokio::fs::read(SFX_PREPENDED_ZIP_FILE).await.unwrap();
+    let mut zip = ZipFileReader::new(Cursor::new(&data)).await.unwrap();
+    assert_eq!(zip.file().prepended_bytes(), STUB_LENGTH);
+    assert_eq!(zip.file().entries()[0].header_offset(), STUB_LENGTH);
+    assert_eq!(zip.central_directory_bytes().await.unwrap()[..4], [0x50, 0x4b, 0x01, 0x02]);
+
+    for (index, (name, content)) in expected.iter().enumerate() {
+        assert_eq!(zip.file().entries()[index].filename().as_str().unwrap(), *name);
+
+        let mut buffer = String::new();
+        zip.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+        assert_eq!(buffer, *content);
+    }
+
+    // Entries appended to the archive are recorded relative to the stub, like the existing ones.
+    let mut writer = ZipFileWriter::append(Cursor::new(data.clone())).await.unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("new.txt".into(), Compression::Deflate), b"appended").await.unwrap();
+    let appended = writer.close().await.unwrap().into_inner();
+
+    let mut zip = ZipFileReader::new(Cursor::new(&appended)).await.unwrap();
+    assert_eq!(zip.file().prepended_bytes(), STUB_LENGTH);
+    assert_eq!(zip.file().entries().len(), 4);
+
+    let mut buffer = String::new();
+    zip.reader_with_entry(3).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "appended");
+
+    // The ZIP64 end of central directory records are located relative to the stub too.
+    let mut writer = ZipFileWriter::new(Vec::new()).force_zip64();
+    writer.write_entry_whole(ZipEntryBuilder::new("zip64.txt".into(), Compression::Deflate), b"zip64").await.unwrap();
+    let mut zip64 = data[..STUB_LENGTH as usize].to_vec();
+    zip64.extend(writer.close().await.unwrap());
+
+    let mut zip = ZipFileReader::new(Cursor::new(&zip64)).await.unwrap();
+    assert!(zip.file().zip64());
+    assert_eq!(zip.file().prepended_bytes(), STUB_LENGTH);
+
+    let mut buffer = String::new();
+    zip.reader_with_entry(0).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "zip64");
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn load_into_memory_fs() {
+    use async_zip::tokio::read::fs::ZipFileReader;
+
+    let reader = ZipFileReader::new(STORE_ZIP_FILE).await.unwrap();
+    let mem_reader = reader.load_into_memory().await.unwrap();
+
+    assert_eq!(mem_reader.data(), std::fs::read(STORE_ZIP_FILE).unwrap());
+    assert_eq!(mem_reader.file().entries().len(), reader.file().entries().len());
+
+    for index in 0..reader.file().entries().len() {
+        let (mut expected, mut actual) = (Vec::new(), Vec::new());
+        reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut expected).await.unwrap();
+        mem_reader.reader_with_entry(index).await.unwrap().read_to_end_checked(&mut actual).await.unwrap();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn entry_handles_bounded_fs() {
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use futures_util::{FutureExt, StreamExt};
+
+    let reader = ZipFileReader::new(STORE_ZIP_FILE).await.unwrap();
+    let mut handles = Box::pin(reader.entry_handles(2));
+
+    let first = handles.next().await.unwrap().unwrap();
+    let second = handles.next().await.unwrap().unwrap();
+    assert!(handles.next().now_or_never().is_none());
+
+    drop(first);
+    let third = handles.next().await.unwrap().unwrap();
+    assert_eq!(third.entry().filename().as_bytes(), reader.file().entries()[2].filename().as_bytes());
+    drop((second, third));
+
+    let mut workers = Vec::new();
+    let mut handles = Box::pin(reader.entry_handles(2));
+    while let Some(handle) = handles.next().await {
+        let mut handle = handle.unwrap();
+        workers.push(tokio::spawn(async move {
+            let mut data = Vec::new();
+            handle.reader_mut().read_to_end_checked(&mut data).await.unwrap();
+            (handle.entry().filename().as_str().unwrap().to_string(), data.len() as u64)
+        }));
+    }
+
+    let mut processed = Vec::new();
+    for worker in workers {
+        processed.push(worker.await.unwrap());
+    }
+    let expected: Vec<_> = reader
+        .file()
+        .entries()
+        .iter()
+        .map(|entry| (entry.filename().as_str().unwrap().to_string(), entry.uncompressed_size()))
+        .collect();
+    assert_eq!(processed, expected);
+}
+
+#[tokio::test]
+async fn decompress_crc_checked_at_eof() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use futures_util::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+    let mut data = Vec::new();
+    let mut writer = ZipFileWriter::new(Cursor::new(&mut data));
+    let builder = ZipEntryBuilder::new("whole.txt".into(), Compression::Stored);
+    writer.write_entry_whole(builder, b"whole entry data").await.unwrap();
+
+    // Stream written entries store their CRC32 value within a data descriptor after the data.
+    let builder = ZipEntryBuilder::new("stream.txt".into(), Compression::Stored);
+    let mut entry_writer = writer.write_entry_stream(builder).await.unwrap();
+    entry_writer.write_all(b"stream entry data").await.unwrap();
+    entry_writer.close().await.unwrap();
+    writer.close().await.unwrap();
+
+    for content in [b"whole entry data".as_slice(), b"stream entry data"] {
+        let position = data.windows(content.len()).position(|window| window == content).unwrap();
+        data[position] ^= 0x20;
+    }
+
+    let mut reader = ZipFileReader::new(Cursor::new(data)).await.unwrap();
+    for index in 0..2 {
+        let expected = reader.file().entries()[index].crc32();
+
+        let mut buffer = Vec::new();
+        let err = reader.reader_without_entry(index).await.unwrap().read_to_end(&mut buffer).await.unwrap_err();
+        match ZipError::from(err) {
+            ZipError::CRC32CheckError { expected: stored, actual } => {
+                assert_eq!(stored, expected);
+                assert_ne!(actual, expected);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+
+        let mut buffer = Vec::new();
+        reader.reader_without_entry_unchecked(index).await.unwrap().read_to_end(&mut buffer).await.unwrap();
+        assert!(buffer.ends_with(b"entry data"));
+    }
+}
+
+#[cfg(all(feature = "aes", feature = "deflate"))]
+#[tokio::test]
+async fn decompress_aes_encrypted() {
+    use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+    use async_zip::error::ZipError;
+
+    let data = std::fs::read(AES_ENCRYPTED_ZIP_FILE).unwrap();
+    let expected: [&[u8]; 2] = [
+        b"Encrypted with AE-1 and stored, so its CRC32 value is also checked.\n",
+        &b"Encrypted with AE-2 and deflated. ".repeat(20),
+    ];
+
+    let zip = ZipFileReader::new(data.clone()).await.unwrap();
+    for (index, expected) in expected.iter().enumerate() {
+        let mut buffer = Vec::new();
+        let mut reader = zip.reader_with_entry_and_password(index, b"password").await.unwrap();
+        reader.read_to_end_checked(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, expected);
+
+        let err = zip.reader_with_entry_and_password(index, b"incorrect").await.err().unwrap();
+        assert!(matches!(err.without_entry(), ZipError::WrongPassword));
+    }
+
+    let zip = ZipFileReader::with_options(data, ReadOptions::new().password(b"password".to_vec())).await.unwrap();
+    let mut buffer = Vec::new();
+    zip.reader_with_entry(1).await.unwrap().read_to_end_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, expected[1]);
+}
+
+#[tokio::test]
+async fn decompress_aes_info() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::encryption::{AesStrength, AesVendorVersion};
+
+    let zip = ZipFileReader::new(std::fs::read(AES_ENCRYPTED_ZIP_FILE).unwrap()).await.unwrap();
+    let expected = [(AesVendorVersion::Ae1, AesStrength::Aes128, 0), (AesVendorVersion::Ae2, AesStrength::Aes256, 8)];
+
+    for (entry, (version, strength, compression)) in zip.file().entries().iter().zip(expected) {
+        let info = entry.aes_info().unwrap();
+        assert_eq!(info.version(), version);
+        assert_eq!(info.strength(), strength);
+        assert_eq!(u16::from(info.compression()), compression);
+    }
+
+    let zip = ZipFileReader::new(std::fs::read(STORE_ZIP_FILE).unwrap()).await.unwrap();
+    assert!(zip.file().entries().iter().all(|entry| entry.aes_info().is_none()));
+}
+
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_zipcrypto_encrypted() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use async_zip::error::ZipError;
+
+    // Created by Info-ZIP, which always writes data descriptors for encrypted entries.
+    let data = std::fs::read(ZIPCRYPTO_ENCRYPTED_ZIP_FILE).unwrap();
+    let expected: [&[u8]; 3] = [
+        b"Streamed with a data descriptor.",
+        b"Encrypted with ZipCrypto and stored.",
+        &b"Encrypted with ZipCrypto and deflated. ".repeat(20),
+    ];
+
+    let zip = ZipFileReader::new(data).await.unwrap();
+    for (index, expected) in expected.iter().enumerate() {
+        let entry = &zip.file().entries()[index];
+        assert!(entry.is_encrypted() && entry.has_data_descriptor());
+
+        let mut buffer = Vec::new();
+        let mut reader = zip.reader_with_entry_and_password(index, b"password").await.unwrap();
+        reader.read_to_end_checked(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, expected);
+
+        let err = zip.reader_with_entry_and_password(index, b"incorrect").await.err().unwrap();
+        assert!(matches!(err.without_entry(), ZipError::WrongPassword));
+    }
+}
+
+/// Entries whose general purpose flag doesn't indicate an end-of-stream marker must be decoded up to their declared
+/// uncompressed sizes, rather than waiting for a marker.
+#[cfg(feature = "lzma")]
+#[tokio::test]
+async fn decompress_lzma_without_eos_flag() {
+    let content = tokio::fs::read(LZMA_WITHOUT_EOS_FLAG_ZIP_FILE).await.unwrap();
+    common::check_decompress_mem(content).await
+}
+
+/// Deflate streams which end without a block marked as final (as some producers write) must be decoded up to the end of
+/// their declared compressed sizes.
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_deflate_without_final_block() {
+    let content = tokio::fs::read(DEFLATE_WITHOUT_FINAL_BLOCK_ZIP_FILE).await.unwrap();
+    common::check_decompress_mem(content).await
+}
+
+/// A Deflate stream without a final block must still fail to read if it's truncated before the end of its declared
+/// compressed size, even when the CRC32 value isn't checked.
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_deflate_without_final_block_truncated() {
+    use async_zip::base::read::mem::ZipFileReader;
+    use futures_util::io::AsyncReadExt;
+
+    let content = tokio::fs::read(DEFLATE_WITHOUT_FINAL_BLOCK_ZIP_FILE).await.unwrap();
+    let file = ZipFileReader::new(content.clone()).await.unwrap().file().clone();
+    let data_offset = file.entries()[0].header_offset() + 30 + file.entries()[0].filename().as_bytes().len() as u64;
+    let truncated = content[..(data_offset + file.entries()[0].compressed_size() / 2) as usize].to_vec();
+
+    let reader = ZipFileReader::from_raw_parts(truncated, file);
+    let mut buffer = Vec::new();
+    let err = reader.reader_without_entry_unchecked(0).await.unwrap().read_to_end(&mut buffer).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+/// Entries written with a data descriptor whose sizes were left as zero within the central directory must only be
+/// decompressed until their data ends when opted into.
+#[cfg(feature = "deflate")]
+#[tokio::test]
+async fn decompress_unknown_sizes_seek() {
+    use async_zip::base::read::{seek::ZipFileReader, ReadOptions};
+    use async_zip::error::ZipError;
+
+    let mut file = tokio::fs::File::open(UNKNOWN_SIZES_ZIP_FILE).await.unwrap().compat();
+    let mut zip = ZipFileReader::new(&mut file).await.unwrap();
+    let mut buffer = Vec::new();
+    let result = zip.reader_with_entry(0).await.unwrap().read_to_end_checked(&mut buffer).await;
+    assert!(matches!(result.unwrap_err().without_entry(), ZipError::CRC32CheckError { .. }));
+    assert!(buffer.is_empty());
+
+    let mut file = tokio::fs::File::open(UNKNOWN_SIZES_ZIP_FILE).await.unwrap().compat();
+    let options = ReadOptions::new().read_unknown_sizes_to_end();
+    let mut zip = ZipFileReader::with_options(&mut file, options).await.unwrap();
+
+    for index in 0..zip.file().entries().len() {
+        let entry = zip.file().entries()[index].clone();
+        assert_eq!((entry.compressed_size(), entry.uncompressed_size()), (0, 0));
+
+        let mut output = String::new();
+        zip.reader_with_entry(index).await.unwrap().read_to_string_checked(&mut output).await.unwrap();
+        let expected = tokio::fs::read_to_string(format!("tests/test_inputs/{}", entry.filename().as_str().unwrap()));
+        assert_eq!(output, expected.await.unwrap());
+    }
+
+    assert!(zip.verify().await.unwrap().is_ok());
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_on_error_skips_corrupt_entry_fs() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use async_zip::tokio::extract::{ConflictPolicy, ErrorAction};
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    let mut data = Vec::new();
+    let mut writer = ZipFileWriter::new(futures_util::io::Cursor::new(&mut data));
+    for (name, content) in
+        [("first.txt", b"first entry"), ("second.txt", b"other entry"), ("third.txt", b"third entry")]
+    {
+        let builder = ZipEntryBuilder::new(name.into(), Compression::Stored);
+        writer.write_entry_whole(builder, content).await.unwrap();
+    }
+    writer.close().await.unwrap();
+
+    let position = data.windows(11).position(|window| window == b"other entry").unwrap();
+    data[position] = b'O';
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("corrupt.zip");
+    tokio::fs::write(&path, data).await.unwrap();
+
+    let out_dir = temp_dir.path().join("out");
+    tokio::fs::create_dir(&out_dir).await.unwrap();
+
+    // Retry the corrupt entry once before skipping it.
+    let mut failures = Vec::new();
+    let reader = ZipFileReader::new(&path).await.unwrap();
+    reader
+        .extractor()
+        .on_error(|index, err| {
+            assert!(matches!(err.without_entry(), ZipError::CRC32CheckError { .. }));
+            failures.push(index);
+            match failures.len() {
+                1 => ErrorAction::Retry,
+                _ => ErrorAction::Skip,
+            }
+        })
+        .extract_to(&out_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(failures, [1, 1]);
+    assert_eq!(std::fs::read(out_dir.join("first.txt")).unwrap(), b"first entry");
+    assert_eq!(std::fs::read(out_dir.join("third.txt")).unwrap(), b"third entry");
+    assert!(!out_dir.join("second.txt").exists());
+
+    // The partial file is removed before retrying, so a retry doesn't conflict with it.
+    let out_dir = temp_dir.path().join("renamed");
+    tokio::fs::create_dir(&out_dir).await.unwrap();
+
+    let mut failures = 0;
+    ZipFileReader::new(&path)
+        .await
+        .unwrap()
+        .extractor()
+        .on_conflict(ConflictPolicy::Rename)
+        .on_error(|_, _| {
+            failures += 1;
+            if failures == 1 {
+                ErrorAction::Retry
+            } else {
+                ErrorAction::Skip
+            }
+        })
+        .extract_to(&out_dir)
+        .await
+        .unwrap();
+
+    let mut names: Vec<_> = std::fs::read_dir(&out_dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    names.sort();
+    assert_eq!(names, ["first.txt", "third.txt"]);
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_to_strips_drive_prefixes_fs() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::{Compression, ZipEntryBuilder};
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for name in ["C:\\x", "C:/y", "dir/D:/z"] {
+        writer
+            .write_entry_whole(ZipEntryBuilder::new(name.into(), Compression::Stored), name.as_bytes())
+            .await
+            .unwrap();
+    }
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("drives.zip");
+    tokio::fs::write(&path, writer.close().await.unwrap()).await.unwrap();
+
+    let out_dir = temp_dir.path().join("out");
+    tokio::fs::create_dir(&out_dir).await.unwrap();
+
+    let reader = ZipFileReader::new(&path).await.unwrap();
+    reader.extractor().reject_unsafe_paths(false).extract_to(&out_dir).await.unwrap();
+
+    assert_eq!(std::fs::read(out_dir.join("x")).unwrap(), b"C:\\x");
+    assert_eq!(std::fs::read(out_dir.join("y")).unwrap(), b"C:/y");
+    assert_eq!(std::fs::read(out_dir.join("dir/z")).unwrap(), b"dir/D:/z");
+    assert!(!out_dir.join("C:").exists() && !out_dir.join("dir/D:").exists());
+}
+
+#[cfg(all(feature = "cap-std", unix))]
+#[tokio::test]
+async fn extract_to_dir_contains_traversal_fs() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::tokio::extract::ErrorAction;
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use cap_std::ambient_authority;
+    use cap_std::fs::Dir;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for name in ["../escape.txt", "nested/../../escape.txt", "/abs.txt", "link/escape.txt", "dir/safe.txt"] {
+        writer
+            .write_entry_whole(ZipEntryBuilder::new(name.into(), Compression::Stored), name.as_bytes())
+            .await
+            .unwrap();
+    }
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("traversal.zip");
+    tokio::fs::write(&path, writer.close().await.unwrap()).await.unwrap();
+
+    // A symbolic link already within the output directory which points outside of it, which sanitising names alone
+    // wouldn't catch.
+    let (out_dir, outside) = (temp_dir.path().join("out"), temp_dir.path().join("outside"));
+    tokio::fs::create_dir(&out_dir).await.unwrap();
+    tokio::fs::create_dir(&outside).await.unwrap();
+    tokio::fs::symlink(&outside, out_dir.join("link")).await.unwrap();
+
+    let mut failed = Vec::new();
+    let dir = Dir::open_ambient_dir(&out_dir, ambient_authority()).unwrap();
+    let reader = ZipFileReader::new(&path).await.unwrap();
+    reader
+        .extractor()
+        .reject_unsafe_paths(false)
+        .on_error(|index, _| {
+            failed.push(index);
+            ErrorAction::Skip
+        })
+        .extract_to_dir(&dir)
+        .await
+        .unwrap();
+
+    assert_eq!(failed, [3]);
+    assert_eq!(std::fs::read(out_dir.join("escape.txt")).unwrap(), b"../escape.txt");
+    assert_eq!(std::fs::read(out_dir.join("nested/escape.txt")).unwrap(), b"nested/../../escape.txt");
+    assert_eq!(std::fs::read(out_dir.join("abs.txt")).unwrap(), b"/abs.txt");
+    assert_eq!(std::fs::read(out_dir.join("dir/safe.txt")).unwrap(), b"dir/safe.txt");
+    assert!(!temp_dir.path().join("escape.txt").exists());
+    assert_eq!(std::fs::read_dir(&outside).unwrap().count(), 0);
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_to_rejects_unsafe_entries_fs() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::error::ZipError;
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::{Compression, UnsafeReason, ZipEntryBuilder};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    let builder = ZipEntryBuilder::new("dir/safe.txt".into(), Compression::Stored)
+        .unix_permissions(0o100600)
+        .last_modification_unix_time(1_000_000_000);
+    writer.write_entry_whole(builder, b"safe").await.unwrap();
+    writer.write_entry_whole(ZipEntryBuilder::new("../evil.txt".into(), Compression::Stored), b"evil").await.unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("evil.zip");
+    tokio::fs::write(&path, writer.close().await.unwrap()).await.unwrap();
+
+    let out_dir = temp_dir.path().join("out");
+    tokio::fs::create_dir(&out_dir).await.unwrap();
+
+    let reader = ZipFileReader::new(&path).await.unwrap();
+    let err = reader.extract_to(&out_dir).await.unwrap_err();
+    assert!(matches!(err.without_entry(), ZipError::UnsafeEntryPath(UnsafeReason::ParentComponent)));
+    assert!(!temp_dir.path().join("evil.txt").exists());
+    assert!(!out_dir.join("evil.txt").exists());
+
+    // The extractor rejects such names by default too, only sanitising them once explicitly allowed.
+    let err = reader.extractor().extract_to(&out_dir).await.unwrap_err();
+    assert!(matches!(err.without_entry(), ZipError::UnsafeEntryPath(UnsafeReason::ParentComponent)));
+    reader.extractor().reject_unsafe_paths(false).extract_to(&out_dir).await.unwrap();
+    assert!(!temp_dir.path().join("evil.txt").exists());
+    assert_eq!(std::fs::read(out_dir.join("evil.txt")).unwrap(), b"evil");
+
+    // The entry prior to the rejected one was extracted with its metadata restored.
+    let metadata = std::fs::metadata(out_dir.join("dir/safe.txt")).unwrap();
+    assert_eq!(metadata.modified().unwrap(), UNIX_EPOCH + Duration::from_secs(1_000_000_000));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+
+    // Symbolic links are refused unless explicitly allowed.
+    let reader = ZipFileReader::new(UNSAFE_ENTRIES_ZIP_FILE).await.unwrap();
+    let link = reader.file().entries().iter().position(|entry| entry.filename().as_bytes() == b"link").unwrap();
+
+    let err = reader.extractor().only([link]).extract_to(&out_dir).await.unwrap_err();
+    assert!(matches!(err.without_entry(), ZipError::UnsafeEntryPath(UnsafeReason::Symlink)));
+    assert!(std::fs::symlink_metadata(out_dir.join("link")).is_err());
+
+    #[cfg(unix)]
+    {
+        reader.extractor().only([link]).allow_symlinks(true).extract_to(&out_dir).await.unwrap();
+        assert_eq!(std::fs::read_link(out_dir.join("link")).unwrap(), std::path::Path::new("../../outside"));
+    }
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn extract_on_conflict_fs() {
+    use async_zip::base::write::ZipFileWriter;
+    use async_zip::tokio::extract::ConflictPolicy;
+    use async_zip::tokio::read::fs::ZipFileReader;
+    use async_zip::{Compression, ZipEntryBuilder};
+    use std::io::ErrorKind;
+
+    let mut data = Vec::new();
+    let mut writer = ZipFileWriter::new(futures_util::io::Cursor::new(&mut data));
+    for (name, content) in [("file.txt", b"archived"), ("README", b"archived")] {
+        let builder = ZipEntryBuilder::new(name.into(), Compression::Stored);
+        writer.write_entry_whole(builder, content).await.unwrap();
+    }
+    writer.close().await.unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("conflict.zip");
+    tokio::fs::write(&path, data).await.unwrap();
+
+    let out_dir = temp_dir.path().join("out");
+    tokio::fs::create_dir(&out_dir).await.unwrap();
+    for name in ["file.txt", "file (1).txt", "README"] {
+        tokio::fs::write(out_dir.join(name), b"existing").await.unwrap();
+    }
+
+    let reader = ZipFileReader::new(&path).await.unwrap();
+    let mut written = Vec::new();
+    reader
+        .extractor()
+        .on_conflict(ConflictPolicy::Rename)
+        .on_entry_written(|path| written.push(path.file_name().unwrap().to_owned()))
+        .extract_to(&out_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(written, ["file (2).txt", "README (1)"]);
+    for name in ["file.txt", "file (1).txt", "README"] {
+        assert_eq!(std::fs::read(out_dir.join(name)).unwrap(), b"existing");
+    }
+    assert_eq!(std::fs::read(out_dir.join("file (2).txt")).unwrap(), b"archived");
+    assert_eq!(std::fs::read(out_dir.join("README (1)")).unwrap(), b"archived");
+
+    reader.extractor().on_conflict(ConflictPolicy::Skip).extract_to(&out_dir).await.unwrap();
+    assert_eq!(std::fs::read(out_dir.join("file.txt")).unwrap(), b"existing");
+
+    let err = reader.extractor().on_conflict(ConflictPolicy::Error).extract_to(&out_dir).await.unwrap_err();
+    let err = err.without_entry();
+    assert!(
+        matches!(err, async_zip::error::ZipError::UpstreamReadError(err) if err.kind() == ErrorKind::AlreadyExists)
+    );
+
+    reader.extractor().extract_to(&out_dir).await.unwrap();
+    assert_eq!(std::fs::read(out_dir.join("file.txt")).unwrap(), b"archived");
+}
+
+#[tokio::test]
+async fn entry_names_seek() {
+    use async_zip::base::read::seek::ZipFileReader;
+    use std::borrow::Cow;
+
+    let data = tokio::fs::read(DIRECTORY_ATTRIBUTES_ZIP_FILE).await.unwrap();
+    let reader = ZipFileReader::new(futures_util::io::Cursor::new(data)).await.unwrap();
+    let names: Vec<_> = reader.entry_names().collect();
+    assert_eq!(names, ["unix_dir", "dos_dir", "slash_dir/", "slash_file/", "file.txt"]);
+    assert!(names.iter().all(|name| matches!(name, Cow::Borrowed(_))));
+
+    let data = tokio::fs::read(RAW_NAME_BYTES_ZIP_FILE).await.unwrap();
+    let reader = async_zip::base::read::mem::ZipFileReader::new(data).await.unwrap();
+    let names: Vec<_> = reader.entry_names().collect();
+    assert_eq!(names, ["\u{c4}\u{a6}\u{1}dir\\name.txt"]);
+}
+
+#[tokio::test]
+async fn max_entries_to_parse_mem() {
+    use async_zip::base::read::{mem::ZipFileReader, ReadOptions};
+    use async_zip::base::write::ZipFileWriter;
+
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for index in 0..500 {
+        writer.write_entry(format!("{index}.txt"), index.to_string().as_bytes()).await.unwrap();
+    }
+    let data = writer.close().await.unwrap();
+
+    let reader = ZipFileReader::with_options(data.clone(), ReadOptions::new().max_entries_to_parse(10)).await.unwrap();
+    assert_eq!(reader.file().entries().len(), 10);
+    assert!(reader.file().is_partial());
+    assert_eq!(reader.file().entry_count_mismatch(), None);
+    assert_eq!(reader.file().entries()[9].filename().as_str().unwrap(), "9.txt");
+
+    let mut buffer = String::new();
+    reader.reader_with_entry(9).await.unwrap().read_to_string_checked(&mut buffer).await.unwrap();
+    assert_eq!(buffer, "9");
+
+    let reader = ZipFileReader::with_options(data.clone(), ReadOptions::new().max_entries_to_parse(500)).await.unwrap();
+    assert_eq!(reader.file().entries().len(), 500);
+    assert!(!reader.file().is_partial());
+
+    let reader = ZipFileReader::new(data).await.unwrap();
+    assert!(!reader.file().is_partial());
+}